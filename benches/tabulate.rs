@@ -0,0 +1,80 @@
+//! Benchmarks for cell selection, area tabulation and GDS enclosure extraction.
+//!
+//! Run with `cargo bench`; results are written to `target/criterion`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use gds21::{GdsBoundary, GdsElement, GdsPoint};
+
+use memea::config::Config;
+use memea::db::synthetic_db;
+use memea::gds::compute_enc;
+use memea::tabulate::{tabulate, Options, SelectionCache};
+
+/// Cells of each type in the synthetic database.
+const CELLS_PER_TYPE: usize = 500;
+
+/// Boundary polygons in the synthetic GDS cell.
+const POLYGONS: usize = 10_000;
+
+/// A mid-sized array exercising every peripheral section.
+const CONFIG: &str = "
+n: 256
+m: 128
+cell: core0
+wl: [4, 2.5, 0, 1]
+bl: [1, 2, 0]
+well: [0, 2]
+bits: 8
+fs: 1e8
+adcs: 32
+";
+
+fn bench_tabulate(c: &mut Criterion) {
+    let db = synthetic_db(CELLS_PER_TYPE);
+    let config: Config = serde_yaml::from_str(CONFIG).expect("valid benchmark config");
+    let opts = Options::default();
+
+    c.bench_function("tabulate", |b| {
+        b.iter(|| tabulate("bench", black_box(&config), black_box(&db), &opts, None))
+    });
+
+    let cache = SelectionCache::new();
+    c.bench_function("tabulate_cached", |b| {
+        b.iter(|| {
+            tabulate(
+                "bench",
+                black_box(&config),
+                black_box(&db),
+                &opts,
+                Some(&cache),
+            )
+        })
+    });
+}
+
+fn bench_compute_enc(c: &mut Criterion) {
+    // Grid of 1 x 1 μm squares (1 nm database units) spread over many layers
+    let elems: Vec<GdsElement> = (0..POLYGONS as i32)
+        .map(|i| {
+            let (x, y) = ((i % 100) * 1500, (i / 100) * 1500);
+            GdsElement::GdsBoundary(GdsBoundary {
+                layer: (i % 32) as i16,
+                xy: GdsPoint::vec(&[
+                    (x, y),
+                    (x + 1000, y),
+                    (x + 1000, y + 1000),
+                    (x, y + 1000),
+                    (x, y),
+                ]),
+                ..Default::default()
+            })
+        })
+        .collect();
+
+    c.bench_function("compute_enc", |b| {
+        b.iter(|| compute_enc(black_box(&elems), 140.0, 140.0, 1e-9, None, false))
+    });
+}
+
+criterion_group!(benches, bench_tabulate, bench_compute_enc);
+criterion_main!(benches);
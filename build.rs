@@ -0,0 +1,50 @@
+//! Build script that records build information for `--version --verbose`.
+//!
+//! Git details are emitted by `vergen`, the rustc version is queried from the
+//! compiler cargo builds with, and the resolved versions of key dependencies
+//! are read from `Cargo.lock`.
+
+use std::{env, fs, process::Command};
+use vergen_gitcl::{Emitter, GitclBuilder};
+
+/// Dependencies whose resolved versions are reported, with their env var names.
+const DEPS: [(&str, &str); 2] = [("gds21", "MEMEA_DEP_GDS21"), ("serde", "MEMEA_DEP_SERDE")];
+
+/// Finds the resolved version of a package in the contents of `Cargo.lock`.
+fn locked_version(lock: &str, name: &str) -> Option<String> {
+    let header = format!("name = \"{name}\"");
+    let mut lines = lock.lines();
+
+    while let Some(line) = lines.next() {
+        if line.trim() == header {
+            let version = lines.next()?.trim().strip_prefix("version = ")?;
+            return Some(version.trim_matches('"').to_string());
+        }
+    }
+
+    None
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let git = GitclBuilder::default().sha(true).dirty(true).build()?;
+    Emitter::default().add_instructions(&git)?.emit()?;
+
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".into());
+    let version = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .and_then(|out| String::from_utf8(out.stdout).ok())
+        .and_then(|v| v.split_whitespace().nth(1).map(str::to_string))
+        .unwrap_or_else(|| "unknown".into());
+    println!("cargo:rustc-env=MEMEA_RUSTC_VERSION={version}");
+
+    println!("cargo:rerun-if-changed=Cargo.lock");
+    let lock = fs::read_to_string("Cargo.lock").unwrap_or_default();
+    for (name, var) in DEPS {
+        let version = locked_version(&lock, name).unwrap_or_else(|| "unknown".into());
+        println!("cargo:rustc-env={var}={version}");
+    }
+
+    Ok(())
+}
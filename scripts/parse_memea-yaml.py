@@ -1,4 +1,13 @@
 #!/usr/bin/env python
+"""Summarize a MemEA YAML export into a CSV of per-configuration areas.
+
+A YAML export maps each configuration name to its list of reports and ends
+with the reserved `meta` key, which holds the grand total and, keyed by
+configuration, the floorplans, subtotals, effective areas, capacities and
+warnings. Only the configurations are read here; `meta` is skipped.
+
+Usage: parse_memea-yaml.py EXPORT.yaml  (writes EXPORT_parsed.csv)
+"""
 
 import yaml
 import sys
@@ -12,6 +21,8 @@ from pprint import pprint
 
 DATABASE = sys.argv[1]
 OUTPUT = f"{os.path.splitext(DATABASE)[0]}_parsed.csv"
+# Reserved key of the export metadata (export::META_KEY)
+META_KEY = 'meta'
 PATTERN = r'([0-9]+) x ([0-9]+) ([0-9]+)-level ([\w\-\_]+) \(Vw = ([0-9\.]+)\)'
 
 with open(DATABASE, 'r') as f:
@@ -24,6 +35,9 @@ df = pd.DataFrame(
 regex = re.compile(PATTERN)
 
 for config, cells in data.items():
+    if config == META_KEY:
+        continue
+
     matches = regex.search(config)
     n, m, states, device, vw = matches.group(1, 2, 3, 4, 5)
 
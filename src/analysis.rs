@@ -0,0 +1,426 @@
+//! Analysis views over MemEA estimation results.
+//!
+//! This module provides summaries that operate on already tabulated results,
+//! such as distributions of total area across many configurations. None of
+//! these functions affect cell selection or area computation.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+use crate::db::{CellType, Database};
+use crate::tabulate::Report;
+use crate::{scale, Float};
+
+/// Maximum width of a histogram bar in characters.
+const BAR_WIDTH: usize = 40;
+
+/// Totals area by physical cell name across locations and configurations.
+///
+/// # Arguments
+/// * `reports` - Reports to aggregate, from any number of configurations
+///
+/// # Returns
+/// Vector of (cell name, total area in μm²), largest first; ties are ordered
+/// by name
+///
+/// # Examples
+/// ```
+/// use memea::analysis::area_by_cell_name;
+/// use memea::db::CellType;
+/// use memea::tabulate::Report;
+///
+/// let report = |name: &str, loc: &str, area| Report {
+///     name: name.to_string(),
+///     count: 1,
+///     celltype: CellType::Switch,
+///     loc: loc.to_string(),
+///     area,
+///     copies: 1,
+///     dx_required: None,
+///     dx_selected: None,
+///     leakage: None,
+///     extent: [0.0, 0.0],
+/// };
+///
+/// let reports = [report("sw", "WL", 2.0), report("adc", "BL", 5.0), report("sw", "BL", 4.0)];
+/// assert_eq!(
+///     area_by_cell_name(&reports),
+///     vec![("sw".to_string(), 6.0), ("adc".to_string(), 5.0)]
+/// );
+/// ```
+pub fn area_by_cell_name<'a>(
+    reports: impl IntoIterator<Item = &'a Report>,
+) -> Vec<(String, Float)> {
+    let mut totals: HashMap<&str, Float> = HashMap::new();
+    for r in reports {
+        *totals.entry(&r.name).or_default() += r.area;
+    }
+
+    let mut totals: Vec<(String, Float)> = totals
+        .into_iter()
+        .map(|(name, area)| (name.to_string(), area))
+        .collect();
+    totals.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    totals
+}
+
+/// Square micrometers per square centimeter.
+const UM2_PER_CM2: f64 = 1e8;
+
+/// Inflates an area by the silicon lost to defects.
+///
+/// Uses the Poisson yield model `Y = exp(-A * D0)`, where `A` is the area in
+/// cm² and `D0` the density of fatal defects per cm². The model assumes
+/// defects are randomly (not clustered) distributed and that any defect in
+/// the area is fatal; it is pessimistic for large areas compared to the
+/// negative-binomial model. The effective area `A / Y` is the silicon spent
+/// per working unit.
+///
+/// # Arguments
+/// * `area` - Raw area in μm²
+/// * `density` - Fatal defect density in defects/cm²
+///
+/// # Returns
+/// Yield-adjusted effective area in μm² (equal to `area` for zero density)
+///
+/// # Examples
+/// ```
+/// use memea::analysis::yield_adjust;
+///
+/// let close = |a: f32, b: f32| (a - b).abs() <= 1e-6 * b;
+///
+/// // 1 cm² at 1 defect/cm²: Y = 1/e
+/// assert!(close(yield_adjust(1e8, 1.0), 2.718_281_8e8));
+/// // 0.5 cm² at 0.2 defects/cm²: Y = exp(-0.1) = 0.904837
+/// assert!(close(yield_adjust(5e7, 0.2), 5.525_855e7));
+/// // No defects, no penalty
+/// assert_eq!(yield_adjust(1234.5, 0.0), 1234.5);
+/// ```
+pub fn yield_adjust(area: Float, density: Float) -> Float {
+    let cm2 = area as f64 / UM2_PER_CM2;
+    let yield_ = (-cm2 * density as f64).exp();
+    (area as f64 / yield_) as Float
+}
+
+/// Formats per-cell area totals as a table with each cell's share.
+///
+/// # Arguments
+/// * `totals` - (cell name, area) pairs, e.g. from [`area_by_cell_name`]
+///
+/// # Returns
+/// Table with one line per cell
+pub fn fmt_by_cell(totals: &[(String, Float)]) -> String {
+    let mut output = String::new();
+    let total: Float = totals.iter().map(|(_, a)| a).sum();
+    let name_w = totals
+        .iter()
+        .map(|(n, _)| n.len())
+        .max()
+        .unwrap_or(0)
+        .max(4);
+
+    writeln!(output, "{:<name_w$} | Area (μm²) | Share", "Cell").ok();
+    for (name, area) in totals {
+        let share = match total > 0.0 {
+            true => 100.0 * area / total,
+            false => 0.0,
+        };
+        writeln!(output, "{name:<name_w$} | {area:>10.1} | {share:>4.1}%").ok();
+    }
+
+    output
+}
+
+/// Computes the median of a sorted, non-empty slice.
+fn median(sorted: &[Float]) -> Float {
+    let mid = sorted.len() / 2;
+    match sorted.len() % 2 {
+        0 => (sorted[mid - 1] + sorted[mid]) / 2.0,
+        _ => sorted[mid],
+    }
+}
+
+/// Renders an ASCII histogram of total areas.
+///
+/// The range between the smallest and largest total is split into `bins`
+/// equal-width bins, and each bin is drawn as a bar proportional to the
+/// number of totals that fall into it. The histogram is followed by a line
+/// of min/max/median/mean annotations.
+///
+/// # Arguments
+/// * `totals` - Total area of each configuration in square micrometers
+/// * `bins` - Number of bins to split the area range into
+///
+/// # Returns
+/// Formatted histogram, or an empty string if there are no totals or bins
+///
+/// # Examples
+/// ```
+/// use memea::analysis::histogram;
+///
+/// let hist = histogram(&[10.0, 20.0, 20.0, 40.0], 3);
+/// let lines: Vec<&str> = hist.lines().collect();
+///
+/// assert_eq!(lines.len(), 4);
+/// assert!(lines[0].ends_with(" 1"));
+/// assert!(lines[1].ends_with(" 2"));
+/// assert!(lines[2].ends_with(" 1"));
+/// assert_eq!(lines[3], "Min: 10.0  Max: 40.0  Median: 20.0  Mean: 22.5 (μm²)");
+/// ```
+pub fn histogram(totals: &[Float], bins: usize) -> String {
+    let mut output = String::new();
+
+    if totals.is_empty() || bins == 0 {
+        return output;
+    }
+
+    let mut sorted = totals.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+
+    let min = sorted[0];
+    let max = sorted[sorted.len() - 1];
+    let mean = sorted.iter().sum::<Float>() / sorted.len() as Float;
+    let width = (max - min) / bins as Float;
+
+    let mut counts = vec![0usize; bins];
+    for t in &sorted {
+        let idx = match width > 0.0 {
+            true => (((t - min) / width) as usize).min(bins - 1),
+            false => 0,
+        };
+        counts[idx] += 1;
+    }
+
+    let peak = counts.iter().copied().max().unwrap_or(0).max(1);
+
+    for (i, count) in counts.iter().enumerate() {
+        let lo = min + width * i as Float;
+        let hi = lo + width;
+        let len = count * BAR_WIDTH / peak;
+
+        writeln!(
+            output,
+            "{:>12.1} - {:<12.1} | {:<w$} {}",
+            lo,
+            hi,
+            "#".repeat(len),
+            count,
+            w = BAR_WIDTH
+        )
+        .ok();
+    }
+
+    write!(
+        output,
+        "Min: {:.1}  Max: {:.1}  Median: {:.1}  Mean: {:.1} (μm²)",
+        min,
+        max,
+        median(&sorted),
+        mean
+    )
+    .ok();
+
+    output
+}
+
+/// Lists the sorted names of cells not present in the used set.
+fn unused_of<T>(
+    cells: &HashMap<String, T>,
+    celltype: CellType,
+    used: &HashSet<(CellType, String)>,
+) -> Vec<String> {
+    let mut names: Vec<String> = cells
+        .keys()
+        .filter(|name| !used.contains(&(celltype, name.to_string())))
+        .cloned()
+        .collect();
+    names.sort();
+    names
+}
+
+/// Finds database cells that were never selected by any configuration.
+///
+/// # Arguments
+/// * `db` - Database the configurations were tabulated against
+/// * `used` - Set of (cell type, name) pairs taken from the produced reports
+///
+/// # Returns
+/// Vector of (cell type, sorted unused cell names), omitting types with no
+/// unused cells
+///
+/// # Examples
+/// ```
+/// use std::collections::HashSet;
+/// use memea::analysis::unused_cells;
+/// use memea::db::{CellType, Database, Dims, Switch};
+///
+/// let mut db = Database::new();
+/// let sw = Switch { dx: 4.0, voltage: [0.0, 1.8], dims: Dims::new(), leakage: None, note: None, source: None };
+/// db.switch.insert("used".to_string(), sw.clone());
+/// db.switch.insert("spare".to_string(), sw);
+///
+/// let used = HashSet::from([(CellType::Switch, "used".to_string())]);
+/// assert_eq!(
+///     unused_cells(&db, &used),
+///     vec![(CellType::Switch, vec!["spare".to_string()])]
+/// );
+/// ```
+pub fn unused_cells(
+    db: &Database,
+    used: &HashSet<(CellType, String)>,
+) -> Vec<(CellType, Vec<String>)> {
+    let unused = [
+        (CellType::Core, unused_of(&db.core, CellType::Core, used)),
+        (CellType::Logic, unused_of(&db.logic, CellType::Logic, used)),
+        (
+            CellType::Switch,
+            unused_of(&db.switch, CellType::Switch, used),
+        ),
+        (CellType::ADC, unused_of(&db.adc, CellType::ADC, used)),
+        (
+            CellType::SenseAmp,
+            unused_of(&db.sense, CellType::SenseAmp, used),
+        ),
+    ];
+
+    unused
+        .into_iter()
+        .filter(|(_, names)| !names.is_empty())
+        .collect()
+}
+
+/// Tabulates total areas scaled to several technology nodes.
+///
+/// Each row is a configuration (sorted by name) and each column a target
+/// node, with areas scaled from `from` using [`crate::scale`].
+///
+/// # Arguments
+/// * `totals` - (configuration name, total area at the source node) pairs
+/// * `from` - Source technology node in nanometers
+/// * `to` - Target technology nodes in nanometers
+///
+/// # Returns
+/// Formatted table of scaled areas in μm²
+///
+/// # Examples
+/// ```
+/// use memea::analysis::sweep_table;
+///
+/// let table = sweep_table(&[("cfg".to_string(), 100.0)], 65, &[65, 28]);
+/// let lines: Vec<&str> = table.lines().collect();
+///
+/// assert!(lines[0].contains("65 nm") && lines[0].contains("28 nm"));
+/// assert!(lines[2].starts_with("cfg"));
+/// assert!(lines[2].contains("100.0"));
+/// ```
+pub fn sweep_table(totals: &[(String, Float)], from: usize, to: &[usize]) -> String {
+    let mut output = String::new();
+
+    let mut rows: Vec<&(String, Float)> = totals.iter().collect();
+    rows.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let factors: Vec<Float> = to.iter().map(|t| scale(from, *t)).collect();
+    let name_w = rows
+        .iter()
+        .map(|(n, _)| n.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(13);
+
+    write!(output, "{:<name_w$}", "Configuration").ok();
+    for t in to {
+        write!(output, " | {:>12}", format!("{t} nm")).ok();
+    }
+    writeln!(output).ok();
+    writeln!(output, "{}", "-".repeat(name_w + 15 * to.len())).ok();
+
+    for (name, area) in rows {
+        write!(output, "{name:<name_w$}").ok();
+        for f in &factors {
+            write!(output, " | {:>12.1}", area * f).ok();
+        }
+        writeln!(output).ok();
+    }
+
+    output
+}
+
+/// Labels of the drive headroom buckets, by selected/required drive ratio.
+const HEADROOM_BUCKETS: [&str; 4] = ["<1.0x", "1.0-1.2x", "1.2-1.5x", ">1.5x"];
+
+/// Peripheral area grouped by how much drive strength headroom it carries.
+///
+/// Each switch and logic report is bucketed by the ratio of the selected
+/// cell's drive strength to the required drive strength. The `<1.0x` bucket
+/// only holds cells pinned with `--force`. Reports without drive information
+/// (core cells and ADCs) are ignored.
+///
+/// # Examples
+/// ```
+/// use memea::analysis::Headroom;
+/// use memea::db::CellType;
+/// use memea::tabulate::Report;
+///
+/// let report = |area, dx_selected| Report {
+///     name: "sw".to_string(),
+///     count: 1,
+///     celltype: CellType::Switch,
+///     loc: "WL".to_string(),
+///     area,
+///     copies: 1,
+///     dx_required: Some(10.0),
+///     dx_selected: Some(dx_selected),
+///     leakage: None,
+///     extent: [0.0, 0.0],
+/// };
+///
+/// let mut headroom = Headroom::default();
+/// headroom.add(&[report(5.0, 11.0), report(20.0, 40.0), report(1.0, 10.0)]);
+/// assert_eq!(headroom.area, [0.0, 6.0, 0.0, 20.0]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Headroom {
+    /// Area in μm² per bucket, in the order `<1.0x`, `1.0-1.2x`, `1.2-1.5x`, `>1.5x`
+    pub area: [Float; 4],
+}
+
+impl Headroom {
+    /// Adds the drive-tracked reports of one configuration to the buckets.
+    ///
+    /// # Arguments
+    /// * `reports` - Reports produced by [`crate::tabulate::tabulate`]
+    pub fn add(&mut self, reports: &[Report]) {
+        for r in reports {
+            let (Some(required), Some(selected)) = (r.dx_required, r.dx_selected) else {
+                continue;
+            };
+
+            let ratio = selected / required;
+            let idx = match ratio {
+                x if x < 1.0 => 0,
+                x if x <= 1.2 => 1,
+                x if x <= 1.5 => 2,
+                _ => 3,
+            };
+            self.area[idx] += r.area;
+        }
+    }
+
+    /// Formats the buckets as a table with the share of peripheral area in each.
+    pub fn fmt(&self) -> String {
+        let mut output = String::new();
+        let total: Float = self.area.iter().sum();
+
+        writeln!(output, "Drive headroom | Area (μm²) | Share").ok();
+        for (label, area) in HEADROOM_BUCKETS.iter().zip(self.area) {
+            let share = match total > 0.0 {
+                true => 100.0 * area / total,
+                false => 0.0,
+            };
+            writeln!(output, "{label:<14} | {area:>10.1} | {share:>4.1}%").ok();
+        }
+
+        output
+    }
+}
@@ -4,13 +4,16 @@
 //! from YAML files. Each configuration specifies memory array parameters,
 //! cell types, voltages, and ADC settings used for peripheral estimation.
 
-use serde::Deserialize;
+use regex::Regex;
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_yaml::{Mapping, Value};
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::io::BufReader;
-use std::{collections::HashMap, path::PathBuf};
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
-use crate::{errorln, Float, MemeaError};
+use crate::db::{normalize_name, Database};
+use crate::{errorln, read_text, warnln, Float, MemeaError};
 
 /// A collection of memory configurations indexed by name.
 type Configs = HashMap<String, Config>;
@@ -21,6 +24,141 @@ pub enum ConfigError {
     /// Indicates that a required configuration option was not found.
     #[error("Cannot find option in config: {0}")]
     MissingOption(String),
+    /// Indicates that a configuration option has an unusable value.
+    #[error("Invalid option in config: {0}")]
+    InvalidOption(String),
+    /// Indicates that configuration files include or extend each other in a loop.
+    #[error("Include cycle: {0}")]
+    IncludeCycle(String),
+    /// Indicates an unsupported file format was encountered.
+    #[error("Unsupported file extension: {0}")]
+    FileType(String),
+}
+
+/// Commented starter configuration listing every supported key.
+///
+/// Required keys are set to example values; optional keys are commented out
+/// and show their default (or an example where there is none).
+///
+/// # Examples
+/// ```
+/// use memea::config::{Config, TEMPLATE};
+///
+/// // The template parses as-is
+/// let config: Config = serde_yaml::from_str(TEMPLATE).unwrap();
+/// assert_eq!(config.cell, "1FeFET_100");
+///
+/// // With every optional key enabled, every field is set. Listing the fields
+/// // without `..` stops compiling when a field is added to `Config`, as a
+/// // reminder to add it to the template too.
+/// let all: String = TEMPLATE
+///     .lines()
+///     .filter(|l| !l.starts_with("# include") && !l.starts_with("# extends"))
+///     .map(|l| l.strip_prefix("# ").filter(|l| l.contains(": ")).unwrap_or(l))
+///     .collect::<Vec<_>>()
+///     .join("\n");
+/// let Config {
+///     name, n: _, m: _, cell: _, transpose, bl, wl, well, rails, adcs,
+///     adc_per_cols, bits, fs, sense_amps, vdd, temp, copies, banks, bank_split, bits_per_cell,
+///     defect_density, wl_switch, wl_logic, bl_switch, bl_logic, well_switch, well_logic, adc,
+///     sense_amp, strict, options,
+/// } = serde_yaml::from_str(&all).unwrap();
+/// assert!(name.is_some() && transpose.is_some() && rails.is_some() && options.is_some());
+/// assert!(bl.is_some() && wl.is_some() && well.is_some());
+/// assert!(adcs.is_some() && adc_per_cols.is_some() && bits.is_some() && fs.is_some());
+/// assert!(vdd.is_some() && temp.is_some() && copies.is_some() && strict.is_some());
+/// assert!(banks.is_some() && bank_split.is_some());
+/// assert!(bits_per_cell.is_some() && defect_density.is_some());
+/// assert!(wl_switch.is_some() && wl_logic.is_some() && bl_switch.is_some());
+/// assert!(bl_logic.is_some() && well_switch.is_some() && well_logic.is_some());
+/// assert!(adc.is_some() && sense_amps.is_some() && sense_amp.is_some());
+/// ```
+pub const TEMPLATE: &str = "\
+# MemEA configuration
+#
+# Required keys are set below; uncomment optional keys to use them.
+
+# Name of the configuration (default: the file path)
+# name: FeRAM_128-64
+
+# Base configuration to inherit every key from, relative to this file;
+# keys set here (or in includes) replace the base's value
+# extends: base.yaml
+
+# Shared fragments merged into this file, relative to it; keys set here win
+# include: [adc.yaml]
+
+# Array size: rows and columns
+n: 128
+m: 64
+
+# Memory cell, by name in the database
+cell: 1FeFET_100
+# Rotate the memory cell by 90 degrees (default: false)
+# transpose: false
+
+# Named supply rails that voltage lists may refer to by name
+# rails: {vdd: 0.8, vpp: 4}
+# Wordline, bitline and well voltages in V; omit a key to skip that peripheral
+# wl: [4, 2.5, 0, 1]
+# bl: [1, 2, 0]
+# well: [0, 4]
+
+# ADC resolution, sampling rate in Hz, and either a count or columns per ADC
+# bits: 1
+# fs: 1e9
+# adcs: 64
+# adc_per_cols: 1
+
+# Number of bitline sense amplifiers, which must also reach the fs above
+# sense_amps: 64
+
+# Operating point used to derate ADC sampling rates
+# vdd: 0.8
+# temp: 25
+
+# Number of identical, fully replicated arrays (default: 1)
+# copies: 1
+
+# Split the array into banks with their own wordline, bitline and well
+# peripherals, dividing either the rows or the columns (default: 1, cols)
+# banks: 2
+# bank_split: cols
+
+# Bits stored per memory cell, e.g. 2 for multi-level cells (default: 1)
+# bits_per_cell: 1
+
+# Fatal defect density in defects/cm2, to also report a yield-adjusted area
+# defect_density: 0.1
+
+# Pin peripherals to database cells by name instead of selecting them
+# wl_switch: TXGD16
+# wl_logic: DC2D1
+# bl_switch: TXGD16
+# bl_logic: DC2D1
+# well_switch: TXHV
+# well_logic: DC2D1
+# adc: DFFLA1
+# sense_amp: SA1
+
+# Treat missing wl, bl, well or ADC keys as errors (default: false)
+# strict: false
+
+# Additional free-form options
+# options: {owner: memory-team}
+";
+
+/// Dimension of the array that banks divide.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BankSplit {
+    /// Banks are stacked vertically with `n / banks` rows each, so every bank
+    /// repeats the bitline and well peripherals.
+    Rows,
+    /// Banks sit side by side with `m / banks` columns each, so every bank
+    /// repeats the wordline peripherals.
+    #[default]
+    Cols,
 }
 
 /// Represents one memory configuration for peripheral estimation.
@@ -54,6 +192,8 @@ pub struct Config {
     pub m: usize,
     /// Memory cell type to use for estimation.
     pub cell: String,
+    /// Rotate the memory cell by 90°, swapping its width and height.
+    pub transpose: Option<bool>,
 
     /// Bitline voltages
     pub bl: Option<Vec<Float>>,
@@ -61,44 +201,836 @@ pub struct Config {
     pub wl: Option<Vec<Float>>,
     /// Voltages required for well biasing
     pub well: Option<Vec<Float>>,
+    /// Named supply rails that `bl`, `wl` and `well` may refer to by name.
+    pub rails: Option<HashMap<String, Float>>,
 
     /// Number of downstream analog-to-digital converters.
     pub adcs: Option<usize>,
+    /// Number of columns sharing one ADC, as an alternative to `adcs`.
+    pub adc_per_cols: Option<usize>,
     /// Number of bits required for ADCs.
     pub bits: Option<usize>,
     /// Sampling rate of the ADCs in Hz.
     pub fs: Option<Float>,
 
+    /// Number of bitline sense amplifiers (per bank).
+    pub sense_amps: Option<usize>,
+
+    /// Operating supply voltage in volts, used to derate ADC sampling rates.
+    pub vdd: Option<Float>,
+    /// Operating temperature in °C, used to derate ADC sampling rates.
+    pub temp: Option<Float>,
+
+    /// Number of identical, fully replicated arrays (including peripherals).
+    /// Defaults to 1.
+    pub copies: Option<usize>,
+
+    /// Number of banks the array is split into, each with its own wordline,
+    /// bitline and well peripherals. Defaults to 1.
+    pub banks: Option<usize>,
+    /// Which dimension `banks` divides. Defaults to [`BankSplit::Cols`].
+    pub bank_split: Option<BankSplit>,
+
+    /// Number of bits stored per memory cell, above 1 for multi-level cells.
+    /// Defaults to 1.
+    pub bits_per_cell: Option<usize>,
+
+    /// Density of fatal defects in defects/cm², used to report a
+    /// yield-adjusted effective area.
+    pub defect_density: Option<Float>,
+
+    /// Wordline switch to use instead of automatic selection.
+    pub wl_switch: Option<String>,
+    /// Wordline logic to use instead of automatic selection.
+    pub wl_logic: Option<String>,
+    /// Bitline switch to use instead of automatic selection.
+    pub bl_switch: Option<String>,
+    /// Bitline logic to use instead of automatic selection.
+    pub bl_logic: Option<String>,
+    /// Well switch to use instead of automatic selection.
+    pub well_switch: Option<String>,
+    /// Well logic to use instead of automatic selection.
+    pub well_logic: Option<String>,
+    /// ADC to use instead of automatic selection.
+    pub adc: Option<String>,
+    /// Sense amplifier to use instead of automatic selection.
+    pub sense_amp: Option<String>,
+
+    /// Treat missing peripheral sections as errors instead of skipping them.
+    pub strict: Option<bool>,
+
     /// Additional configuration options as key-value pairs.
     pub options: Option<HashMap<String, String>>,
 }
 
-/// Deserializes a configuration from a YAML file.
+impl Config {
+    /// Lists the peripheral sections this configuration does not specify.
+    ///
+    /// Missing sections are skipped during tabulation unless strict mode is
+    /// enabled, in which case they are reported as an error.
+    ///
+    /// # Returns
+    /// Names of the missing keys (`wl`, `bl`, `well`, `bits`, `fs`, `adcs`)
+    pub fn missing_sections(&self) -> Vec<&'static str> {
+        let mut missing = Vec::new();
+
+        if self.wl.is_none() {
+            missing.push("wl");
+        }
+        if self.bl.is_none() {
+            missing.push("bl");
+        }
+        if self.well.is_none() {
+            missing.push("well");
+        }
+        if self.bits.is_none() {
+            missing.push("bits");
+        }
+        if self.fs.is_none() {
+            missing.push("fs");
+        }
+        if self.adcs.is_none() && self.adc_per_cols.is_none() {
+            missing.push("adcs");
+        }
+
+        missing
+    }
+
+    /// Returns the number of ADCs, from `adcs` or derived from `adc_per_cols`.
+    ///
+    /// With `adc_per_cols: k`, one ADC is placed per `k` columns, rounding up:
+    /// `ceil(m / k)`.
+    ///
+    /// # Returns
+    /// * `Ok(Some(count))` - Number of ADCs
+    /// * `Ok(None)` - Neither key is given
+    /// * `Err(ConfigError::InvalidOption)` - Both keys are given, or `adc_per_cols` is zero
+    ///
+    /// # Examples
+    /// ```
+    /// use memea::config::Config;
+    ///
+    /// let config: Config = serde_yaml::from_str("{n: 64, m: 128, cell: c, adc_per_cols: 16}").unwrap();
+    /// assert_eq!(config.adc_count().unwrap(), Some(8));
+    ///
+    /// let config: Config = serde_yaml::from_str("{n: 64, m: 100, cell: c, adc_per_cols: 16}").unwrap();
+    /// assert_eq!(config.adc_count().unwrap(), Some(7));
+    ///
+    /// let config: Config = serde_yaml::from_str("{n: 64, m: 128, cell: c, adcs: 8, adc_per_cols: 16}").unwrap();
+    /// assert!(config.adc_count().is_err());
+    /// ```
+    pub fn adc_count(&self) -> Result<Option<usize>, ConfigError> {
+        match (self.adcs, self.adc_per_cols) {
+            (Some(_), Some(_)) => Err(ConfigError::InvalidOption(
+                "'adcs' and 'adc_per_cols' are mutually exclusive".to_string(),
+            )),
+            (_, Some(0)) => Err(ConfigError::InvalidOption(
+                "'adc_per_cols' must be at least 1".to_string(),
+            )),
+            (adcs, None) => Ok(adcs),
+            (None, Some(k)) => Ok(Some(self.m.div_ceil(k))),
+        }
+    }
+
+    /// Returns the number of rows and columns of each bank.
+    ///
+    /// `banks` divides `n` when splitting rows and `m` when splitting
+    /// columns, and must do so evenly.
+    ///
+    /// # Returns
+    /// * `Ok((rows, cols))` - Dimensions of one bank
+    /// * `Err(ConfigError::InvalidOption)` - `banks` is zero or does not
+    ///   divide the split dimension
+    ///
+    /// # Examples
+    /// ```
+    /// use memea::config::Config;
+    ///
+    /// let config: Config = serde_yaml::from_str("{n: 64, m: 128, cell: c, banks: 4}").unwrap();
+    /// assert_eq!(config.bank().unwrap(), (64, 32));
+    ///
+    /// let config: Config = serde_yaml::from_str("{n: 64, m: 128, cell: c, banks: 4, bank_split: rows}").unwrap();
+    /// assert_eq!(config.bank().unwrap(), (16, 128));
+    ///
+    /// let config: Config = serde_yaml::from_str("{n: 64, m: 128, cell: c, banks: 3}").unwrap();
+    /// assert!(config.bank().is_err());
+    /// ```
+    pub fn bank(&self) -> Result<(usize, usize), ConfigError> {
+        let banks = self.banks.unwrap_or(1);
+        let split = self.bank_split.unwrap_or_default();
+        let (key, lines) = match split {
+            BankSplit::Rows => ("n", self.n),
+            BankSplit::Cols => ("m", self.m),
+        };
+
+        if banks == 0 || lines % banks != 0 {
+            return Err(ConfigError::InvalidOption(format!(
+                "'banks' ({banks}) must evenly divide '{key}' ({lines})"
+            )));
+        }
+
+        Ok(match split {
+            BankSplit::Rows => (self.n / banks, self.m),
+            BankSplit::Cols => (self.n, self.m / banks),
+        })
+    }
+
+    /// Returns the number of bits the array stores.
+    ///
+    /// Each of the `n * m` cells stores `bits_per_cell` bits. Copies replicate
+    /// the same data, so they add area but not capacity.
+    ///
+    /// # Examples
+    /// ```
+    /// use memea::config::Config;
+    ///
+    /// let config: Config = serde_yaml::from_str("{n: 64, m: 128, cell: c}").unwrap();
+    /// assert_eq!(config.capacity(), 8192);
+    ///
+    /// let config: Config = serde_yaml::from_str("{n: 64, m: 128, cell: c, bits_per_cell: 2, copies: 3}").unwrap();
+    /// assert_eq!(config.capacity(), 16384);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.n * self.m * self.bits_per_cell.unwrap_or(1)
+    }
+
+    /// Normalizes the core cell and every pinned peripheral the same way as
+    /// database names (see [`normalize_name`]).
+    ///
+    /// # Arguments
+    /// * `fold_case` - Also fold names to lowercase
+    ///
+    /// # Examples
+    /// ```
+    /// use memea::config::Config;
+    ///
+    /// let mut config: Config = serde_yaml::from_str("{n: 4, m: 4, cell: ' C ', wl_switch: 'SW_X4 '}").unwrap();
+    /// config.normalize_names(true);
+    /// assert_eq!(config.cell, "c");
+    /// assert_eq!(config.wl_switch.as_deref(), Some("sw_x4"));
+    /// ```
+    pub fn normalize_names(&mut self, fold_case: bool) {
+        self.cell = normalize_name(&self.cell, fold_case);
+        for pinned in [
+            &mut self.wl_switch,
+            &mut self.wl_logic,
+            &mut self.bl_switch,
+            &mut self.bl_logic,
+            &mut self.well_switch,
+            &mut self.well_logic,
+            &mut self.adc,
+            &mut self.sense_amp,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            *pinned = normalize_name(pinned, fold_case);
+        }
+    }
+
+    /// Checks that the supplied values can be tabulated.
+    ///
+    /// Voltage lists (`wl`, `bl`, `well`) must contain at least one voltage
+    /// when present; omit the key entirely to skip that peripheral.
+    ///
+    /// # Returns
+    /// * `Ok(())` - Configuration is valid
+    /// * `Err(ConfigError::InvalidOption)` - A voltage list is empty, the
+    ///   ADC count is given inconsistently (see [`Config::adc_count`]), the
+    ///   banks do not divide the array (see [`Config::bank`]), `copies`,
+    ///   `banks` or `bits_per_cell` is zero, or the defect density is negative
+    ///
+    /// # Examples
+    /// ```
+    /// use memea::config::Config;
+    ///
+    /// let config: Config = serde_yaml::from_str("{n: 4, m: 4, cell: c, wl: [1.0]}").unwrap();
+    /// assert!(config.validate().is_ok());
+    ///
+    /// let config: Config = serde_yaml::from_str("{n: 4, m: 4, cell: c, wl: []}").unwrap();
+    /// assert!(config.validate().is_err());
+    ///
+    /// let config: Config = serde_yaml::from_str("{n: 4, m: 4, cell: c, defect_density: -1}").unwrap();
+    /// assert!(config.validate().is_err());
+    ///
+    /// let config: Config = serde_yaml::from_str("{n: 4, m: 4, cell: c, copies: 0}").unwrap();
+    /// assert!(config.validate().is_err());
+    ///
+    /// let config: Config = serde_yaml::from_str("{n: 4, m: 4, cell: c, banks: 0}").unwrap();
+    /// let err = config.validate().unwrap_err().to_string();
+    /// assert!(err.contains("'banks' must be at least 1"));
+    /// ```
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let lists = [("wl", &self.wl), ("bl", &self.bl), ("well", &self.well)];
+
+        for (key, list) in lists {
+            if list.as_ref().is_some_and(|v| v.is_empty()) {
+                return Err(ConfigError::InvalidOption(format!(
+                    "'{key}' must list at least one voltage (omit it to skip)"
+                )));
+            }
+        }
+
+        self.adc_count()?;
+
+        let counts = [
+            ("copies", self.copies),
+            ("banks", self.banks),
+            ("bits_per_cell", self.bits_per_cell),
+        ];
+        for (key, count) in counts {
+            if count == Some(0) {
+                return Err(ConfigError::InvalidOption(format!(
+                    "'{key}' must be at least 1"
+                )));
+            }
+        }
+
+        self.bank()?;
+
+        if self
+            .defect_density
+            .is_some_and(|d| !(d >= 0.0 && d.is_finite()))
+        {
+            return Err(ConfigError::InvalidOption(
+                "'defect_density' must be a non-negative number".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses a YAML (`.yaml`/`.yml`) or JSON (`.json`) file by its extension.
+fn parse_file<T: DeserializeOwned>(filename: &std::path::Path) -> Result<T, MemeaError> {
+    let ext = filename
+        .extension()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+
+    match ext.as_str() {
+        "yaml" | "yml" => Ok(serde_yaml::from_str(&read_text(filename)?)?),
+        "json" => Ok(serde_json::from_str(&read_text(filename)?)?),
+        other => Err(ConfigError::FileType(other.to_string()).into()),
+    }
+}
+
+/// Reads a defaults file holding values shared by every configuration.
+///
+/// The file uses the same keys as a configuration, but any subset of them may
+/// be given. `name` is rejected, since every configuration would inherit it
+/// and be reported under the same name.
 ///
 /// # Arguments
-/// * `filename` - Path of the YAML file to read
+/// * `filename` - Path of the YAML or JSON defaults file to read
 ///
 /// # Returns
-/// * `Ok(Config)` - Successfully parsed configuration
-/// * `Err(MemeaError)` - File I/O error or YAML parsing error
+/// * `Ok(Mapping)` - Default values indexed by key
+/// * `Err(MemeaError)` - File I/O error, parsing error, unsupported extension
+///   or a `name` key
 ///
 /// # Examples
-/// ```no_run
-/// use std::path::PathBuf;
-/// # use memea::config::read;
+/// ```
+/// use memea::config::read_defaults;
+///
+/// let path = std::env::temp_dir().join("memea_read_defaults.yaml");
+/// std::fs::write(&path, "vdd: 0.8\n").unwrap();
+/// assert_eq!(read_defaults(&path).unwrap().len(), 1);
 ///
-/// let config_path = PathBuf::from("config.yaml");
-/// let config = read(&config_path).expect("Failed to read config");
+/// std::fs::write(&path, "vdd: 0.8\nname: shared\n").unwrap();
+/// assert!(read_defaults(&path).is_err());
+/// # std::fs::remove_file(&path).unwrap();
 /// ```
-fn read(filename: &std::path::PathBuf) -> Result<Config, MemeaError> {
-    // TODO: Check filetype
-    let file = fs::File::open(filename)?;
-    let rdr = BufReader::new(file);
-    let config: Config = serde_yaml::from_reader(rdr)?;
+pub fn read_defaults(filename: &std::path::Path) -> Result<Mapping, MemeaError> {
+    let defaults: Mapping = parse_file(filename)?;
+    if defaults.contains_key("name") {
+        return Err(ConfigError::InvalidOption(format!(
+            "'name' cannot be set in defaults file {filename:?}"
+        ))
+        .into());
+    }
+
+    Ok(defaults)
+}
+
+/// Fills keys missing from a configuration with values from the defaults.
+///
+/// Keys explicitly present in the configuration always take precedence; a key
+/// set to `null` counts as missing.
+///
+/// # Arguments
+/// * `config` - Configuration as a raw YAML value, updated in place
+/// * `defaults` - Default values indexed by key
+///
+/// # Examples
+/// ```
+/// use memea::config::{apply_defaults, Config};
+/// use serde_yaml::{Mapping, Value};
+///
+/// let defaults: Mapping = serde_yaml::from_str("vdd: 0.8\nbits: 1").unwrap();
+/// let mut a: Value = serde_yaml::from_str("n: 4\nm: 4\ncell: c\nvdd: 1.0").unwrap();
+/// let mut b: Value = serde_yaml::from_str("n: 4\nm: 4\ncell: c").unwrap();
+///
+/// apply_defaults(&mut a, &defaults);
+/// apply_defaults(&mut b, &defaults);
+///
+/// let a: Config = serde_yaml::from_value(a).unwrap();
+/// let b: Config = serde_yaml::from_value(b).unwrap();
+/// assert_eq!((a.vdd, a.bits), (Some(1.0), Some(1)));
+/// assert_eq!((b.vdd, b.bits), (Some(0.8), Some(1)));
+/// ```
+pub fn apply_defaults(config: &mut Value, defaults: &Mapping) {
+    if let Value::Mapping(map) = config {
+        for (key, value) in defaults {
+            if map.get(key).is_none_or(Value::is_null) {
+                map.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+/// Replaces rail names in the voltage lists with the rail voltages.
+///
+/// A top-level `rails` map defines named voltages once; entries of `bl`,
+/// `wl` and `well` may then be either numbers or rail names. Numbers are
+/// left unchanged.
+///
+/// # Arguments
+/// * `config` - Configuration as a raw YAML value, updated in place
+///
+/// # Returns
+/// * `Ok(())` - Every rail name was resolved
+/// * `Err(ConfigError)` - A list refers to an undefined rail, or a rail is not a number
+///
+/// # Examples
+/// ```
+/// use memea::config::{resolve_rails, Config};
+/// use serde_yaml::Value;
+///
+/// let mut value: Value = serde_yaml::from_str(
+///     "n: 4\nm: 4\ncell: c\nrails: {vdd: 0.8, vpp: 2.5}\nwl: [vpp, vdd, 0]",
+/// )
+/// .unwrap();
+/// resolve_rails(&mut value).unwrap();
+///
+/// let config: Config = serde_yaml::from_value(value).unwrap();
+/// assert_eq!(config.wl, Some(vec![2.5, 0.8, 0.0]));
+///
+/// let mut bad: Value = serde_yaml::from_str("rails: {vdd: 0.8}\nbl: [vddq]").unwrap();
+/// assert!(resolve_rails(&mut bad).is_err());
+/// ```
+pub fn resolve_rails(config: &mut Value) -> Result<(), ConfigError> {
+    let Value::Mapping(map) = config else {
+        return Ok(());
+    };
+
+    let mut rails: HashMap<String, Value> = HashMap::new();
+    if let Some(Value::Mapping(defined)) = map.get("rails") {
+        for (name, voltage) in defined {
+            let name = name.as_str().unwrap_or_default().to_string();
+            if !voltage.is_number() {
+                return Err(ConfigError::InvalidOption(format!(
+                    "rail '{name}' must be a voltage"
+                )));
+            }
+            rails.insert(name, voltage.clone());
+        }
+    }
+
+    for key in ["bl", "wl", "well"] {
+        let Some(Value::Sequence(voltages)) = map.get_mut(key) else {
+            continue;
+        };
+
+        for v in voltages.iter_mut() {
+            if let Value::String(name) = v {
+                *v = rails.get(name.as_str()).cloned().ok_or_else(|| {
+                    ConfigError::InvalidOption(format!("undefined rail '{name}' in {key}"))
+                })?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads a configuration file as a raw value, resolving its base and includes.
+///
+/// The format is chosen by extension: YAML for `.yaml`/`.yml`, JSON for
+/// `.json`; fragments may use either format regardless of the including file.
+///
+/// A top-level `include` key lists fragment files (relative to the including
+/// file) whose keys are merged into the configuration. Later includes take
+/// precedence over earlier ones, and keys set in the including file take
+/// precedence over all of them. Fragments may themselves include other
+/// fragments; include cycles are reported as an error.
+///
+/// A top-level `extends` key names a single base configuration (relative to
+/// the extending file) to inherit every key from. It is merged before any
+/// includes, so both the includes and the extending file override it, and the
+/// base may itself extend another file. Merging is per key: a key present in
+/// the extending file replaces the base's value entirely, so e.g. a `wl` list
+/// replaces the base's `wl` rather than appending to it. The base's `name`
+/// is not inherited.
+///
+/// # Arguments
+/// * `filename` - Path of the YAML or JSON file to read
+///
+/// # Returns
+/// * `Ok(Value)` - Merged configuration, without the `include` key
+/// * `Err(MemeaError)` - File I/O error, parsing error, unsupported extension
+///   or include cycle
+///
+/// # Examples
+/// ```
+/// use memea::config::{read_value, Config};
+/// use std::fs;
+///
+/// let dir = std::env::temp_dir().join("memea_include_doc");
+/// fs::create_dir_all(&dir).unwrap();
+/// fs::write(dir.join("adc.yaml"), "adc: sar8\nbits: 8\nfs: 1e9\nadcs: 16").unwrap();
+/// fs::write(dir.join("a.yaml"), "include: [adc.yaml]\nn: 64\nm: 64\ncell: c").unwrap();
+/// fs::write(dir.join("b.yaml"), "include: [adc.yaml]\nn: 32\nm: 32\ncell: c\nadcs: 8").unwrap();
+///
+/// let a: Config = serde_yaml::from_value(read_value(&dir.join("a.yaml")).unwrap()).unwrap();
+/// let b: Config = serde_yaml::from_value(read_value(&dir.join("b.yaml")).unwrap()).unwrap();
+/// assert_eq!((a.adc.as_deref(), a.bits, a.adcs), (Some("sar8"), Some(8), Some(16)));
+/// assert_eq!((b.adc.as_deref(), b.bits, b.adcs), (Some("sar8"), Some(8), Some(8)));
+///
+/// fs::write(dir.join("loop.yaml"), "include: [loop.yaml]\nn: 1").unwrap();
+/// assert!(read_value(&dir.join("loop.yaml")).is_err());
+///
+/// fs::write(dir.join("base.yaml"), "n: 64\nm: 64\ncell: c\nwl: [4, 0]\nbits: 4").unwrap();
+/// fs::write(dir.join("big.yaml"), "extends: base.yaml\nn: 256\nwl: [2.5]").unwrap();
+/// let big: Config = serde_yaml::from_value(read_value(&dir.join("big.yaml")).unwrap()).unwrap();
+/// assert_eq!((big.n, big.m, big.bits), (256, 64, Some(4)));
+/// assert_eq!(big.wl, Some(vec![2.5]));
+///
+/// fs::write(dir.join("x.yaml"), "extends: y.yaml\nn: 1").unwrap();
+/// fs::write(dir.join("y.yaml"), "extends: x.yaml\nm: 1").unwrap();
+/// assert!(read_value(&dir.join("x.yaml")).is_err());
+/// ```
+pub fn read_value(filename: &std::path::Path) -> Result<Value, MemeaError> {
+    read_included(filename, &mut Vec::new())
+}
+
+/// Reads one file of an include chain, tracking the files currently open.
+fn read_included(
+    filename: &std::path::Path,
+    stack: &mut Vec<PathBuf>,
+) -> Result<Value, MemeaError> {
+    let path = fs::canonicalize(filename)?;
+    if let Some(start) = stack.iter().position(|p| *p == path) {
+        let chain: Vec<String> = stack[start..]
+            .iter()
+            .chain(std::iter::once(&path))
+            .map(|p| p.display().to_string())
+            .collect();
+        return Err(ConfigError::IncludeCycle(chain.join(" -> ")).into());
+    }
+
+    let mut value: Value = parse_file(&path)?;
+
+    let (extends, includes) = match &mut value {
+        Value::Mapping(map) => (map.remove("extends"), map.remove("include")),
+        _ => (None, None),
+    };
+    let extends = match extends {
+        None | Some(Value::Null) => None,
+        Some(Value::String(s)) => Some(s),
+        Some(_) => {
+            return Err(ConfigError::InvalidOption(format!(
+                "extends in {} must be a file path",
+                filename.display()
+            ))
+            .into())
+        }
+    };
+    let includes = match includes {
+        None | Some(Value::Null) => vec![],
+        Some(Value::String(s)) => vec![s],
+        Some(Value::Sequence(seq)) => seq
+            .into_iter()
+            .map(|v| match v {
+                Value::String(s) => Ok(s),
+                _ => Err(ConfigError::InvalidOption(format!(
+                    "include entries in {} must be file paths",
+                    filename.display()
+                ))),
+            })
+            .collect::<Result<_, _>>()?,
+        Some(_) => {
+            return Err(ConfigError::InvalidOption(format!(
+                "include in {} must be a list of file paths",
+                filename.display()
+            ))
+            .into())
+        }
+    };
+
+    if extends.is_none() && includes.is_empty() {
+        return Ok(value);
+    }
+
+    let base = path.parent().map(PathBuf::from).unwrap_or_default();
+    stack.push(path);
+    let mut merged = Mapping::new();
+
+    // The base configuration is merged first, so includes and this file win;
+    // its name is not inherited, so that siblings do not collide
+    if let Some(parent) = extends {
+        if let Value::Mapping(mut inherited) = read_included(&base.join(parent), stack)? {
+            inherited.remove("name");
+            merged.extend(inherited);
+        }
+    }
+    for include in includes {
+        if let Value::Mapping(fragment) = read_included(&base.join(include), stack)? {
+            merged.extend(fragment);
+        }
+    }
+    stack.pop();
+
+    if let Value::Mapping(host) = value {
+        merged.extend(host);
+    }
+
+    Ok(Value::Mapping(merged))
+}
+
+/// Deserializes a configuration from a YAML or JSON file.
+///
+/// The format is chosen by extension (`.yaml`/`.yml` or `.json`). The
+/// `extends` base and includes are resolved as described in [`read_value`],
+/// then defaults are applied
+/// and rail names resolved (see [`resolve_rails`]).
+///
+/// # Arguments
+/// * `filename` - Path of the YAML or JSON file to read
+/// * `defaults` - Optional default values for keys the file leaves unset
+///
+/// # Returns
+/// * `Ok(Config)` - Successfully parsed configuration
+/// * `Err(MemeaError)` - File I/O error, parsing error or unsupported extension
+///
+/// A leading UTF-8 byte order mark, as written by some Windows editors, is
+/// ignored.
+fn read(filename: &std::path::Path, defaults: Option<&Mapping>) -> Result<Config, MemeaError> {
+    let mut value = read_value(filename)?;
+
+    if let Some(d) = defaults {
+        apply_defaults(&mut value, d);
+    }
+    resolve_rails(&mut value)?;
+
+    let config: Config = serde_yaml::from_value(value)?;
 
     Ok(config)
 }
 
+/// File extensions recognized as configurations when expanding directories.
+const CONFIG_EXTS: [&str; 3] = ["yaml", "yml", "json"];
+
+/// Checks whether a path has a configuration file extension.
+fn is_config(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| CONFIG_EXTS.iter().any(|c| e.eq_ignore_ascii_case(c)))
+}
+
+/// Lists the configuration files in a directory, sorted by path.
+fn scan_dir(dir: &Path, recursive: bool, found: &mut Vec<PathBuf>) {
+    let mut entries: Vec<PathBuf> = match fs::read_dir(dir) {
+        Ok(rd) => rd.filter_map(|e| e.ok().map(|e| e.path())).collect(),
+        Err(e) => {
+            errorln!("Failed to read directory {:?} ({})", dir, e);
+            return;
+        }
+    };
+    entries.sort();
+
+    for path in entries {
+        if path.is_dir() {
+            if recursive {
+                scan_dir(&path, recursive, found);
+            }
+        } else if is_config(&path) {
+            found.push(path);
+        }
+    }
+}
+
+/// Translates one path component of a shell-style glob into a regex.
+///
+/// Supports `*`, `?` and bracket classes (`[abc]`, `[a-z]`, `[!abc]`).
+fn glob_regex(pattern: &str) -> Option<Regex> {
+    let mut re = String::from("^");
+    let mut chars = pattern.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '*' => re.push_str(".*"),
+            '?' => re.push('.'),
+            '[' => {
+                re.push('[');
+                if chars.next_if(|&c| c == '!' || c == '^').is_some() {
+                    re.push('^');
+                }
+                for c in chars.by_ref() {
+                    if c == ']' {
+                        break;
+                    }
+                    if c == '\\' || c == '[' {
+                        re.push('\\');
+                    }
+                    re.push(c);
+                }
+                re.push(']');
+            }
+            c => re.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    re.push('$');
+
+    Regex::new(&re).ok()
+}
+
+/// Expands a shell-style glob into the sorted paths it matches.
+///
+/// A `**` component matches any number of directories. As in the shell,
+/// wildcards only match hidden names if the pattern starts with a dot.
+fn glob(pattern: &Path) -> Vec<PathBuf> {
+    let mut matches = vec![PathBuf::new()];
+
+    for comp in pattern.components() {
+        let part = comp.as_os_str().to_string_lossy();
+        if part == "**" {
+            let mut dirs = Vec::new();
+            while let Some(dir) = matches.pop() {
+                let base = if dir.as_os_str().is_empty() {
+                    Path::new(".")
+                } else {
+                    &dir
+                };
+                if let Ok(rd) = fs::read_dir(base) {
+                    matches.extend(
+                        rd.filter_map(|e| e.ok().map(|e| dir.join(e.file_name())))
+                            .filter(|p| p.is_dir()),
+                    );
+                }
+                dirs.push(dir);
+            }
+            matches = dirs;
+        } else if part.contains(['*', '?', '[']) {
+            let Some(re) = glob_regex(&part) else {
+                return vec![];
+            };
+            matches = matches
+                .iter()
+                .flat_map(|dir| {
+                    let base = if dir.as_os_str().is_empty() {
+                        Path::new(".")
+                    } else {
+                        dir
+                    };
+                    fs::read_dir(base)
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|e| {
+                            e.ok().map(|e| e.file_name().to_string_lossy().into_owned())
+                        })
+                        .filter(|name| part.starts_with('.') || !name.starts_with('.'))
+                        .filter(|name| re.is_match(name))
+                        .map(|name| dir.join(name))
+                        .collect::<Vec<_>>()
+                })
+                .collect();
+        } else {
+            matches = matches
+                .into_iter()
+                .map(|dir| dir.join(comp))
+                .filter(|p| p.exists())
+                .collect();
+        }
+    }
+
+    matches.sort();
+    matches.dedup();
+    matches
+}
+
+/// Expands configuration inputs given on the command line.
+///
+/// Each input may be a file (kept as-is), a directory (expanded into the
+/// `.yaml`, `.yml` and `.json` files directly inside it, or anywhere below it
+/// if `recursive` is set), or a shell-style glob such as `configs/*.yaml` or
+/// `configs/**/*.json` (useful when quoted, or where the shell does not
+/// expand globs). Directories matched by a glob are expanded like
+/// directories. Inputs resolving to the same file are only kept once, at
+/// their first position.
+///
+/// # Arguments
+/// * `inputs` - Files, directories and globs to expand
+/// * `recursive` - Whether directories are searched recursively
+///
+/// # Returns
+/// Configuration file paths, with directory contents in sorted order
+///
+/// # Examples
+/// ```
+/// use memea::config::expand_inputs;
+/// use std::fs;
+///
+/// let dir = std::env::temp_dir().join("memea_expand_doc");
+/// fs::create_dir_all(dir.join("sub")).unwrap();
+/// for f in ["a.yaml", "b.json", "notes.txt", "sub/c.yml"] {
+///     fs::write(dir.join(f), "").unwrap();
+/// }
+///
+/// assert_eq!(expand_inputs(&[dir.clone()], false), [dir.join("a.yaml"), dir.join("b.json")]);
+/// assert_eq!(expand_inputs(&[dir.clone()], true).len(), 3);
+/// assert_eq!(expand_inputs(&[dir.join("*.yaml")], false), [dir.join("a.yaml")]);
+/// assert_eq!(expand_inputs(&[dir.join("**").join("*.y?l")], false).len(), 1);
+///
+/// // The same file named twice is read once
+/// let twice = expand_inputs(&[dir.join("b.json"), dir.clone()], false);
+/// assert_eq!(twice, [dir.join("b.json"), dir.join("a.yaml")]);
+/// ```
+pub fn expand_inputs(inputs: &[PathBuf], recursive: bool) -> Vec<PathBuf> {
+    let mut expanded = Vec::new();
+
+    for input in inputs {
+        let is_glob = !input.exists() && input.to_string_lossy().contains(['*', '?', '[']);
+        let matches = if is_glob {
+            glob(input)
+        } else {
+            vec![input.clone()]
+        };
+        if is_glob && matches.is_empty() {
+            warnln!("No files match {:?}", input);
+        }
+
+        for path in matches {
+            if path.is_dir() {
+                let before = expanded.len();
+                scan_dir(&path, recursive, &mut expanded);
+                if expanded.len() == before {
+                    warnln!("No configuration files in {:?}", path);
+                }
+            } else {
+                expanded.push(path);
+            }
+        }
+    }
+
+    // Deduplicate by resolved path, keeping the first occurrence
+    let mut seen = HashSet::new();
+    expanded.retain(|p| seen.insert(fs::canonicalize(p).unwrap_or_else(|_| p.clone())));
+
+    expanded
+}
+
 /// Reads multiple configuration files and returns them indexed by name.
 ///
 /// This function attempts to read all provided configuration files. If a file
@@ -107,6 +1039,7 @@ fn read(filename: &std::path::PathBuf) -> Result<Config, MemeaError> {
 ///
 /// # Arguments
 /// * `paths` - Vector of configuration file paths to read
+/// * `defaults` - Optional default values merged into every configuration
 ///
 /// # Returns
 /// * `HashMap<String, Config>` - Successfully parsed configurations indexed by name
@@ -120,24 +1053,238 @@ fn read(filename: &std::path::PathBuf) -> Result<Config, MemeaError> {
 ///     PathBuf::from("config1.yaml"),
 ///     PathBuf::from("config2.yaml"),
 /// ];
-/// let configs = read_all(&paths);
+/// let configs = read_all(&paths, None);
 /// println!("Loaded {} configurations", configs.len());
 /// ```
-pub fn read_all(paths: &Vec<PathBuf>) -> Configs {
+///
+/// Configurations sharing a name are all kept; later ones are renamed with
+/// their file stem (and a number, if that is taken too):
+/// ```
+/// use memea::config::read_all;
+/// use std::fs;
+///
+/// let dir = std::env::temp_dir().join("memea_collide_doc");
+/// fs::create_dir_all(&dir).unwrap();
+/// fs::write(dir.join("small.yaml"), "name: FeRAM\nn: 64\nm: 64\ncell: c").unwrap();
+/// fs::write(dir.join("large.yaml"), "name: FeRAM\nn: 256\nm: 256\ncell: c").unwrap();
+///
+/// let configs = read_all(&vec![dir.join("small.yaml"), dir.join("large.yaml")], None);
+/// assert_eq!(configs["FeRAM"].n, 64);
+/// assert_eq!(configs["FeRAM (large)"].n, 256);
+/// ```
+///
+/// Files are YAML (a leading byte order mark is ignored) or JSON; other
+/// extensions are reported and skipped:
+/// ```
+/// use memea::config::read_all;
+/// use std::fs;
+///
+/// let dir = std::env::temp_dir().join("memea_read_doc");
+/// fs::create_dir_all(&dir).unwrap();
+/// fs::write(dir.join("bom.yaml"), "\u{FEFF}n: 128\nm: 64\ncell: 1FeFET_100\n").unwrap();
+/// fs::write(dir.join("j.json"), r#"{"n": 32, "m": 16, "cell": "1FeFET_100", "wl": [0.8, 0]}"#).unwrap();
+/// fs::write(dir.join("t.toml"), "n = 32").unwrap();
+///
+/// let paths = ["bom.yaml", "j.json", "t.toml"].map(|f| dir.join(f));
+/// let configs = read_all(&paths, None);
+/// assert_eq!(configs.len(), 2);
+/// let bom = configs.values().find(|c| c.n == 128).unwrap();
+/// assert_eq!(bom.m, 64);
+/// let json = configs.values().find(|c| c.n == 32).unwrap();
+/// assert_eq!((json.m, json.wl.as_ref().map(|v| v.len())), (16, Some(2)));
+/// ```
+pub fn read_all(paths: &[PathBuf], defaults: Option<&Mapping>) -> Configs {
+    let (configs, failed) = try_read_all(paths, defaults);
+    for (c, e) in failed {
+        errorln!("Failed to read config {:?} ({})", &c, e);
+    }
+
+    configs
+}
+
+/// Reads multiple configuration files, returning failures instead of logging them.
+///
+/// Behaves like [`read_all`], except that files which fail to read are
+/// returned with their error rather than printed.
+///
+/// # Arguments
+/// * `paths` - Configuration file paths to read
+/// * `defaults` - Optional default values merged into every configuration
+///
+/// # Returns
+/// Successfully parsed configurations indexed by name, and the paths that
+/// failed with their errors
+///
+/// # Examples
+/// ```
+/// use memea::config::try_read_all;
+/// use std::path::PathBuf;
+///
+/// let (configs, failed) = try_read_all(&[PathBuf::from("missing.yaml")], None);
+/// assert!(configs.is_empty());
+/// assert_eq!(failed[0].0, PathBuf::from("missing.yaml"));
+/// ```
+pub fn try_read_all(
+    paths: &[PathBuf],
+    defaults: Option<&Mapping>,
+) -> (Configs, Vec<(PathBuf, MemeaError)>) {
     let mut configs: Configs = HashMap::new();
+    let mut failed = Vec::new();
+    let mut sources: HashMap<String, &PathBuf> = HashMap::new();
     for c in paths {
-        match read(c) {
+        match read(c, defaults) {
             Ok(r) => {
                 let name = match &r.name {
                     Some(s) => s.clone(),
                     None => c.to_string_lossy().into(),
                 };
 
+                let name = match sources.get(&name) {
+                    Some(first) => {
+                        let stem = c.file_stem().unwrap_or_default().to_string_lossy();
+                        let base = format!("{name} ({stem})");
+                        let unique = std::iter::once(base.clone())
+                            .chain((2..).map(|i| format!("{base} {i}")))
+                            .find(|n| !configs.contains_key(n))
+                            .unwrap_or(base);
+                        warnln!(
+                            "Configurations {:?} and {:?} are both named '{}'; reporting the latter as '{}'",
+                            first,
+                            c,
+                            name,
+                            unique
+                        );
+                        unique
+                    }
+                    None => name,
+                };
+
+                sources.insert(name.clone(), c);
                 configs.insert(name, r);
             }
-            Err(e) => errorln!("Failed to read config {:?} ({})", &c, e),
+            Err(e) => failed.push((c.clone(), e)),
         }
     }
 
-    configs
+    (configs, failed)
+}
+
+/// Keeps only the first `n` configurations in sorted name order.
+///
+/// # Arguments
+/// * `configs` - Configurations indexed by name
+/// * `n` - Number of configurations to keep
+///
+/// # Returns
+/// The subset of configurations with the `n` lexicographically smallest names
+///
+/// # Examples
+/// ```
+/// use std::collections::HashMap;
+/// use memea::config::limit;
+///
+/// let configs = HashMap::from([("c", 3), ("a", 1), ("b", 2)].map(|(k, v)| (k.to_string(), v)));
+/// let kept = limit(configs, 2);
+/// assert!(kept.contains_key("a") && kept.contains_key("b"));
+/// assert_eq!(kept.len(), 2);
+/// ```
+pub fn limit<V>(configs: HashMap<String, V>, n: usize) -> HashMap<String, V> {
+    let mut entries: Vec<(String, V)> = configs.into_iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    entries.into_iter().take(n).collect()
+}
+
+/// Advances a SplitMix64 generator and returns its next output.
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Keeps a pseudo-random subset of `k` configurations.
+///
+/// Names are sorted before shuffling, so the same seed always selects the
+/// same configurations regardless of file order.
+///
+/// # Arguments
+/// * `configs` - Configurations indexed by name
+/// * `k` - Number of configurations to keep
+/// * `seed` - Seed for the pseudo-random generator
+///
+/// # Returns
+/// A subset of `min(k, configs.len())` configurations
+///
+/// # Examples
+/// ```
+/// use std::collections::HashMap;
+/// use memea::config::sample;
+///
+/// let configs: HashMap<String, usize> = (0..20).map(|i| (format!("cfg{i}"), i)).collect();
+/// let a = sample(configs.clone(), 5, 42);
+/// let b = sample(configs, 5, 42);
+///
+/// assert_eq!(a.len(), 5);
+/// assert_eq!(a, b);
+/// ```
+pub fn sample<V>(configs: HashMap<String, V>, k: usize, seed: u64) -> HashMap<String, V> {
+    let mut entries: Vec<(String, V)> = configs.into_iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+    // Partial Fisher-Yates shuffle of the first k entries
+    let mut state = seed;
+    let k = k.min(entries.len());
+    for i in 0..k {
+        let j = i + (splitmix64(&mut state) % (entries.len() - i) as u64) as usize;
+        entries.swap(i, j);
+    }
+
+    entries.into_iter().take(k).collect()
+}
+
+/// Finds core cells referenced by configurations but absent from the database.
+///
+/// # Arguments
+/// * `configs` - Configurations indexed by name
+/// * `db` - Database the configurations will be tabulated against
+///
+/// # Returns
+/// Vector of (missing cell name, sorted names of configurations using it),
+/// sorted by cell name
+///
+/// # Examples
+/// ```
+/// use std::collections::HashMap;
+/// use memea::config::{missing_cells, Config};
+/// use memea::db::Database;
+///
+/// let config: Config = serde_yaml::from_str("{n: 4, m: 4, cell: sram}").unwrap();
+/// let configs = HashMap::from([("a".to_string(), config)]);
+///
+/// let missing = missing_cells(&configs, &Database::new());
+/// assert_eq!(missing, vec![("sram".to_string(), vec!["a".to_string()])]);
+/// ```
+pub fn missing_cells(
+    configs: &HashMap<String, Config>,
+    db: &Database,
+) -> Vec<(String, Vec<String>)> {
+    let mut missing: HashMap<&str, Vec<String>> = HashMap::new();
+
+    for (name, c) in configs {
+        if !db.core.contains_key(&c.cell) {
+            missing.entry(&c.cell).or_default().push(name.clone());
+        }
+    }
+
+    let mut missing: Vec<(String, Vec<String>)> = missing
+        .into_iter()
+        .map(|(cell, mut names)| {
+            names.sort();
+            (cell.to_string(), names)
+        })
+        .collect();
+    missing.sort();
+
+    missing
 }
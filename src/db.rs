@@ -4,13 +4,19 @@
 //! containing memory cells, logic blocks, switches, and ADCs. The database supports
 //! both YAML and JSON formats for storage and retrieval.
 
+use clap::ValueEnum;
 use dialoguer::Input;
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, path::PathBuf};
-use std::{fmt, fs, io, path};
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+};
+use std::{fmt, fs, path};
 use thiserror::Error;
 
-use crate::{errorln, infoln, query, vprintln, Float, MemeaError, Mosaic};
+use crate::{
+    errorln, infoln, query, read_text, vprintln, warnln, Float, MemeaError, Mosaic, QueryDefault,
+};
 
 /// Errors that can occur during database operations.
 #[derive(Debug, Error)]
@@ -21,9 +27,38 @@ pub enum DBError {
     /// Indicates that no cells matching the criteria were found.
     #[error("Failed to find suitable cell: {0}")]
     NoSuitableCells(String),
+    /// Indicates that a cell pinned by name does not meet the requirements.
+    #[error("Pinned cell does not meet requirements: {0}")]
+    UnsuitableCell(String),
     /// Indicates an unsupported file format was encountered.
     #[error("Unsupported file extension: {0}")]
     FileType(String),
+    /// Indicates that database sources are malformed or conflict.
+    #[error("Invalid database source: {0}")]
+    Source(String),
+    /// Indicates that database cells have invalid parameters.
+    #[error("Invalid database: {0}")]
+    Invalid(String),
+    /// Indicates that a cell type name is not recognized.
+    #[error("Unknown cell type '{0}' (expected core, logic, switch, adc or sense)")]
+    CellType(String),
+}
+
+/// Relative tolerance used when comparing floating-point cell parameters.
+const EPSILON: Float = 1e-6;
+
+/// Compares two floats with a relative tolerance of [`EPSILON`].
+fn approx_eq(a: Float, b: Float) -> bool {
+    (a - b).abs() <= EPSILON * a.abs().max(b.abs()).max(1.0)
+}
+
+/// Compares two optional floats with a relative tolerance of [`EPSILON`].
+fn approx_eq_opt(a: Option<Float>, b: Option<Float>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => approx_eq(a, b),
+        (None, None) => true,
+        _ => false,
+    }
 }
 
 /// Physical dimensions of a component including size and enclosure.
@@ -36,6 +71,19 @@ pub struct Dims {
     pub size: [Float; 2],
     /// Horizontal and vertical enclosure requirements in micrometers [x_enc, y_enc].
     pub enc: [Float; 2],
+    /// True area of one instance in μm², measured as the union of its GDS
+    /// polygons on the footprint layer. Unlike `size` and `enc`, which give a
+    /// bounding box, this follows non-rectangular (e.g. L-shaped) outlines.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub footprint: Option<Float>,
+    /// Total area of the RECT shapes in the LEF `PIN` blocks of one
+    /// instance in μm², for port-area reporting only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pin_area: Option<Float>,
+    /// Total area of the RECT shapes in the LEF `OBS` block of one instance
+    /// in μm², for routing-blockage reporting only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub obs_area: Option<Float>,
 }
 
 impl Default for Dims {
@@ -53,6 +101,9 @@ impl Dims {
         Dims {
             size: [0.0, 0.0],
             enc: [0.0, 0.0],
+            footprint: None,
+            pin_area: None,
+            obs_area: None,
         }
     }
 
@@ -70,19 +121,280 @@ impl Dims {
         Dims {
             size: [width, height],
             enc: [enc_x, enc_y],
+            footprint: None,
+            pin_area: None,
+            obs_area: None,
         }
     }
 
+    /// Calculates the width and height of an array of components.
+    ///
+    /// Columns multiply the width (`size[0]`) and rows multiply the height
+    /// (`size[1]`); enclosures are not included.
+    ///
+    /// # Arguments
+    /// * `mos` - Arrangement of the components
+    ///
+    /// # Returns
+    /// `[width, height]` in micrometers
+    ///
+    /// # Examples
+    /// ```
+    /// use memea::db::Dims;
+    /// use memea::Mosaic;
+    ///
+    /// let dims = Dims::from(2.0, 3.0, 0.5, 0.5);
+    /// assert_eq!(dims.span(Mosaic::row(4)), [2.0, 12.0]);
+    /// assert_eq!(dims.span(Mosaic::col(4)), [8.0, 3.0]);
+    /// assert_eq!(dims.span(Mosaic::array(4, 5)), [10.0, 12.0]);
+    /// ```
+    pub fn span(&self, mos: Mosaic) -> [Float; 2] {
+        [
+            mos.cols as Float * self.size[0],
+            mos.rows as Float * self.size[1],
+        ]
+    }
+
     /// Calculates the total area occupied by an array of components.
     ///
     /// # Arguments
-    /// * `(n, m)` - Array dimensions as (rows, columns)
+    /// * `mos` - Arrangement of the components
+    ///
+    /// # Returns
+    /// Total area in square micrometers including enclosures
+    ///
+    /// # Examples
+    /// ```
+    /// use memea::db::Dims;
+    /// use memea::Mosaic;
+    ///
+    /// // 1 x 2 μm cell with 0.5 μm horizontal and 0.25 μm vertical enclosure
+    /// let dims = Dims::from(1.0, 2.0, 0.5, 0.25);
+    /// // (2 * 1 + 2 * 0.5) * (4 * 2 + 2 * 0.25)
+    /// assert_eq!(dims.area(Mosaic::array(4, 2)), 25.5);
+    /// assert_eq!(dims.area(Mosaic::SINGLE), 2.0 * 2.5);
+    /// assert_eq!(dims.transposed().area(Mosaic::SINGLE), 2.5 * 2.0);
+    /// ```
+    pub fn area(&self, mos: Mosaic) -> Float {
+        let [w, h] = self.span(mos);
+        (w + (self.enc[0] * 2.0)) * (h + (self.enc[1] * 2.0))
+    }
+
+    /// Calculates the total area of an array of components under an area model.
+    ///
+    /// With `w`/`h` the component size, `enc_x`/`enc_y` its enclosure, and
+    /// `n`/`m` the mosaic rows and columns:
+    ///
+    /// * [`AreaModel::PerimeterOnly`] - enclosure is counted once around the whole
+    ///   array; identical to [`Dims::area`]
+    /// * [`AreaModel::PerCell`] - every instance carries its own enclosure:
+    ///   `(m * (w + 2 * enc_x)) * (n * (h + 2 * enc_y))`
+    /// * [`AreaModel::Footprint`] - every instance occupies its measured
+    ///   footprint: `n * m * footprint`; cells without a footprint fall back
+    ///   to [`AreaModel::PerCell`]
+    ///
+    /// # Arguments
+    /// * `mos` - Arrangement of the components
+    /// * `model` - How enclosure is accounted for
     ///
     /// # Returns
     /// Total area in square micrometers including enclosures
-    pub fn area(&self, (n, m): Mosaic) -> Float {
-        ((m as Float * self.size[0]) + (self.enc[0] * 2.0))
-            * ((n as Float * self.size[1]) + (self.size[1] * 2.0))
+    ///
+    /// # Examples
+    /// ```
+    /// use memea::db::{AreaModel, Dims};
+    /// use memea::Mosaic;
+    ///
+    /// let mos = Mosaic::array(2, 2);
+    /// let dims = Dims::from(1.0, 1.0, 0.5, 0.5);
+    /// assert_eq!(dims.area_with(mos, AreaModel::PerCell), 16.0);
+    /// assert_eq!(
+    ///     dims.area_with(mos, AreaModel::PerimeterOnly),
+    ///     dims.area(mos)
+    /// );
+    ///
+    /// let l_shaped = Dims { footprint: Some(3.0), ..dims };
+    /// assert_eq!(l_shaped.area_with(mos, AreaModel::Footprint), 12.0);
+    /// assert_eq!(dims.area_with(mos, AreaModel::Footprint), 16.0);
+    /// ```
+    pub fn area_with(&self, mos: Mosaic, model: AreaModel) -> Float {
+        match model {
+            AreaModel::PerimeterOnly => self.area(mos),
+            AreaModel::Footprint if self.footprint.is_some() => {
+                (mos.rows * mos.cols) as Float * self.footprint.unwrap_or_default()
+            }
+            AreaModel::PerCell | AreaModel::Footprint => {
+                (mos.cols as Float * (self.size[0] + self.enc[0] * 2.0))
+                    * (mos.rows as Float * (self.size[1] + self.enc[1] * 2.0))
+            }
+        }
+    }
+
+    /// Calculates the bounding width and height of an array of components.
+    ///
+    /// Enclosure is added once around the whole array under
+    /// [`AreaModel::PerimeterOnly`], and around every instance otherwise
+    /// (footprints have no shape, so [`AreaModel::Footprint`] uses the
+    /// per-cell bounding box).
+    ///
+    /// # Arguments
+    /// * `mos` - Arrangement of the components
+    /// * `model` - How enclosure is accounted for
+    ///
+    /// # Returns
+    /// `[width, height]` in micrometers including enclosures
+    ///
+    /// # Examples
+    /// ```
+    /// use memea::db::{AreaModel, Dims};
+    /// use memea::Mosaic;
+    ///
+    /// let dims = Dims::from(1.0, 2.0, 0.5, 0.25);
+    /// let mos = Mosaic::array(4, 2);
+    /// assert_eq!(dims.extent(mos, AreaModel::PerimeterOnly), [3.0, 8.5]);
+    /// assert_eq!(dims.extent(mos, AreaModel::PerCell), [4.0, 10.0]);
+    /// ```
+    pub fn extent(&self, mos: Mosaic, model: AreaModel) -> [Float; 2] {
+        match model {
+            AreaModel::PerimeterOnly => {
+                let [w, h] = self.span(mos);
+                [w + self.enc[0] * 2.0, h + self.enc[1] * 2.0]
+            }
+            AreaModel::PerCell | AreaModel::Footprint => [
+                mos.cols as Float * (self.size[0] + self.enc[0] * 2.0),
+                mos.rows as Float * (self.size[1] + self.enc[1] * 2.0),
+            ],
+        }
+    }
+
+    /// Returns a copy rotated by 90°.
+    ///
+    /// Width and height are swapped, as are the horizontal and vertical
+    /// enclosures; the footprint is unchanged.
+    ///
+    /// # Returns
+    /// A `Dims` for the same component turned on its side
+    ///
+    /// # Examples
+    /// ```
+    /// use memea::db::{AreaModel, Dims};
+    /// use memea::Mosaic;
+    ///
+    /// // Tall cell in a 4-row by 2-column array
+    /// let tall = Dims::from(1.0, 2.0, 0.5, 0.0);
+    /// let wide = tall.transposed();
+    /// assert_eq!(wide.size, [2.0, 1.0]);
+    /// assert_eq!(wide.enc, [0.0, 0.5]);
+    ///
+    /// let mos = Mosaic::array(4, 2);
+    /// assert_eq!(tall.span(mos), [2.0, 8.0]);
+    /// assert_eq!(wide.span(mos), [4.0, 4.0]);
+    ///
+    /// // Per-cell area does not depend on orientation
+    /// assert_eq!(
+    ///     tall.area_with(mos, AreaModel::PerCell),
+    ///     wide.area_with(mos, AreaModel::PerCell)
+    /// );
+    /// ```
+    pub fn transposed(&self) -> Dims {
+        Dims {
+            size: [self.size[1], self.size[0]],
+            enc: [self.enc[1], self.enc[0]],
+            footprint: self.footprint,
+            pin_area: self.pin_area,
+            obs_area: self.obs_area,
+        }
+    }
+
+    /// Returns a copy with adjusted enclosures.
+    ///
+    /// Each enclosure is first multiplied by `scale`, then `add` is added;
+    /// a negative `add` shrinks enclosures down to zero but never below. The
+    /// measured footprint, if any, is left unchanged.
+    ///
+    /// # Arguments
+    /// * `scale` - Factor applied to each enclosure
+    /// * `add` - Distance in micrometers added to each enclosure side
+    ///
+    /// # Returns
+    /// A `Dims` with the same size and adjusted enclosures
+    ///
+    /// # Examples
+    /// ```
+    /// use memea::db::Dims;
+    ///
+    /// let dims = Dims::from(1.0, 2.0, 0.5, 1.0).adjust_enc(2.0, 0.25);
+    /// assert_eq!(dims.size, [1.0, 2.0]);
+    /// assert_eq!(dims.enc, [1.25, 2.25]);
+    ///
+    /// let dims = Dims::from(1.0, 2.0, 0.5, 1.0).adjust_enc(1.0, -0.75);
+    /// assert_eq!(dims.enc, [0.0, 0.25]);
+    /// ```
+    pub fn adjust_enc(&self, scale: Float, add: Float) -> Dims {
+        Dims {
+            size: self.size,
+            enc: self.enc.map(|e| (e * scale + add).max(0.0)),
+            footprint: self.footprint,
+            pin_area: self.pin_area,
+            obs_area: self.obs_area,
+        }
+    }
+
+    /// Checks whether one dimension is an integer multiple of a pitch.
+    ///
+    /// # Arguments
+    /// * `axis` - Dimension to check (0 for width, 1 for height)
+    /// * `pitch` - Pitch in micrometers
+    ///
+    /// # Returns
+    /// `true` if the dimension is within tolerance of `k * pitch` for some `k >= 1`
+    ///
+    /// # Examples
+    /// ```
+    /// use memea::db::Dims;
+    ///
+    /// let dims = Dims::from(0.5, 0.872, 0.0, 0.0);
+    /// assert!(dims.matches_pitch(1, 0.436));
+    /// assert!(!dims.matches_pitch(0, 0.436));
+    /// ```
+    pub fn matches_pitch(&self, axis: usize, pitch: Float) -> bool {
+        let dim = self.size[axis];
+        let k = (dim / pitch).round().max(1.0);
+        approx_eq(dim, k * pitch)
+    }
+
+    /// Scales every dimension and enclosure by a linear factor.
+    ///
+    /// Because area is quadratic in length, the area of the result is
+    /// `linear²` times the original area.
+    ///
+    /// # Arguments
+    /// * `linear` - Linear shrink (or growth) factor
+    ///
+    /// # Returns
+    /// A `Dims` with size and enclosures multiplied by `linear`, and the
+    /// footprint, pin and obstruction areas by `linear²`
+    ///
+    /// # Examples
+    /// ```
+    /// use memea::db::Dims;
+    ///
+    /// let dims = Dims::from(4.0, 2.0, 0.5, 0.25);
+    /// let half = dims.scaled(0.5);
+    /// assert_eq!(half.size, [2.0, 1.0]);
+    /// assert_eq!(half.enc, [0.25, 0.125]);
+    ///
+    /// let mos = memea::Mosaic::array(8, 16);
+    /// assert!((half.area(mos) - 0.25 * dims.area(mos)).abs() < 1e-9);
+    /// ```
+    pub fn scaled(&self, linear: Float) -> Dims {
+        Dims {
+            size: self.size.map(|x| x * linear),
+            enc: self.enc.map(|e| e * linear),
+            footprint: self.footprint.map(|a| a * linear * linear),
+            pin_area: self.pin_area.map(|a| a * linear * linear),
+            obs_area: self.obs_area.map(|a| a * linear * linear),
+        }
     }
 
     /// Prints the dimensions in a human-readable format.
@@ -97,28 +409,152 @@ impl Dims {
             "Enclosure..: {:.4} (horizontal) by {:.4} (vertical)",
             self.enc[0], self.enc[1]
         );
+        if let Some(area) = self.footprint {
+            println!("Footprint..: {area:.4} μm²");
+        }
+        if let Some(area) = self.pin_area {
+            println!("Pin area...: {area:.4} μm²");
+        }
+        if let Some(area) = self.obs_area {
+            println!("OBS area...: {area:.4} μm²");
+        }
+    }
+}
+
+// Cell parameters compare equal within a small relative tolerance, so that
+// values surviving a save/load round trip still match their originals. The
+// descriptive `note` and `source` fields and the reported pin and obstruction
+// areas are not parameters and are ignored.
+
+impl PartialEq for Dims {
+    fn eq(&self, other: &Self) -> bool {
+        self.size
+            .iter()
+            .zip(other.size)
+            .all(|(a, b)| approx_eq(*a, b))
+            && self
+                .enc
+                .iter()
+                .zip(other.enc)
+                .all(|(a, b)| approx_eq(*a, b))
+            && approx_eq_opt(self.footprint, other.footprint)
+    }
+}
+
+impl PartialEq for Core {
+    fn eq(&self, other: &Self) -> bool {
+        approx_eq(self.dx_wl, other.dx_wl)
+            && approx_eq(self.dx_bl, other.dx_bl)
+            && approx_eq_opt(self.wl_pitch, other.wl_pitch)
+            && approx_eq_opt(self.bl_pitch, other.bl_pitch)
+            && approx_eq_opt(self.leakage, other.leakage)
+            && self.dims == other.dims
     }
 }
 
+impl PartialEq for Logic {
+    fn eq(&self, other: &Self) -> bool {
+        approx_eq(self.dx, other.dx)
+            && self.bits == other.bits
+            && approx_eq(self.fs, other.fs)
+            && approx_eq_opt(self.leakage, other.leakage)
+            && self.dims == other.dims
+    }
+}
+
+impl PartialEq for Switch {
+    fn eq(&self, other: &Self) -> bool {
+        approx_eq(self.dx, other.dx)
+            && approx_eq(self.voltage[0], other.voltage[0])
+            && approx_eq(self.voltage[1], other.voltage[1])
+            && approx_eq_opt(self.leakage, other.leakage)
+            && self.dims == other.dims
+    }
+}
+
+impl PartialEq for SenseAmp {
+    fn eq(&self, other: &Self) -> bool {
+        approx_eq(self.dx, other.dx)
+            && approx_eq(self.fs, other.fs)
+            && approx_eq_opt(self.leakage, other.leakage)
+            && self.dims == other.dims
+    }
+}
+
+impl PartialEq for ADC {
+    fn eq(&self, other: &Self) -> bool {
+        approx_eq(self.enob, other.enob)
+            && approx_eq(self.fs, other.fs)
+            && approx_eq_opt(self.fs_derate, other.fs_derate)
+            && approx_eq_opt(self.vdd_nom, other.vdd_nom)
+            && approx_eq_opt(self.leakage, other.leakage)
+            && self.dims == other.dims
+    }
+}
+
+/// Strategy for accounting for enclosure when computing array area.
+///
+/// On the command line the models are written in kebab case
+/// (`perimeter-only`), but their variant names (`PerimeterOnly`) are
+/// accepted too.
+///
+/// # Examples
+/// ```
+/// use clap::ValueEnum;
+/// use memea::db::AreaModel;
+///
+/// assert_eq!(AreaModel::from_str("per-cell", false), Ok(AreaModel::PerCell));
+/// assert_eq!(AreaModel::from_str("PerCell", false), Ok(AreaModel::PerCell));
+/// assert_eq!(AreaModel::from_str("PerimeterOnly", false), Ok(AreaModel::PerimeterOnly));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum AreaModel {
+    /// Enclosure is counted once around the whole array (default).
+    #[default]
+    #[value(alias = "PerimeterOnly")]
+    PerimeterOnly,
+    /// Every instance in the array carries its own enclosure.
+    #[value(alias = "PerCell")]
+    PerCell,
+    /// Every instance occupies its measured GDS footprint (per-cell if unmeasured).
+    #[value(alias = "Footprint")]
+    Footprint,
+}
+
 /// Memory core cell parameters.
 ///
 /// Represents the electrical and physical characteristics of a memory core cell,
 /// including drive strengths for wordlines and bitlines.
-#[derive(Debug, Deserialize, Serialize, Clone, Copy)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Core {
     /// Requred wordline drive strength
     pub dx_wl: Float,
     /// Required bitline drive strength
     pub dx_bl: Float,
+    /// Row pitch in μm that wordline peripherals should match
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wl_pitch: Option<Float>,
+    /// Column pitch in μm that bitline peripherals should match
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bl_pitch: Option<Float>,
     /// Physical dimensions of the core cell
     pub dims: Dims,
+    /// Leakage power of one instance in watts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub leakage: Option<Float>,
+    /// Free-form note about the cell
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// Provenance of the cell data (e.g. foundry and kit revision)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
 }
 
 /// Logic block parameters.
 ///
 /// Represents logic components such as decoders and control circuits with
 /// their electrical and timing characteristics.
-#[derive(Debug, Deserialize, Serialize, Clone, Copy)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Logic {
     /// Drive strength of the logic block
     pub dx: Float,
@@ -128,12 +564,21 @@ pub struct Logic {
     pub fs: Float,
     /// Physical dimensions of the logic block
     pub dims: Dims,
+    /// Leakage power of one instance in watts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub leakage: Option<Float>,
+    /// Free-form note about the cell
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// Provenance of the cell data (e.g. foundry and kit revision)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
 }
 
 /// Switch component parameters.
 ///
 /// Represents switching elements with their drive capability and voltage range.
-#[derive(Debug, Deserialize, Serialize, Clone, Copy)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Switch {
     /// Drive strength of the switch
     pub dx: Float,
@@ -141,26 +586,225 @@ pub struct Switch {
     pub voltage: [Float; 2],
     /// Physical dimensions of the switch
     pub dims: Dims,
+    /// Leakage power of one instance in watts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub leakage: Option<Float>,
+    /// Free-form note about the cell
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// Provenance of the cell data (e.g. foundry and kit revision)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+impl Switch {
+    /// Checks whether the switch can pass a voltage.
+    ///
+    /// The voltage may lie up to `tol` volts outside `voltage`, so that
+    /// requests at a bound survive float rounding.
+    ///
+    /// # Arguments
+    /// * `voltage` - Requested voltage in volts
+    /// * `tol` - Allowed slack beyond either bound in volts
+    ///
+    /// # Returns
+    /// `true` if `voltage` is within the switch range widened by `tol`
+    ///
+    /// # Examples
+    /// ```
+    /// use memea::db::{Dims, Switch};
+    ///
+    /// let sw = Switch { dx: 4.0, voltage: [0.0, 1.8], dims: Dims::new(), leakage: None, note: None, source: None };
+    /// assert!(sw.covers(1.8, 0.0));
+    /// assert!(!sw.covers(1.8001, 0.0));
+    /// assert!(sw.covers(1.8001, 1e-3));
+    /// assert!(sw.covers(-0.0005, 1e-3));
+    /// assert!(!sw.covers(1.81, 1e-3));
+    /// ```
+    pub fn covers(&self, voltage: Float, tol: Float) -> bool {
+        (self.voltage[0] - tol..=self.voltage[1] + tol).contains(&voltage)
+    }
+}
+
+/// Bitline sense amplifier parameters.
+///
+/// Represents sense amplifiers with their drive capability and maximum
+/// sensing rate.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct SenseAmp {
+    /// Drive strength of the sense amplifier
+    pub dx: Float,
+    /// Maximum sensing rate in Hz
+    pub fs: Float,
+    /// Physical dimensions of the sense amplifier
+    pub dims: Dims,
+    /// Leakage power of one instance in watts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub leakage: Option<Float>,
+    /// Free-form note about the cell
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// Provenance of the cell data (e.g. foundry and kit revision)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
 }
 
 /// Analog-to-Digital Converter (ADC) parameters.
 ///
 /// Represents ADC components with their resolution and sampling characteristics.
-#[derive(Debug, Deserialize, Serialize, Clone, Copy)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct ADC {
     /// Resolution as effective number of bits
     pub enob: Float,
     /// Maximum sampling frequency in Hz
     pub fs: Float,
+    /// Fractional loss of `fs` per 100 °C above 25 °C
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fs_derate: Option<Float>,
+    /// Supply voltage at which `fs` was characterized
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vdd_nom: Option<Float>,
     /// Physical dimensions of the ADC
     pub dims: Dims,
+    /// Leakage power of one instance in watts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub leakage: Option<Float>,
+    /// Free-form note about the cell
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// Provenance of the cell data (e.g. foundry and kit revision)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+impl ADC {
+    /// Maximum sampling frequency derated to an operating point.
+    ///
+    /// See [`derate_fs`] for the derating model.
+    pub fn fs_at(&self, op: OperatingPoint) -> Float {
+        derate_fs(self.fs, self.fs_derate, self.vdd_nom, op)
+    }
+}
+
+/// Temperature in °C at which cell frequencies are assumed to be characterized.
+const NOMINAL_TEMP: Float = 25.0;
+
+/// Supply voltage and temperature at which a configuration operates.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct OperatingPoint {
+    /// Operating supply voltage in volts
+    pub vdd: Option<Float>,
+    /// Operating temperature in °C
+    pub temp: Option<Float>,
+}
+
+/// Derates a maximum frequency to an operating point.
+///
+/// The model is linear in both temperature and supply:
+///
+/// * above 25 °C, `fs` drops by `fs_derate` (as a fraction) per 100 °C
+/// * below `vdd_nom`, `fs` scales in proportion to `vdd / vdd_nom`
+///
+/// Each term only applies when both the cell and the operating point provide
+/// the data it needs, so cells without derating data keep their nominal `fs`.
+/// The result is never negative.
+///
+/// # Arguments
+/// * `fs` - Nominal maximum frequency in Hz
+/// * `fs_derate` - Fractional frequency loss per 100 °C above 25 °C
+/// * `vdd_nom` - Supply voltage at which `fs` was characterized
+/// * `op` - Operating point to derate to
+///
+/// # Returns
+/// Derated maximum frequency in Hz
+///
+/// # Examples
+/// ```
+/// use memea::db::{derate_fs, OperatingPoint};
+///
+/// let hot = OperatingPoint { vdd: Some(0.9), temp: Some(125.0) };
+/// assert_eq!(derate_fs(1e9, None, None, hot), 1e9);
+/// assert_eq!(derate_fs(1e9, Some(0.2), None, hot), 0.8e9);
+/// assert_eq!(derate_fs(1e9, Some(0.2), Some(1.8), hot), 0.4e9);
+/// ```
+pub fn derate_fs(
+    fs: Float,
+    fs_derate: Option<Float>,
+    vdd_nom: Option<Float>,
+    op: OperatingPoint,
+) -> Float {
+    let thermal = match (fs_derate, op.temp) {
+        (Some(d), Some(t)) => 1.0 - d * (t - NOMINAL_TEMP).max(0.0) / 100.0,
+        _ => 1.0,
+    };
+
+    let supply = match (vdd_nom, op.vdd) {
+        (Some(nom), Some(v)) if nom > 0.0 => (v / nom).min(1.0),
+        _ => 1.0,
+    };
+
+    (fs * thermal * supply).max(0.0)
+}
+
+/// Drive strength multipliers a database may set for every run.
+///
+/// Unset values fall back to command-line flags, then to the built-in
+/// defaults ([`crate::tabulate::WELL_SCALE`], [`crate::tabulate::LOGIC_SCALE`]).
+///
+/// # Examples
+/// ```
+/// use memea::db::Database;
+///
+/// let db: Database = serde_yaml::from_str("scales: {well: 0.3}").unwrap();
+/// assert_eq!(db.scales.well, Some(0.3));
+/// assert_eq!(db.scales.logic, None);
+/// assert!(db.scales.check().is_ok());
+///
+/// let db: Database = serde_yaml::from_str("scales: {logic: 0}").unwrap();
+/// assert!(db.scales.check().is_err());
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, Serialize)]
+pub struct Scales {
+    /// Fraction of the array's drive strength well switches must provide
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub well: Option<Float>,
+    /// Fraction of a peripheral's drive strength its logic must provide
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logic: Option<Float>,
+}
+
+impl Scales {
+    /// Whether neither multiplier is set.
+    pub fn is_unset(&self) -> bool {
+        self.well.is_none() && self.logic.is_none()
+    }
+
+    /// Rejects multipliers that are not positive and finite.
+    pub fn check(&self) -> Result<(), DBError> {
+        for (key, value) in [("well", self.well), ("logic", self.logic)] {
+            if let Some(v) = value.filter(|v| !(*v > 0.0 && v.is_finite())) {
+                return Err(DBError::Invalid(format!(
+                    "scales.{key} must be positive and finite, got {v}"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Combines two sets of multipliers, preferring values set in `other`.
+    pub fn overridden_by(self, other: Scales) -> Scales {
+        Scales {
+            well: other.well.or(self.well),
+            logic: other.logic.or(self.logic),
+        }
+    }
 }
 
 /// Component database containing all available peripheral elements.
 ///
 /// The database stores collections of different component types (core cells,
-/// logic blocks, switches, and ADCs) indexed by name. It supports serialization
-/// to and from YAML and JSON formats.
+/// logic blocks, switches, ADCs, and sense amplifiers) indexed by name. It supports serialization
+/// to and from YAML and JSON formats; collections missing from a file are empty.
 ///
 /// # Examples
 /// ```no_run
@@ -169,14 +813,15 @@ pub struct ADC {
 ///
 /// // Load database from file
 /// let db_path = PathBuf::from("components.yaml");
-/// let db = build_db(&db_path).expect("Failed to load database");
+/// let db = build_db(&db_path, false).expect("Failed to load database");
 ///
 /// // Access components
 /// if let Some(core_cell) = db.core.get("sram_6t") {
 ///     println!("Found core cell with WL drive: {}", core_cell.dx_wl);
 /// }
 /// ```
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
 pub struct Database {
     /// Collection of memory core cells indexed by name.
     pub core: HashMap<String, Core>,
@@ -186,6 +831,11 @@ pub struct Database {
     pub switch: HashMap<String, Switch>,
     /// Collection of ADC components indexed by name.
     pub adc: HashMap<String, ADC>,
+    /// Collection of bitline sense amplifiers indexed by name.
+    pub sense: HashMap<String, SenseAmp>,
+    /// Drive strength multipliers overriding the built-in defaults.
+    #[serde(skip_serializing_if = "Scales::is_unset")]
+    pub scales: Scales,
 }
 
 /// Prompts the user for input and parses it to the specified type.
@@ -251,6 +901,8 @@ impl Database {
             logic: HashMap::new(),
             switch: HashMap::new(),
             adc: HashMap::new(),
+            sense: HashMap::new(),
+            scales: Scales::default(),
         }
     }
 
@@ -263,7 +915,16 @@ impl Database {
         let enob: Float = prompt("Bits");
         let fs: f32 = prompt("Sampling rate");
 
-        let adc = ADC { enob, fs, dims };
+        let adc = ADC {
+            enob,
+            fs,
+            fs_derate: None,
+            vdd_nom: None,
+            dims,
+            leakage: None,
+            note: None,
+            source: None,
+        };
         self.adc.insert(name.to_string(), adc);
     }
 
@@ -276,7 +937,16 @@ impl Database {
         let dx_wl: f32 = prompt::<f32>("WL drive strength");
         let dx_bl: f32 = prompt::<f32>("BL drive strength");
 
-        let core = Core { dx_wl, dx_bl, dims };
+        let core = Core {
+            dx_wl,
+            dx_bl,
+            wl_pitch: None,
+            bl_pitch: None,
+            dims,
+            leakage: None,
+            note: None,
+            source: None,
+        };
         self.core.insert(name.to_string(), core);
     }
 
@@ -290,7 +960,15 @@ impl Database {
         let bits: usize = prompt::<usize>("Decoding bits");
         let fs: f32 = prompt::<f32>("Sampling rate");
 
-        let logic = Logic { dx, bits, fs, dims };
+        let logic = Logic {
+            dx,
+            bits,
+            fs,
+            dims,
+            leakage: None,
+            note: None,
+            source: None,
+        };
         self.logic.insert(name.to_string(), logic);
     }
 
@@ -308,14 +986,238 @@ impl Database {
             dx,
             voltage: [vmin, vmax],
             dims,
+            leakage: None,
+            note: None,
+            source: None,
         };
         self.switch.insert(name.to_string(), switch);
     }
 
+    /// Adds a new sense amplifier to the database via interactive prompts.
+    ///
+    /// # Arguments
+    /// * `name` - Name identifier for the sense amplifier
+    /// * `dims` - Physical dimensions of the sense amplifier
+    pub fn add_sense(&mut self, name: &str, dims: Dims) {
+        let dx: f32 = prompt::<f32>("Drive strength");
+        let fs: f32 = prompt::<f32>("Sensing rate");
+
+        let sense = SenseAmp {
+            dx,
+            fs,
+            dims,
+            leakage: None,
+            note: None,
+            source: None,
+        };
+        self.sense.insert(name.to_string(), sense);
+    }
+
+    /// Adds a cell of the given type via interactive prompts.
+    ///
+    /// # Arguments
+    /// * `celltype` - Type of the cell to add
+    /// * `name` - Name identifier for the cell
+    /// * `dims` - Physical dimensions of the cell
+    pub fn add_prompted(&mut self, celltype: CellType, name: &str, dims: Dims) {
+        match celltype {
+            CellType::Core => self.add_core(name, dims),
+            CellType::Logic => self.add_logic(name, dims),
+            CellType::Switch => self.add_switch(name, dims),
+            CellType::ADC => self.add_adc(name, dims),
+            CellType::SenseAmp => self.add_sense(name, dims),
+        }
+    }
+
+    /// Returns whether a cell of the given type and name exists.
+    pub fn contains(&self, celltype: CellType, name: &str) -> bool {
+        match celltype {
+            CellType::Core => self.core.contains_key(name),
+            CellType::Logic => self.logic.contains_key(name),
+            CellType::Switch => self.switch.contains_key(name),
+            CellType::ADC => self.adc.contains_key(name),
+            CellType::SenseAmp => self.sense.contains_key(name),
+        }
+    }
+
+    /// Edits the database through an interactive menu.
+    ///
+    /// The menu repeatedly offers to add (or overwrite) a cell, remove a cell,
+    /// list the cells of one type (or `all`), save, or quit. Removing a cell
+    /// that does not exist only warns. Quitting with unsaved changes asks for
+    /// confirmation first. Entered names are normalized without case folding
+    /// (see [`normalize_name`]), so they match a database loaded with
+    /// `fold_case` unset, as it must be for a database that is written back.
+    ///
+    /// # Returns
+    /// * `Ok(true)` - The user chose to save the edited database
+    /// * `Ok(false)` - The user quit; the caller should discard the changes
+    /// * `Err(MemeaError)` - Error during user interaction
+    pub fn edit_interactive(&mut self) -> Result<bool, MemeaError> {
+        let mut changed = false;
+
+        loop {
+            println!("\n{}", crate::bar(Some("Edit database"), '-'));
+            // Read the action directly so a closed input aborts the session
+            let action: String = Input::new()
+                .with_prompt("add/remove/list/save/quit")
+                .interact_text()?;
+
+            match action.trim().to_lowercase().as_str() {
+                "a" | "add" => {
+                    let celltype: CellType = prompt("Cell type");
+                    let name = normalize_name(&prompt::<String>("Cell name"), false);
+                    if self.contains(celltype, &name)
+                        && !query(
+                            &format!("{celltype} '{name}' exists. Overwrite?"),
+                            true,
+                            QueryDefault::No,
+                        )?
+                    {
+                        continue;
+                    }
+
+                    let dims = Dims::from(
+                        prompt("Width (μm)"),
+                        prompt("Height (μm)"),
+                        prompt("Horizontal enc (μm)"),
+                        prompt("Vertical enc (μm)"),
+                    );
+                    self.add_prompted(celltype, &name, dims);
+                    changed = true;
+                }
+                "r" | "rm" | "remove" => {
+                    let celltype: CellType = prompt("Cell type");
+                    let name = normalize_name(&prompt::<String>("Cell name"), false);
+                    match self.remove(celltype, &name) {
+                        true => changed = true,
+                        false => warnln!("No {} named '{}' to remove", celltype, name),
+                    }
+                }
+                "l" | "ls" | "list" => {
+                    let filter: String = prompt("Cell type (or all)");
+                    let filter = match filter.trim().to_lowercase().as_str() {
+                        "" | "all" => None,
+                        other => match other.parse::<CellType>() {
+                            Ok(t) => Some(t),
+                            Err(e) => {
+                                errorln!("{}", e);
+                                continue;
+                            }
+                        },
+                    };
+                    let cells: Vec<CellSummary> = self
+                        .summaries()
+                        .into_iter()
+                        .filter(|c| filter.is_none_or(|t| c.celltype == t))
+                        .collect();
+                    print!("{}", crate::export::fmt_cells(&cells, false));
+                }
+                "s" | "save" => return Ok(true),
+                "q" | "quit" => {
+                    if !changed || query("Discard unsaved changes?", true, QueryDefault::No)? {
+                        return Ok(false);
+                    }
+                }
+                other => errorln!(
+                    "Unknown action '{}' (expected add, remove, list, save or quit)",
+                    other
+                ),
+            }
+        }
+    }
+
+    /// Adds a cell of the given type with every electrical parameter set to zero.
+    ///
+    /// Used when building a database non-interactively; drive strengths, bit
+    /// counts, sampling rates and voltage ranges are left for the user to fill
+    /// in afterwards.
+    ///
+    /// # Arguments
+    /// * `celltype` - Type of the cell to add
+    /// * `name` - Name identifier for the cell
+    /// * `dims` - Physical dimensions of the cell
+    ///
+    /// # Examples
+    /// ```
+    /// use memea::db::{CellType, Database, Dims};
+    ///
+    /// let mut db = Database::new();
+    /// db.add_default(CellType::Logic, "dec", Dims::from(2.0, 1.0, 0.0, 0.0));
+    /// assert_eq!((db.logic["dec"].dx, db.logic["dec"].bits, db.logic["dec"].fs), (0.0, 0, 0.0));
+    /// ```
+    pub fn add_default(&mut self, celltype: CellType, name: &str, dims: Dims) {
+        let name = name.to_string();
+        match celltype {
+            CellType::Core => {
+                let core = Core {
+                    dx_wl: 0.0,
+                    dx_bl: 0.0,
+                    wl_pitch: None,
+                    bl_pitch: None,
+                    dims,
+                    leakage: None,
+                    note: None,
+                    source: None,
+                };
+                self.core.insert(name, core);
+            }
+            CellType::Logic => {
+                let logic = Logic {
+                    dx: 0.0,
+                    bits: 0,
+                    fs: 0.0,
+                    dims,
+                    leakage: None,
+                    note: None,
+                    source: None,
+                };
+                self.logic.insert(name, logic);
+            }
+            CellType::Switch => {
+                let switch = Switch {
+                    dx: 0.0,
+                    voltage: [0.0, 0.0],
+                    dims,
+                    leakage: None,
+                    note: None,
+                    source: None,
+                };
+                self.switch.insert(name, switch);
+            }
+            CellType::ADC => {
+                let adc = ADC {
+                    enob: 0.0,
+                    fs: 0.0,
+                    fs_derate: None,
+                    vdd_nom: None,
+                    dims,
+                    leakage: None,
+                    note: None,
+                    source: None,
+                };
+                self.adc.insert(name, adc);
+            }
+            CellType::SenseAmp => {
+                let sense = SenseAmp {
+                    dx: 0.0,
+                    fs: 0.0,
+                    dims,
+                    leakage: None,
+                    note: None,
+                    source: None,
+                };
+                self.sense.insert(name, sense);
+            }
+        }
+    }
+
     /// Saves the database to a file in YAML or JSON format.
     ///
     /// The output format is determined by the file extension (.yaml/.yml for YAML,
-    /// .json for JSON).
+    /// .json for JSON). Both formats write floats in their shortest exact
+    /// representation, so loading the file with [`build_db`] yields an equal
+    /// database.
     ///
     /// # Arguments
     /// * `filename` - Path where the database should be saved
@@ -324,6 +1226,72 @@ impl Database {
     /// # Returns
     /// * `Ok(())` - Database was successfully saved
     /// * `Err(MemeaError)` - File I/O error or unsupported format
+    ///
+    /// # Examples
+    /// ```
+    /// use memea::db::{build_db, Core, Database, Dims, Logic, SenseAmp, Switch, ADC};
+    ///
+    /// let mut db = Database::new();
+    /// let dims = Dims::from(0.363, 0.436, 1.7, 0.1);
+    /// let source = Some("foundry X, rev Y".to_string());
+    /// db.core.insert(
+    ///     "c".into(),
+    ///     Core {
+    ///         dx_wl: 0.24,
+    ///         dx_bl: 0.3,
+    ///         wl_pitch: Some(0.436),
+    ///         bl_pitch: None,
+    ///         dims,
+    ///         leakage: Some(2e-12),
+    ///         note: Some("1T1C".into()),
+    ///         source,
+    ///     },
+    /// );
+    /// db.logic.insert(
+    ///     "l".into(),
+    ///     Logic {
+    ///         dx: 8.0,
+    ///         bits: 2,
+    ///         fs: 1e9,
+    ///         dims,
+    ///         leakage: None,
+    ///         note: None,
+    ///         source: None,
+    ///     },
+    /// );
+    /// db.switch.insert(
+    ///     "s".into(),
+    ///     Switch { dx: 16.0, voltage: [-0.5, 1.3], dims, leakage: None, note: None, source: None },
+    /// );
+    /// db.adc.insert(
+    ///     "a".into(),
+    ///     ADC {
+    ///         enob: 6.2,
+    ///         fs: 150e6,
+    ///         fs_derate: Some(0.15),
+    ///         vdd_nom: Some(0.9),
+    ///         dims,
+    ///         leakage: None,
+    ///         note: None,
+    ///         source: None,
+    ///     },
+    /// );
+    /// db.sense.insert(
+    ///     "sa".into(),
+    ///     SenseAmp { dx: 2.0, fs: 1e9, dims, leakage: Some(1e-9), note: None, source: None },
+    /// );
+    ///
+    /// for ext in ["yaml", "json"] {
+    ///     let path = std::env::temp_dir().join(format!("memea_roundtrip.{ext}"));
+    ///     db.save(&path, false)?;
+    ///     let loaded = build_db(&path, false)?;
+    ///     assert_eq!(loaded, db);
+    ///     assert_eq!(loaded.core["c"].note, db.core["c"].note);
+    ///     assert_eq!(loaded.core["c"].source, db.core["c"].source);
+    ///     std::fs::remove_file(&path)?;
+    /// }
+    /// # Ok::<(), memea::MemeaError>(())
+    /// ```
     pub fn save(&self, filename: &PathBuf, verbose: bool) -> Result<(), MemeaError> {
         let ext = filename
             .extension()
@@ -343,20 +1311,369 @@ impl Database {
 
         vprintln!(
             verbose,
-            "Wrote {} core cells, {} switches, {} logic cells, and {} ADCs to {:?}",
+            "Wrote {} core cells, {} switches, {} logic cells, {} ADCs, and {} sense amplifiers to {:?}",
             self.core.len(),
             self.switch.len(),
             self.logic.len(),
             self.adc.len(),
+            self.sense.len(),
             filename
         );
 
         Ok(())
     }
+
+    /// Merges another database into this one.
+    ///
+    /// Cells of `other` replace cells of the same type and name in this
+    /// database, with a warning for each replaced cell, and [`Scales`] set in
+    /// `other` replace those set here.
+    ///
+    /// # Arguments
+    /// * `other` - Database whose cells take precedence
+    ///
+    /// # Examples
+    /// ```
+    /// use memea::db::{Database, Dims, Switch};
+    ///
+    /// let sw = Switch { dx: 4.0, voltage: [0.0, 1.8], dims: Dims::new(), leakage: None, note: None, source: None };
+    /// let mut base = Database::new();
+    /// base.switch.insert("a".to_string(), sw.clone());
+    /// base.switch.insert("b".to_string(), sw.clone());
+    ///
+    /// let mut vendor = Database::new();
+    /// vendor.switch.insert("b".to_string(), Switch { dx: 8.0, ..sw.clone() });
+    /// vendor.switch.insert("c".to_string(), sw);
+    ///
+    /// base.merge(vendor);
+    /// assert_eq!(base.switch.len(), 3);
+    /// assert_eq!(base.switch["a"].dx, 4.0);
+    /// assert_eq!(base.switch["b"].dx, 8.0);
+    /// ```
+    pub fn merge(&mut self, other: Database) {
+        // Overriding is always allowed, so the merge cannot fail
+        let _ = self.merge_with(other, |celltype, name| {
+            warnln!(
+                "{} '{}' is defined again; using the later definition",
+                celltype,
+                name
+            );
+            Ok(true)
+        });
+    }
+
+    /// Merges another database into this one, deciding each name collision.
+    ///
+    /// Cells of `other` are visited by type and name; a cell whose name is
+    /// already taken replaces the existing one only if `overwrite` returns
+    /// `true` for it. [`Scales`] set in `other` replace those set here.
+    ///
+    /// # Arguments
+    /// * `other` - Database to merge in
+    /// * `overwrite` - Decides whether a colliding cell replaces the existing one
+    ///
+    /// # Returns
+    /// * `Ok(())` - Databases merged
+    /// * `Err(MemeaError)` - Error returned by `overwrite`
+    ///
+    /// # Examples
+    /// ```
+    /// use memea::db::{Database, Dims, Switch};
+    ///
+    /// let sw = Switch { dx: 4.0, voltage: [0.0, 1.8], dims: Dims::new(), leakage: None, note: None, source: None };
+    /// let mut base = Database::new();
+    /// base.switch.insert("a".to_string(), sw.clone());
+    ///
+    /// let mut new = Database::new();
+    /// new.switch.insert("a".to_string(), Switch { dx: 8.0, ..sw.clone() });
+    /// new.switch.insert("b".to_string(), sw);
+    ///
+    /// base.merge_with(new, |_, _| Ok(false)).unwrap();
+    /// assert_eq!((base.switch.len(), base.switch["a"].dx), (2, 4.0));
+    /// ```
+    pub fn merge_with(
+        &mut self,
+        other: Database,
+        mut overwrite: impl FnMut(CellType, &str) -> Result<bool, MemeaError>,
+    ) -> Result<(), MemeaError> {
+        fn merge_map<T>(
+            into: &mut HashMap<String, T>,
+            from: HashMap<String, T>,
+            celltype: CellType,
+            overwrite: &mut impl FnMut(CellType, &str) -> Result<bool, MemeaError>,
+        ) -> Result<(), MemeaError> {
+            let mut cells: Vec<(String, T)> = from.into_iter().collect();
+            cells.sort_by(|a, b| a.0.cmp(&b.0));
+            for (name, cell) in cells {
+                if into.contains_key(&name) && !overwrite(celltype, &name)? {
+                    continue;
+                }
+                into.insert(name, cell);
+            }
+            Ok(())
+        }
+
+        merge_map(&mut self.core, other.core, CellType::Core, &mut overwrite)?;
+        merge_map(
+            &mut self.logic,
+            other.logic,
+            CellType::Logic,
+            &mut overwrite,
+        )?;
+        merge_map(
+            &mut self.switch,
+            other.switch,
+            CellType::Switch,
+            &mut overwrite,
+        )?;
+        merge_map(&mut self.adc, other.adc, CellType::ADC, &mut overwrite)?;
+        merge_map(
+            &mut self.sense,
+            other.sense,
+            CellType::SenseAmp,
+            &mut overwrite,
+        )?;
+        self.scales = self.scales.overridden_by(other.scales);
+        Ok(())
+    }
+
+    /// Builds a database containing only the given cells.
+    ///
+    /// Names that are not present in this database are ignored.
+    ///
+    /// # Arguments
+    /// * `cells` - Set of (cell type, name) pairs to keep
+    ///
+    /// # Returns
+    /// A new `Database` with copies of the selected cells
+    ///
+    /// # Examples
+    /// ```
+    /// use std::collections::HashSet;
+    /// use memea::db::{CellType, Database, Dims, Switch};
+    ///
+    /// let mut db = Database::new();
+    /// let sw = Switch { dx: 4.0, voltage: [0.0, 1.8], dims: Dims::new(), leakage: None, note: None, source: None };
+    /// db.switch.insert("used".to_string(), sw.clone());
+    /// db.switch.insert("spare".to_string(), sw);
+    ///
+    /// let used = HashSet::from([(CellType::Switch, "used".to_string())]);
+    /// let pruned = db.subset(&used);
+    /// assert!(pruned.switch.contains_key("used"));
+    /// assert_eq!(pruned.switch.len(), 1);
+    /// ```
+    pub fn subset(&self, cells: &HashSet<(CellType, String)>) -> Database {
+        fn keep<T: Clone>(
+            map: &HashMap<String, T>,
+            celltype: CellType,
+            cells: &HashSet<(CellType, String)>,
+        ) -> HashMap<String, T> {
+            map.iter()
+                .filter(|(name, _)| cells.contains(&(celltype, name.to_string())))
+                .map(|(name, cell)| (name.clone(), cell.clone()))
+                .collect()
+        }
+
+        Database {
+            core: keep(&self.core, CellType::Core, cells),
+            logic: keep(&self.logic, CellType::Logic, cells),
+            switch: keep(&self.switch, CellType::Switch, cells),
+            adc: keep(&self.adc, CellType::ADC, cells),
+            sense: keep(&self.sense, CellType::SenseAmp, cells),
+            scales: self.scales,
+        }
+    }
+
+    /// Removes a cell from the database.
+    ///
+    /// # Arguments
+    /// * `celltype` - Type of the cell to remove
+    /// * `name` - Name of the cell to remove
+    ///
+    /// # Returns
+    /// `true` if the cell was present
+    ///
+    /// # Examples
+    /// ```
+    /// use memea::db::{CellType, Database, Dims, Switch};
+    ///
+    /// let mut db = Database::new();
+    /// let sw = Switch { dx: 4.0, voltage: [0.0, 1.8], dims: Dims::new(), leakage: None, note: None, source: None };
+    /// db.switch.insert("sw".to_string(), sw);
+    ///
+    /// assert!(db.remove(CellType::Switch, "sw"));
+    /// assert!(!db.remove(CellType::Switch, "sw"));
+    /// ```
+    pub fn remove(&mut self, celltype: CellType, name: &str) -> bool {
+        match celltype {
+            CellType::Core => self.core.remove(name).is_some(),
+            CellType::Logic => self.logic.remove(name).is_some(),
+            CellType::Switch => self.switch.remove(name).is_some(),
+            CellType::ADC => self.adc.remove(name).is_some(),
+            CellType::SenseAmp => self.sense.remove(name).is_some(),
+        }
+    }
+
+    /// Iterates over every cell in the database regardless of type.
+    ///
+    /// Cells are yielded grouped by type (core, logic, switch, ADC, then sense
+    /// amplifiers); the
+    /// order within each type is unspecified.
+    ///
+    /// # Returns
+    /// Iterator of (cell type, cell name, dimensions) tuples
+    ///
+    /// # Examples
+    /// ```
+    /// use memea::db::{CellType, Database, Dims, Switch};
+    ///
+    /// let mut db = Database::new();
+    /// let dims = Dims::from(2.0, 3.0, 0.0, 0.0);
+    /// let sw = Switch { dx: 4.0, voltage: [0.0, 1.8], dims, leakage: None, note: None, source: None };
+    /// db.switch.insert("sw".to_string(), sw);
+    ///
+    /// let cells: Vec<_> = db.iter_cells().collect();
+    /// assert_eq!(cells.len(), 1);
+    /// assert_eq!(cells[0].0, CellType::Switch);
+    /// assert_eq!(cells[0].1, "sw");
+    /// assert_eq!(cells[0].2.size, [2.0, 3.0]);
+    /// ```
+    pub fn iter_cells(&self) -> impl Iterator<Item = (CellType, &str, &Dims)> {
+        let core = self
+            .core
+            .iter()
+            .map(|(name, c)| (CellType::Core, name.as_str(), &c.dims));
+        let logic = self
+            .logic
+            .iter()
+            .map(|(name, c)| (CellType::Logic, name.as_str(), &c.dims));
+        let switch = self
+            .switch
+            .iter()
+            .map(|(name, c)| (CellType::Switch, name.as_str(), &c.dims));
+        let adc = self
+            .adc
+            .iter()
+            .map(|(name, c)| (CellType::ADC, name.as_str(), &c.dims));
+        let sense = self
+            .sense
+            .iter()
+            .map(|(name, c)| (CellType::SenseAmp, name.as_str(), &c.dims));
+
+        core.chain(logic).chain(switch).chain(adc).chain(sense)
+    }
+
+    /// Summarizes every cell with its dimensions and key parameters.
+    ///
+    /// # Returns
+    /// One [`CellSummary`] per cell, sorted by cell type and then by name
+    ///
+    /// # Examples
+    /// ```
+    /// use memea::db::{Database, Dims, Switch};
+    ///
+    /// let mut db = Database::new();
+    /// let dims = Dims::from(2.0, 3.0, 0.5, 0.0);
+    /// let note = Some("Includes LS".to_string());
+    /// let sw = Switch { dx: 4.0, voltage: [0.0, 1.8], dims, leakage: None, note, source: None };
+    /// db.switch.insert("sw".to_string(), sw);
+    ///
+    /// let cells = db.summaries();
+    /// assert_eq!(cells[0].name, "sw");
+    /// assert_eq!(cells[0].area, dims.area(memea::Mosaic::SINGLE));
+    /// assert_eq!(cells[0].parameters, "dx=4 voltage=[0, 1.8]");
+    /// assert_eq!(cells[0].note.as_deref(), Some("Includes LS"));
+    /// ```
+    pub fn summaries(&self) -> Vec<CellSummary> {
+        let summary = |celltype: CellType,
+                       name: &String,
+                       dims: &Dims,
+                       parameters: String,
+                       note: &Option<String>,
+                       source: &Option<String>| CellSummary {
+            celltype,
+            name: name.clone(),
+            width: dims.size[0],
+            height: dims.size[1],
+            enc_x: dims.enc[0],
+            enc_y: dims.enc[1],
+            area: dims.area(Mosaic::SINGLE),
+            pin_area: dims.pin_area,
+            obs_area: dims.obs_area,
+            parameters,
+            note: note.clone(),
+            source: source.clone(),
+        };
+
+        let mut cells: Vec<CellSummary> = Vec::new();
+        cells.extend(self.core.iter().map(|(name, c)| {
+            let params = format!("dx_wl={} dx_bl={}", c.dx_wl, c.dx_bl);
+            summary(CellType::Core, name, &c.dims, params, &c.note, &c.source)
+        }));
+        cells.extend(self.logic.iter().map(|(name, c)| {
+            let params = format!("dx={} bits={} fs={:e}", c.dx, c.bits, c.fs);
+            summary(CellType::Logic, name, &c.dims, params, &c.note, &c.source)
+        }));
+        cells.extend(self.switch.iter().map(|(name, c)| {
+            let params = format!("dx={} voltage=[{}, {}]", c.dx, c.voltage[0], c.voltage[1]);
+            summary(CellType::Switch, name, &c.dims, params, &c.note, &c.source)
+        }));
+        cells.extend(self.adc.iter().map(|(name, c)| {
+            let params = format!("enob={} fs={:e}", c.enob, c.fs);
+            summary(CellType::ADC, name, &c.dims, params, &c.note, &c.source)
+        }));
+        cells.extend(self.sense.iter().map(|(name, c)| {
+            let params = format!("dx={} fs={:e}", c.dx, c.fs);
+            summary(
+                CellType::SenseAmp,
+                name,
+                &c.dims,
+                params,
+                &c.note,
+                &c.source,
+            )
+        }));
+
+        cells.sort_by(|a, b| {
+            (a.celltype.to_string(), &a.name).cmp(&(b.celltype.to_string(), &b.name))
+        });
+        cells
+    }
+}
+
+/// A database cell with its dimensions and key parameters, as listed by the
+/// `list-cells` subcommand.
+#[derive(Debug, Clone, Serialize)]
+pub struct CellSummary {
+    /// Type of the cell
+    #[serde(rename = "type")]
+    pub celltype: CellType,
+    /// Name of the cell
+    pub name: String,
+    /// Horizontal pitch in μm
+    pub width: Float,
+    /// Vertical pitch in μm
+    pub height: Float,
+    /// Horizontal enclosure in μm
+    pub enc_x: Float,
+    /// Vertical enclosure in μm
+    pub enc_y: Float,
+    /// Area of a single instance, enclosure included, in μm²
+    pub area: Float,
+    /// Total LEF pin shape area of a single instance in μm², if known
+    pub pin_area: Option<Float>,
+    /// Total LEF obstruction area of a single instance in μm², if known
+    pub obs_area: Option<Float>,
+    /// Type-specific parameters used for selection, e.g. `dx=4 bits=2`
+    pub parameters: String,
+    /// Free-form note about the cell, if any
+    pub note: Option<String>,
+    /// Provenance of the cell data, if recorded
+    pub source: Option<String>,
 }
 
 /// Enumeration of component types available in the database.
-#[derive(Hash, Eq, PartialEq, Serialize, Debug)]
+#[derive(Hash, Eq, PartialEq, Ord, PartialOrd, Serialize, Debug, Clone, Copy)]
 pub enum CellType {
     /// Memory core cell type.
     Core,
@@ -366,6 +1683,34 @@ pub enum CellType {
     ADC,
     /// Switch component type.
     Switch,
+    /// Bitline sense amplifier type.
+    SenseAmp,
+}
+
+impl std::str::FromStr for CellType {
+    type Err = DBError;
+
+    /// Parses a cell type name (case-insensitive), accepting the short forms
+    /// `sw`, `log` and `sa`.
+    ///
+    /// # Examples
+    /// ```
+    /// use memea::db::CellType;
+    ///
+    /// assert_eq!("Switch".parse::<CellType>().unwrap(), CellType::Switch);
+    /// assert_eq!("sa".parse::<CellType>().unwrap(), CellType::SenseAmp);
+    /// assert!("cores".parse::<CellType>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "core" => Ok(CellType::Core),
+            "logic" | "log" => Ok(CellType::Logic),
+            "switch" | "sw" => Ok(CellType::Switch),
+            "adc" => Ok(CellType::ADC),
+            "sense" | "senseamp" | "sa" => Ok(CellType::SenseAmp),
+            _ => Err(DBError::CellType(s.to_string())),
+        }
+    }
 }
 
 impl fmt::Display for CellType {
@@ -375,6 +1720,7 @@ impl fmt::Display for CellType {
             CellType::Core => write!(f, "Core"),
             CellType::Logic => write!(f, "Logic"),
             CellType::Switch => write!(f, "Switch"),
+            CellType::SenseAmp => write!(f, "SenseAmp"),
         }
     }
 }
@@ -428,10 +1774,70 @@ pub fn valid_ext(path: &str) -> bool {
     }
 }
 
+/// Normalizes a cell name for lookup.
+///
+/// Surrounding whitespace is always trimmed; the name is additionally folded to
+/// lowercase when `fold_case` is set.
+///
+/// # Arguments
+/// * `name` - Cell name to normalize
+/// * `fold_case` - Whether to fold the name to lowercase
+///
+/// # Returns
+/// The normalized cell name
+///
+/// # Examples
+/// ```
+/// use memea::db::normalize_name;
+///
+/// assert_eq!(normalize_name("  SRAM_6T ", false), "SRAM_6T");
+/// assert_eq!(normalize_name("  SRAM_6T ", true), "sram_6t");
+/// ```
+pub fn normalize_name(name: &str, fold_case: bool) -> String {
+    let name = name.trim();
+    match fold_case {
+        true => name.to_lowercase(),
+        false => name.to_string(),
+    }
+}
+
+/// Normalizes every key of a cell collection, warning on collisions.
+///
+/// When two names normalize to the same key, the later entry (in sorted
+/// original-name order) replaces the earlier one.
+fn normalize_map<T>(
+    map: HashMap<String, T>,
+    celltype: CellType,
+    fold_case: bool,
+) -> HashMap<String, T> {
+    let mut entries: Vec<(String, T)> = map.into_iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut normalized: HashMap<String, T> = HashMap::new();
+    for (name, cell) in entries {
+        let key = normalize_name(&name, fold_case);
+        if normalized.insert(key.clone(), cell).is_some() {
+            warnln!(
+                "{} cell '{}' collides with another cell after normalization to '{}'; keeping the last one",
+                celltype,
+                name,
+                key
+            );
+        }
+    }
+
+    normalized
+}
+
 /// Builds a database by deserializing from a YAML or JSON file.
 ///
+/// Cell names are trimmed of surrounding whitespace on load and, if
+/// `fold_case` is set, folded to lowercase (see [`normalize_name`]).
+/// A leading UTF-8 byte order mark is ignored.
+///
 /// # Arguments
 /// * `filename` - Path to the database file to load
+/// * `fold_case` - Whether to fold cell names to lowercase
 ///
 /// # Returns
 /// * `Ok(Database)` - Successfully loaded database
@@ -443,14 +1849,13 @@ pub fn valid_ext(path: &str) -> bool {
 /// use std::path::PathBuf;
 ///
 /// let db_path = PathBuf::from("my_components.yaml");
-/// match build_db(&db_path) {
+/// match build_db(&db_path, false) {
 ///     Ok(database) => println!("Loaded {} core cells", database.core.len()),
 ///     Err(e) => eprintln!("Failed to load database: {}", e),
 /// }
 /// ```
-pub fn build_db(filename: &PathBuf) -> Result<Database, MemeaError> {
-    let file = fs::File::open(filename)?;
-    let rdr = io::BufReader::new(file);
+pub fn build_db(filename: &path::Path, fold_case: bool) -> Result<Database, MemeaError> {
+    let text = read_text(filename)?;
 
     let ext = filename
         .extension()
@@ -458,13 +1863,661 @@ pub fn build_db(filename: &PathBuf) -> Result<Database, MemeaError> {
         .unwrap_or_default()
         .to_lowercase();
 
-    let db = match ext.as_str() {
-        "yaml" | "yml" => serde_yaml::from_reader(rdr)?,
-        "json" => serde_json::from_reader(rdr)?,
+    let db: Database = match ext.as_str() {
+        "yaml" | "yml" => serde_yaml::from_str(&text)?,
+        "json" => serde_json::from_str(&text)?,
         other => {
             return Err(DBError::FileType(other.to_string()).into());
         }
     };
+    db.scales.check()?;
+
+    Ok(Database {
+        core: normalize_map(db.core, CellType::Core, fold_case),
+        logic: normalize_map(db.logic, CellType::Logic, fold_case),
+        switch: normalize_map(db.switch, CellType::Switch, fold_case),
+        adc: normalize_map(db.adc, CellType::ADC, fold_case),
+        sense: normalize_map(db.sense, CellType::SenseAmp, fold_case),
+        scales: db.scales,
+    })
+}
+
+/// A database file, optionally restricted to a single cell type.
+///
+/// Parsed from `FILE` (load every cell type) or `TYPE=FILE`, where `TYPE` is
+/// one of `core`, `logic`, `switch`, `adc` or `sense` (case-insensitive).
+///
+/// # Examples
+/// ```
+/// use memea::db::{CellType, DbSource};
+///
+/// let plain: DbSource = "data/db.yaml".parse().unwrap();
+/// assert_eq!(plain.scope, None);
+///
+/// let scoped: DbSource = "ADC=vendor/adcs.yaml".parse().unwrap();
+/// assert_eq!(scoped.scope, Some(CellType::ADC));
+/// assert_eq!(scoped.path.to_str(), Some("vendor/adcs.yaml"));
+///
+/// assert!("cores=cores.yaml".parse::<DbSource>().is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct DbSource {
+    /// Only this collection is loaded from the file, if set.
+    pub scope: Option<CellType>,
+    /// Path to the database file.
+    pub path: PathBuf,
+}
+
+impl std::str::FromStr for DbSource {
+    type Err = DBError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Anything that does not look like `word=...` is a plain path
+        let Some((prefix, path)) = s
+            .split_once('=')
+            .filter(|(p, _)| !p.is_empty() && p.chars().all(|c| c.is_ascii_alphabetic()))
+        else {
+            return Ok(DbSource {
+                scope: None,
+                path: PathBuf::from(s),
+            });
+        };
+
+        let scope: CellType = prefix.parse().map_err(|_| {
+            DBError::Source(format!(
+                "unknown cell type '{prefix}' (expected core, logic, switch, adc or sense)"
+            ))
+        })?;
+
+        Ok(DbSource {
+            scope: Some(scope),
+            path: PathBuf::from(path),
+        })
+    }
+}
+
+/// Assembles one database from several, possibly type-scoped, files.
+///
+/// Unscoped sources are loaded first, in order, and merged with
+/// [`Database::merge`]: a cell defined in more than one of them is taken from
+/// the last, with a warning, and so are [`Scales`] set in more than one.
+/// Each scoped source then replaces its whole collection (e.g.
+/// `adc=adcs.yaml` discards ADCs from unscoped files), and only that
+/// collection is read from it. Scoping the same cell type to
+/// two files is an error.
+///
+/// # Arguments
+/// * `sources` - Database files to load
+/// * `fold_case` - Whether to fold cell names to lowercase
+///
+/// # Returns
+/// * `Ok(Database)` - Merged database
+/// * `Err(MemeaError)` - A file could not be loaded or two sources claim one type
+///
+/// # Examples
+/// ```
+/// use memea::db::{build_dbs, DbSource};
+/// use std::fs;
+///
+/// let dir = std::env::temp_dir().join("memea_dbs_doc");
+/// fs::create_dir_all(&dir).unwrap();
+/// let sw = "{dx: 1, voltage: [0, 1], dims: {size: [1, 1], enc: [0, 0]}}";
+/// fs::write(dir.join("base.yaml"), format!("switch: {{a: {sw}}}")).unwrap();
+/// fs::write(dir.join("vendor.yaml"), format!("switch: {{b: {sw}}}")).unwrap();
+///
+/// let base = DbSource { scope: None, path: dir.join("base.yaml") };
+/// let vendor: DbSource = format!("switch={}", dir.join("vendor.yaml").display()).parse().unwrap();
+///
+/// let db = build_dbs(&[base, vendor.clone()], false).unwrap();
+/// assert!(db.switch.contains_key("b") && !db.switch.contains_key("a"));
+///
+/// assert!(build_dbs(&[vendor.clone(), vendor], false).is_err());
+/// ```
+pub fn build_dbs(sources: &[DbSource], fold_case: bool) -> Result<Database, MemeaError> {
+    let mut db = Database::new();
+
+    for source in sources.iter().filter(|s| s.scope.is_none()) {
+        db.merge(build_db(&source.path, fold_case)?);
+    }
+
+    let mut claimed: HashMap<CellType, &PathBuf> = HashMap::new();
+    for source in sources {
+        let Some(scope) = source.scope else {
+            continue;
+        };
+        if let Some(prev) = claimed.insert(scope, &source.path) {
+            return Err(DBError::Source(format!(
+                "{scope} cells sourced from both {prev:?} and {:?}",
+                source.path
+            ))
+            .into());
+        }
+
+        let part = build_db(&source.path, fold_case)?;
+        match scope {
+            CellType::Core => db.core = part.core,
+            CellType::Logic => db.logic = part.logic,
+            CellType::Switch => db.switch = part.switch,
+            CellType::ADC => db.adc = part.adc,
+            CellType::SenseAmp => db.sense = part.sense,
+        }
+    }
 
     Ok(db)
 }
+
+/// Builds a deterministic synthetic database for benchmarks and tests.
+///
+/// Each cell type gets `per_type` cells named `<type><i>` (e.g. `switch0`)
+/// whose drive strengths, voltage ranges, bit counts and sample rates cycle
+/// through realistic ranges, with dimensions growing with capability. The
+/// largest cells of each type cover any requirement of a few-hundred-row
+/// array with voltages up to 4 V, 8-bit logic, and 10-bit, 1 GHz ADCs, as long
+/// as `per_type` is at least 12.
+///
+/// # Arguments
+/// * `per_type` - Number of cells of each type
+///
+/// # Returns
+/// A `Database` with `per_type` core, logic, switch, ADC and sense amplifier cells
+///
+/// # Examples
+/// ```
+/// use memea::config::Config;
+/// use memea::db::synthetic_db;
+/// use memea::tabulate::{tabulate, Options};
+///
+/// let db = synthetic_db(100);
+/// assert_eq!(db.switch.len(), 100);
+///
+/// let config: Config = serde_yaml::from_str(
+///     "n: 256\nm: 128\ncell: core0\nwl: [4, 2.5, 0]\nbl: [1, 0]\nwell: [0, 2]\nbits: 8\nfs: 1e8\nadcs: 32",
+/// )
+/// .unwrap();
+/// let reports = tabulate("bench", &config, &db, &Options::default(), None).unwrap();
+/// assert_eq!(reports.len(), 12);
+/// ```
+pub fn synthetic_db(per_type: usize) -> Database {
+    let mut db = Database::new();
+
+    for i in 0..per_type {
+        let step = (i % 12) as Float;
+        let jitter = 1.0 + (i % 7) as Float * 0.05;
+
+        db.core.insert(
+            format!("core{i}"),
+            Core {
+                dx_wl: 0.5 + step * 0.1,
+                dx_bl: 0.25 + step * 0.05,
+                wl_pitch: None,
+                bl_pitch: None,
+                dims: Dims::from(0.3 * jitter, 0.4 * jitter, 1.0, 1.0),
+                leakage: None,
+                note: None,
+                source: None,
+            },
+        );
+
+        let dx = Float::powf(2.0, step) * jitter;
+        db.switch.insert(
+            format!("switch{i}"),
+            Switch {
+                dx,
+                voltage: [-((i % 3) as Float), 1.0 + (i % 4) as Float],
+                dims: Dims::from(0.5 + dx.sqrt() * 0.2, 0.8 * jitter, 0.2, 0.2),
+                leakage: None,
+                note: None,
+                source: None,
+            },
+        );
+
+        db.logic.insert(
+            format!("logic{i}"),
+            Logic {
+                dx,
+                bits: 1 + i % 8,
+                fs: 1e9,
+                dims: Dims::from(1.0 + (i % 8) as Float + dx.sqrt() * 0.1, 0.9, 0.2, 0.2),
+                leakage: None,
+                note: None,
+                source: None,
+            },
+        );
+
+        db.adc.insert(
+            format!("adc{i}"),
+            ADC {
+                enob: 1.0 + (i % 10) as Float,
+                fs: 1e6 * Float::powi(10.0, (i % 4) as i32) * jitter,
+                fs_derate: None,
+                vdd_nom: None,
+                dims: Dims::from(2.0 + step, 1.0 + (i % 4) as Float, 0.2, 0.2),
+                leakage: None,
+                note: None,
+                source: None,
+            },
+        );
+
+        db.sense.insert(
+            format!("sense{i}"),
+            SenseAmp {
+                dx,
+                fs: 1e6 * Float::powi(10.0, (i % 4) as i32) * jitter,
+                dims: Dims::from(0.5 + dx.sqrt() * 0.2, 1.5 * jitter, 0.2, 0.2),
+                leakage: None,
+                note: None,
+                source: None,
+            },
+        );
+    }
+
+    db
+}
+
+/// Groups the names of cells whose parameters are approximately equal.
+///
+/// Names are visited in sorted order, so both the groups and the names within
+/// them are deterministic. Only groups with more than one name are returned.
+fn group_duplicates<T>(
+    cells: &HashMap<String, T>,
+    eq: impl Fn(&T, &T) -> bool,
+) -> Vec<Vec<String>> {
+    let mut names: Vec<&String> = cells.keys().collect();
+    names.sort();
+
+    let mut grouped = vec![false; names.len()];
+    let mut groups = Vec::new();
+
+    for i in 0..names.len() {
+        if grouped[i] {
+            continue;
+        }
+
+        let mut group = vec![names[i].clone()];
+        for j in (i + 1)..names.len() {
+            if !grouped[j] && eq(&cells[names[i]], &cells[names[j]]) {
+                grouped[j] = true;
+                group.push(names[j].clone());
+            }
+        }
+
+        if group.len() > 1 {
+            groups.push(group);
+        }
+    }
+
+    groups
+}
+
+/// Finds cells of the same type with identical parameters under different names.
+///
+/// Two cells are duplicates when they compare equal, i.e. their dimensions
+/// and electrical parameters match within a small relative tolerance.
+///
+/// # Arguments
+/// * `db` - Database to search
+///
+/// # Returns
+/// Vector of (cell type, names of mutually identical cells) pairs
+///
+/// # Examples
+/// ```
+/// use memea::db::{find_duplicates, CellType, Database, Dims, Switch};
+///
+/// let mut db = Database::new();
+/// let dims = Dims::from(1.0, 1.0, 0.1, 0.1);
+/// let sw = Switch { dx: 4.0, voltage: [0.0, 1.8], dims, leakage: None, note: None, source: None };
+/// db.switch.insert("a".to_string(), sw.clone());
+/// db.switch.insert("b".to_string(), Switch { note: Some("copy of a".into()), ..sw.clone() });
+/// db.switch.insert("c".to_string(), Switch { dx: 8.0, ..sw });
+///
+/// let dups = find_duplicates(&db);
+/// assert_eq!(dups, vec![(CellType::Switch, vec!["a".to_string(), "b".to_string()])]);
+/// ```
+pub fn find_duplicates(db: &Database) -> Vec<(CellType, Vec<String>)> {
+    let mut dups = Vec::new();
+
+    let core = group_duplicates(&db.core, |a, b| a == b);
+    let logic = group_duplicates(&db.logic, |a, b| a == b);
+    let switch = group_duplicates(&db.switch, |a, b| a == b);
+    let adc = group_duplicates(&db.adc, |a, b| a == b);
+    let sense = group_duplicates(&db.sense, |a, b| a == b);
+
+    dups.extend(core.into_iter().map(|g| (CellType::Core, g)));
+    dups.extend(logic.into_iter().map(|g| (CellType::Logic, g)));
+    dups.extend(switch.into_iter().map(|g| (CellType::Switch, g)));
+    dups.extend(adc.into_iter().map(|g| (CellType::ADC, g)));
+    dups.extend(sense.into_iter().map(|g| (CellType::SenseAmp, g)));
+
+    dups
+}
+
+/// How serious a problem found by [`lint`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Suspicious, but tabulation can proceed.
+    Warning,
+    /// Tabulation would fail or produce meaningless results.
+    Error,
+}
+
+/// A problem with one database cell, found by [`lint`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Issue {
+    /// Whether the problem is an error or a warning
+    pub severity: Severity,
+    /// Type of the offending cell
+    pub celltype: CellType,
+    /// Name of the offending cell
+    pub name: String,
+    /// Description of the problem
+    pub message: String,
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} '{}': {}", self.celltype, self.name, self.message)
+    }
+}
+
+/// Lists problems with a cell's dimensions.
+///
+/// Sizes must be positive, enclosures non-negative and footprints positive.
+fn lint_dims(dims: &Dims) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    if !dims.size.iter().all(|v| *v > 0.0 && v.is_finite()) {
+        problems.push(format!(
+            "size [{}, {}] must be positive",
+            dims.size[0], dims.size[1]
+        ));
+    }
+    if !dims.enc.iter().all(|v| *v >= 0.0 && v.is_finite()) {
+        problems.push(format!(
+            "enclosure [{}, {}] must not be negative",
+            dims.enc[0], dims.enc[1]
+        ));
+    }
+    if dims.footprint.is_some_and(|f| !(f > 0.0 && f.is_finite())) {
+        problems.push("footprint must be positive".to_string());
+    }
+
+    problems
+}
+
+/// Checks a database for cells that would break or mislead tabulation.
+///
+/// Errors are reported for non-positive sizes or footprints, negative
+/// enclosures, switches whose voltage range is inverted, and logic, ADCs or
+/// sense amplifiers with a non-positive drive strength, resolution or
+/// sampling rate. Warnings are reported for core cells with a non-positive
+/// drive strength, negative leakage, and groups of identical cells (see
+/// [`find_duplicates`]).
+///
+/// # Arguments
+/// * `db` - Database to check
+///
+/// # Returns
+/// Every problem found, sorted by cell type and name
+///
+/// # Examples
+/// ```
+/// use memea::db::{lint, Database, Dims, Severity, Switch};
+///
+/// let mut db = Database::new();
+/// let sw = Switch {
+///     dx: 4.0,
+///     voltage: [1.8, 0.0],
+///     dims: Dims::from(1.0, 0.0, 0.1, 0.1),
+///     leakage: None,
+///     note: None,
+///     source: None,
+/// };
+/// db.switch.insert("bad".to_string(), sw);
+///
+/// let issues = lint(&db);
+/// assert_eq!(issues.len(), 2);
+/// assert!(issues.iter().all(|i| i.severity == Severity::Error && i.name == "bad"));
+/// assert_eq!(issues[1].to_string(), "Switch 'bad': voltage range [1.8, 0] is inverted");
+/// ```
+pub fn lint(db: &Database) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let mut report = |severity, celltype, name: &str, message: String| {
+        issues.push(Issue {
+            severity,
+            celltype,
+            name: name.to_string(),
+            message,
+        })
+    };
+
+    for (celltype, name, dims) in db.iter_cells() {
+        for problem in lint_dims(dims) {
+            report(Severity::Error, celltype, name, problem);
+        }
+    }
+
+    for (name, c) in &db.core {
+        if !(c.dx_wl > 0.0 && c.dx_bl > 0.0) {
+            let message = format!(
+                "drive strengths dx_wl={} dx_bl={} should be positive",
+                c.dx_wl, c.dx_bl
+            );
+            report(Severity::Warning, CellType::Core, name, message);
+        }
+    }
+    for (name, c) in &db.switch {
+        if c.voltage[0] > c.voltage[1] {
+            let message = format!(
+                "voltage range [{}, {}] is inverted",
+                c.voltage[0], c.voltage[1]
+            );
+            report(Severity::Error, CellType::Switch, name, message);
+        }
+        if c.dx <= 0.0 {
+            let message = format!("drive strength {} must be positive", c.dx);
+            report(Severity::Error, CellType::Switch, name, message);
+        }
+    }
+    for (name, c) in &db.logic {
+        if c.dx <= 0.0 {
+            let message = format!("drive strength {} must be positive", c.dx);
+            report(Severity::Error, CellType::Logic, name, message);
+        }
+        if c.fs <= 0.0 {
+            let message = format!("sampling rate {} must be positive", c.fs);
+            report(Severity::Error, CellType::Logic, name, message);
+        }
+    }
+    for (name, c) in &db.adc {
+        if c.enob <= 0.0 {
+            let message = format!("ENOB {} must be positive", c.enob);
+            report(Severity::Error, CellType::ADC, name, message);
+        }
+        if c.fs <= 0.0 {
+            let message = format!("sampling rate {} must be positive", c.fs);
+            report(Severity::Error, CellType::ADC, name, message);
+        }
+    }
+    for (name, c) in &db.sense {
+        if c.dx <= 0.0 {
+            let message = format!("drive strength {} must be positive", c.dx);
+            report(Severity::Error, CellType::SenseAmp, name, message);
+        }
+        if c.fs <= 0.0 {
+            let message = format!("sensing rate {} must be positive", c.fs);
+            report(Severity::Error, CellType::SenseAmp, name, message);
+        }
+    }
+
+    let leaks = db
+        .core
+        .iter()
+        .map(|(n, c)| (CellType::Core, n, c.leakage))
+        .chain(
+            db.logic
+                .iter()
+                .map(|(n, c)| (CellType::Logic, n, c.leakage)),
+        )
+        .chain(
+            db.switch
+                .iter()
+                .map(|(n, c)| (CellType::Switch, n, c.leakage)),
+        )
+        .chain(db.adc.iter().map(|(n, c)| (CellType::ADC, n, c.leakage)))
+        .chain(
+            db.sense
+                .iter()
+                .map(|(n, c)| (CellType::SenseAmp, n, c.leakage)),
+        );
+    for (celltype, name, leakage) in leaks {
+        if let Some(l) = leakage.filter(|l| *l < 0.0) {
+            report(
+                Severity::Warning,
+                celltype,
+                name,
+                format!("leakage {l} is negative"),
+            );
+        }
+    }
+
+    for (celltype, names) in find_duplicates(db) {
+        let message = format!("identical to {}", names[1..].join(", "));
+        report(Severity::Warning, celltype, &names[0], message);
+    }
+
+    issues
+        .sort_by(|a, b| (a.celltype.to_string(), &a.name).cmp(&(b.celltype.to_string(), &b.name)));
+    issues
+}
+
+/// Returns whether dimensions `a` are no larger than `b` in every direction.
+///
+/// Footprints are only comparable when both cells have one (or neither does).
+fn dims_le(a: &Dims, b: &Dims) -> bool {
+    let footprint = match (a.footprint, b.footprint) {
+        (Some(x), Some(y)) => x <= y,
+        (None, None) => true,
+        _ => false,
+    };
+
+    (0..2).all(|i| a.size[i] <= b.size[i] && a.enc[i] <= b.enc[i]) && footprint
+}
+
+/// Returns whether leakage `a` is no worse than `b`; unknown only matches unknown.
+fn leakage_le(a: Option<Float>, b: Option<Float>) -> bool {
+    match (a, b) {
+        (Some(x), Some(y)) => x <= y,
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+/// Lists the names of cells dominated by another cell of the same map, sorted.
+///
+/// `covers(a, b)` decides whether `a` satisfies every requirement `b` does.
+fn dominated_names<T>(
+    cells: &HashMap<String, T>,
+    key: impl Fn(&T) -> (&Dims, Option<Float>),
+    covers: impl Fn(&T, &T) -> bool,
+) -> Vec<String> {
+    let mut names: Vec<String> = cells
+        .iter()
+        .filter(|(name, b)| {
+            let (b_dims, b_leak) = key(b);
+            cells.iter().any(|(other, a)| {
+                let (a_dims, a_leak) = key(a);
+                other != *name
+                    && dims_le(a_dims, b_dims)
+                    && a_dims != b_dims
+                    && leakage_le(a_leak, b_leak)
+                    && covers(a, b)
+            })
+        })
+        .map(|(name, _)| name.clone())
+        .collect();
+
+    names.sort();
+    names
+}
+
+/// Finds cells that another cell of the same type makes redundant.
+///
+/// A cell is dominated when some other cell is no larger in any dimension
+/// (size, enclosure, and footprint), strictly smaller in at least one, leaks
+/// no more, and is at least as capable on every axis selection uses:
+///
+/// * Switch - drive strength and a voltage range covering the other's
+/// * Logic - drive strength and bit count
+/// * ADC - ENOB and sample rate, with identical derating so this holds at
+///   every operating point
+/// * Sense amplifier - drive strength and sensing rate
+///
+/// Core cells are chosen by name and are never reported. Removing dominated
+/// cells never increases an estimate, but ignores pitch preferences and any
+/// configuration that names a peripheral explicitly.
+///
+/// # Arguments
+/// * `db` - Database to search
+///
+/// # Returns
+/// Vector of (cell type, name) pairs, grouped by type and sorted by name
+///
+/// # Examples
+/// ```
+/// use memea::db::{dominated_cells, CellType, Database, Dims, Switch};
+///
+/// let mut db = Database::new();
+/// let small = Switch {
+///     dx: 4.0,
+///     voltage: [0.0, 1.8],
+///     dims: Dims::from(1.0, 1.0, 0.1, 0.1),
+///     leakage: None,
+///     note: None,
+///     source: None,
+/// };
+/// // Larger and no stronger: dominated by `small`
+/// let big = Switch { dx: 2.0, dims: Dims::from(2.0, 1.0, 0.1, 0.1), ..small.clone() };
+/// // Larger but handles a higher voltage: kept
+/// let hv = Switch { voltage: [0.0, 3.3], dims: Dims::from(2.0, 2.0, 0.1, 0.1), ..small.clone() };
+/// db.switch.insert("small".to_string(), small);
+/// db.switch.insert("big".to_string(), big);
+/// db.switch.insert("hv".to_string(), hv);
+///
+/// assert_eq!(dominated_cells(&db), vec![(CellType::Switch, "big".to_string())]);
+/// ```
+pub fn dominated_cells(db: &Database) -> Vec<(CellType, String)> {
+    let logic = dominated_names(
+        &db.logic,
+        |l| (&l.dims, l.leakage),
+        |a, b| a.dx >= b.dx && a.bits >= b.bits,
+    );
+    let switch = dominated_names(
+        &db.switch,
+        |s| (&s.dims, s.leakage),
+        |a, b| a.dx >= b.dx && a.voltage[0] <= b.voltage[0] && a.voltage[1] >= b.voltage[1],
+    );
+    let adc = dominated_names(
+        &db.adc,
+        |a| (&a.dims, a.leakage),
+        |a, b| {
+            a.enob >= b.enob
+                && a.fs >= b.fs
+                && approx_eq_opt(a.fs_derate, b.fs_derate)
+                && approx_eq_opt(a.vdd_nom, b.vdd_nom)
+        },
+    );
+    let sense = dominated_names(
+        &db.sense,
+        |s| (&s.dims, s.leakage),
+        |a, b| a.dx >= b.dx && a.fs >= b.fs,
+    );
+
+    logic
+        .into_iter()
+        .map(|n| (CellType::Logic, n))
+        .chain(switch.into_iter().map(|n| (CellType::Switch, n)))
+        .chain(adc.into_iter().map(|n| (CellType::ADC, n)))
+        .chain(sense.into_iter().map(|n| (CellType::SenseAmp, n)))
+        .collect()
+}
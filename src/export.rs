@@ -4,15 +4,15 @@
 //! results, including CSV, JSON, YAML, and direct console output. It handles
 //! file creation, overwrite confirmation, and format-specific serialization.
 
-use std::collections::HashMap;
-use std::fs::{metadata, File, OpenOptions};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{create_dir_all, metadata, File, OpenOptions};
 use std::io::{self, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str;
 
-use crate::db::DBError;
+use crate::db::{CellSummary, DBError};
 use crate::tabulate::{Report, Reports};
-use crate::{infoln, query, Float, MemeaError};
+use crate::{infoln, overwrite_enabled, query, terminal_width, warnln, Float, MemeaError};
 
 /// Calculates the total area from a collection of reports.
 ///
@@ -25,15 +25,508 @@ pub fn area(reports: &Reports) -> Float {
     reports.iter().map(|r| r.area).sum()
 }
 
+/// Orders configurations by name so every export is reproducible.
+///
+/// # Arguments
+/// * `reports` - HashMap of configuration names to reports
+///
+/// # Returns
+/// The same reports keyed by configuration name in sorted order
+///
+/// # Examples
+/// ```
+/// use memea::export::sorted;
+/// use std::collections::HashMap;
+///
+/// let reports = HashMap::from([
+///     ("b".to_string(), Vec::new()),
+///     ("a".to_string(), Vec::new()),
+/// ]);
+/// let names: Vec<&str> = sorted(&reports).into_keys().collect();
+/// assert_eq!(names, ["a", "b"]);
+/// ```
+pub fn sorted(reports: &HashMap<String, Reports>) -> BTreeMap<&str, &Reports> {
+    reports.iter().map(|(n, r)| (n.as_str(), r)).collect()
+}
+
+/// Calculates a part's share of a total area in percent.
+///
+/// # Arguments
+/// * `part` - Area of the part
+/// * `total` - Total area
+///
+/// # Returns
+/// `part` as a percentage of `total`, or `0.0` if the total is not positive
+///
+/// # Examples
+/// ```
+/// use memea::export::share;
+///
+/// assert_eq!(share(25.0, 200.0), 12.5);
+/// assert_eq!(share(25.0, 0.0), 0.0);
+/// ```
+pub fn share(part: Float, total: Float) -> Float {
+    match total > 0.0 {
+        true => 100.0 * part / total,
+        false => 0.0,
+    }
+}
+
+/// Key reserved in JSON and YAML exports for the grand total and the
+/// derived values of each configuration (see [`layout`]).
+pub const META_KEY: &str = "meta";
+
+/// Storage capacity of a configuration and its area efficiency.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+pub struct Capacity {
+    /// Number of bits stored (see [`crate::config::Config::capacity`])
+    pub bits: usize,
+    /// Total area per stored bit in μm², if both are positive
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub area_per_bit: Option<Float>,
+}
+
+/// Relates a configuration's capacity to its total area.
+///
+/// # Arguments
+/// * `bits` - Number of bits stored
+/// * `area` - Total area in μm² (copies included)
+///
+/// # Returns
+/// The capacity, with no area per bit if either value is zero
+///
+/// # Examples
+/// ```
+/// use memea::export::capacity;
+///
+/// assert_eq!(capacity(8192, 4096.0).area_per_bit, Some(0.5));
+/// assert_eq!(capacity(8192, 0.0).area_per_bit, None);
+/// assert_eq!(capacity(0, 4096.0).area_per_bit, None);
+/// ```
+pub fn capacity(bits: usize, area: Float) -> Capacity {
+    Capacity {
+        bits,
+        area_per_bit: (bits > 0 && area > 0.0).then(|| area / bits as Float),
+    }
+}
+
+/// Aggregate area across every configuration.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+pub struct GrandTotal {
+    /// Sum of every configuration's total area in μm² (after scaling)
+    pub area: Float,
+    /// Number of configurations summed
+    pub configs: usize,
+}
+
+/// Sums the total area of every configuration.
+///
+/// Areas are summed as reported, so any technology scaling applied during
+/// tabulation carries over.
+///
+/// # Arguments
+/// * `reports` - HashMap of configuration names to reports
+///
+/// # Returns
+/// The combined area and the number of configurations
+///
+/// # Examples
+/// ```
+/// use memea::export::{grand_total, GrandTotal};
+/// use std::collections::HashMap;
+///
+/// let reports = HashMap::from([("a".to_string(), vec![]), ("b".to_string(), vec![])]);
+/// assert_eq!(grand_total(&reports), GrandTotal { area: 0.0, configs: 2 });
+/// ```
+pub fn grand_total(reports: &HashMap<String, Reports>) -> GrandTotal {
+    GrandTotal {
+        area: sorted(reports).values().map(|r| area(r)).sum(),
+        configs: reports.len(),
+    }
+}
+
+/// Calculates the total leakage power from a collection of reports.
+///
+/// Reports without leakage data count as zero.
+///
+/// # Arguments
+/// * `reports` - Collection of reports to sum leakage from
+///
+/// # Returns
+/// Total leakage in watts, or `None` if no report has leakage data
+///
+/// # Examples
+/// ```
+/// use memea::db::CellType;
+/// use memea::export::leakage;
+/// use memea::tabulate::Report;
+///
+/// let report = |leakage| Report {
+///     name: "sw".to_string(),
+///     count: 4,
+///     celltype: CellType::Switch,
+///     loc: "WL".to_string(),
+///     area: 1.0,
+///     copies: 1,
+///     dx_required: None,
+///     dx_selected: None,
+///     leakage,
+///     extent: [0.0, 0.0],
+/// };
+///
+/// assert_eq!(leakage(&vec![report(Some(2e-9)), report(None)]), Some(2e-9));
+/// assert_eq!(leakage(&vec![report(None)]), None);
+/// ```
+pub fn leakage(reports: &Reports) -> Option<Float> {
+    reports
+        .iter()
+        .filter_map(|r| r.leakage)
+        .reduce(|a, b| a + b)
+}
+
+/// Estimated outline of one array and its peripherals.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+pub struct Floorplan {
+    /// Macro width in μm
+    pub width: Float,
+    /// Macro height in μm
+    pub height: Float,
+    /// Width divided by height (0 for an empty macro)
+    pub aspect_ratio: Float,
+}
+
+/// Estimates the macro outline from the extents of its reports.
+///
+/// Placement model: the core array sits in the top-right corner. Wordline
+/// blocks (one column of drivers each) are placed side by side to its left,
+/// and bitline, well and ADC blocks (one row each) are stacked below it. The
+/// corner below the wordline blocks is left empty, so
+///
+/// * width = Σ WL widths + max(array width, widest BL block)
+/// * height = max(array height, tallest WL block) + Σ BL heights
+///
+/// Extents describe a single array; copies are not included.
+///
+/// # Arguments
+/// * `reports` - Reports of one configuration
+///
+/// # Returns
+/// Estimated macro width, height and aspect ratio
+///
+/// # Examples
+/// ```
+/// use memea::db::CellType;
+/// use memea::export::floorplan;
+/// use memea::tabulate::Report;
+///
+/// let report = |loc: &str, extent| Report {
+///     name: "x".to_string(),
+///     count: 1,
+///     celltype: CellType::Switch,
+///     loc: loc.to_string(),
+///     area: 0.0,
+///     copies: 1,
+///     dx_required: None,
+///     dx_selected: None,
+///     leakage: None,
+///     extent,
+/// };
+/// let reports = vec![
+///     report("Array", [40.0, 20.0]),
+///     report("WL", [3.0, 20.0]),
+///     report("WL", [2.0, 20.0]),
+///     report("BL", [40.0, 4.0]),
+///     report("BL", [40.0, 1.0]),
+/// ];
+///
+/// let plan = floorplan(&reports);
+/// assert_eq!((plan.width, plan.height), (45.0, 25.0));
+/// assert_eq!(plan.aspect_ratio, 1.8);
+/// ```
+pub fn floorplan(reports: &Reports) -> Floorplan {
+    let (mut core, mut wl, mut bl) = ([0.0 as Float; 2], [0.0 as Float; 2], [0.0 as Float; 2]);
+
+    for r in reports {
+        let [w, h] = r.extent;
+        match r.loc.as_str() {
+            "Array" => core = [core[0].max(w), core[1].max(h)],
+            "WL" => wl = [wl[0] + w, wl[1].max(h)],
+            _ => bl = [bl[0].max(w), bl[1] + h],
+        }
+    }
+
+    let width = wl[0] + core[0].max(bl[0]);
+    let height = core[1].max(wl[1]) + bl[1];
+    let aspect_ratio = match height > 0.0 {
+        true => width / height,
+        false => 0.0,
+    };
+
+    Floorplan {
+        width,
+        height,
+        aspect_ratio,
+    }
+}
+
+/// Formats the estimated macro outline, or nothing if it is unknown.
+fn fmt_floorplan(reports: &Reports) -> String {
+    let plan = floorplan(reports);
+    match plan.width > 0.0 && plan.height > 0.0 {
+        true => format!(
+            "Macro: {:.1} x {:.1} μm (AR {:.2})\n",
+            plan.width, plan.height, plan.aspect_ratio
+        ),
+        false => String::new(),
+    }
+}
+
+/// Formats the total area of each configuration for `--area-only` output.
+///
+/// The `tsv` format prints one `name<TAB>area` line per configuration in the
+/// given order. The `json` and `yaml` formats produce a single map from
+/// configuration name to area with sorted keys.
+///
+/// # Arguments
+/// * `totals` - (configuration name, total area) pairs
+/// * `format` - One of `tsv`, `json`, or `yaml`
+///
+/// # Returns
+/// * `Ok(String)` - Formatted totals
+/// * `Err(MemeaError)` - Serialization error or unsupported format
+///
+/// # Examples
+/// ```
+/// use memea::export::fmt_totals;
+///
+/// let totals = vec![("b".to_string(), 2.5), ("a".to_string(), 1.0)];
+/// assert_eq!(fmt_totals(&totals, "tsv")?, "b\t2.5\na\t1\n");
+/// assert_eq!(fmt_totals(&totals, "json")?, "{\n  \"a\": 1.0,\n  \"b\": 2.5\n}\n");
+/// assert_eq!(fmt_totals(&totals, "yaml")?, "a: 1.0\nb: 2.5\n");
+/// # Ok::<(), memea::MemeaError>(())
+/// ```
+pub fn fmt_totals(totals: &[(String, Float)], format: &str) -> Result<String, MemeaError> {
+    let sorted: BTreeMap<&str, Float> = totals.iter().map(|(n, a)| (n.as_str(), *a)).collect();
+
+    let content = match format {
+        "tsv" => totals.iter().map(|(n, a)| format!("{n}\t{a}\n")).collect(),
+        "json" => serde_json::to_string_pretty(&sorted)? + "\n",
+        "yaml" | "yml" => serde_yaml::to_string(&sorted)?,
+        other => {
+            return Err(DBError::FileType(other.to_string()).into());
+        }
+    };
+
+    Ok(content)
+}
+
+/// Formats database cells as a table for the `list-cells` subcommand.
+///
+/// Pin and obstruction area columns are added when any cell has them (`-`
+/// marks a cell without one).
+///
+/// With `area_only`, each cell gets a single `type<TAB>name<TAB>area` line
+/// instead, for scripting.
+///
+/// # Arguments
+/// * `cells` - Cells to list, in order (see [`crate::db::Database::summaries`])
+/// * `area_only` - Print only the type, name and area of each cell
+///
+/// # Returns
+/// The formatted listing
+///
+/// # Examples
+/// ```
+/// use memea::db::{Database, Dims, Switch};
+/// use memea::export::fmt_cells;
+///
+/// let mut db = Database::new();
+/// let dims = Dims::from(2.0, 3.0, 0.0, 0.0);
+/// let sw = Switch { dx: 4.0, voltage: [0.0, 1.8], dims, leakage: None, note: None, source: None };
+/// db.switch.insert("sw".to_string(), sw);
+///
+/// let table = fmt_cells(&db.summaries(), false);
+/// assert!(table.lines().next().unwrap().starts_with("Type"));
+/// assert!(table.contains("dx=4 voltage=[0, 1.8]"));
+/// assert!(!table.contains("Pin"));
+/// assert_eq!(fmt_cells(&db.summaries(), true), "Switch\tsw\t6\n");
+///
+/// db.switch.get_mut("sw").unwrap().dims.pin_area = Some(0.5);
+/// let table = fmt_cells(&db.summaries(), false);
+/// assert!(table.lines().next().unwrap().contains("Pin (μm²)"));
+/// assert!(table.contains("0.5000 |          - | dx=4"));
+///
+/// // Notes and sources get their own columns once any cell has one
+/// db.switch.get_mut("sw").unwrap().source = Some("foundry X, rev Y".to_string());
+/// let table = fmt_cells(&db.summaries(), false);
+/// assert!(table.lines().next().unwrap().ends_with("| Note | Source"));
+/// assert!(table.contains("dx=4 voltage=[0, 1.8] | -    | foundry X, rev Y"));
+/// ```
+pub fn fmt_cells(cells: &[CellSummary], area_only: bool) -> String {
+    if area_only {
+        return cells
+            .iter()
+            .map(|c| format!("{}\t{}\t{}\n", c.celltype, c.name, c.area))
+            .collect();
+    }
+
+    let width = cells.iter().map(|c| c.name.len()).max().unwrap_or(0).max(4);
+    let ports = cells
+        .iter()
+        .any(|c| c.pin_area.is_some() || c.obs_area.is_some());
+    let area_or_dash = |a: Option<Float>| a.map_or("-".to_string(), |a| format!("{a:.4}"));
+    let mut content = format!(
+        "{:<8} | {:<width$} | {:>17} | {:>14} | {:>11} | ",
+        "Type", "Name", "Size (μm)", "Enc (μm)", "Area (μm²)"
+    );
+    if ports {
+        content.push_str(&format!("{:>10} | {:>10} | ", "Pin (μm²)", "OBS (μm²)"));
+    }
+    // Notes and sources follow the parameters, so pad those to line them up
+    let provenance = cells.iter().any(|c| c.note.is_some() || c.source.is_some());
+    let params = cells
+        .iter()
+        .map(|c| c.parameters.len())
+        .max()
+        .unwrap_or(0)
+        .max(10);
+    let note = cells
+        .iter()
+        .filter_map(|c| c.note.as_ref().map(String::len))
+        .max()
+        .unwrap_or(0)
+        .max(4);
+    let text_or_dash = |t: &Option<String>| t.clone().unwrap_or_else(|| "-".to_string());
+    if provenance {
+        content.push_str(&format!(
+            "{:<params$} | {:<note$} | Source\n",
+            "Parameters", "Note"
+        ));
+    } else {
+        content.push_str("Parameters\n");
+    }
+    for c in cells {
+        content.push_str(&format!(
+            "{:<8} | {:<width$} | {:>8.4} x {:<6.4} | {:>6.3} x {:<5.3} | {:>11.4} | ",
+            c.celltype.to_string(),
+            c.name,
+            c.width,
+            c.height,
+            c.enc_x,
+            c.enc_y,
+            c.area,
+        ));
+        if ports {
+            content.push_str(&format!(
+                "{:>10} | {:>10} | ",
+                area_or_dash(c.pin_area),
+                area_or_dash(c.obs_area)
+            ));
+        }
+        if provenance {
+            content.push_str(&format!(
+                "{:<params$} | {:<note$} | {}\n",
+                c.parameters,
+                text_or_dash(&c.note),
+                text_or_dash(&c.source)
+            ));
+        } else {
+            content.push_str(&c.parameters);
+            content.push('\n');
+        }
+    }
+    content
+}
+
+/// Writes database cells to a CSV, JSON or YAML file, by its extension.
+///
+/// # Arguments
+/// * `cells` - Cells to write (see [`crate::db::Database::summaries`])
+/// * `filename` - Output file path
+///
+/// # Returns
+/// * `Ok(())` - Cells written (or the user declined to overwrite)
+/// * `Err(MemeaError)` - Unsupported extension, serialization or I/O error
+pub fn export_cells(cells: &[CellSummary], filename: &PathBuf) -> Result<(), MemeaError> {
+    let format = format_of(&Some(filename.clone()));
+    if !matches!(format.as_str(), "csv" | "json" | "yaml" | "yml") {
+        return Err(DBError::FileType(format).into());
+    }
+    let Some(mut file) = open_output(filename)? else {
+        return Ok(());
+    };
+
+    match format.as_str() {
+        "csv" => {
+            let mut wtr = csv::Writer::from_writer(file);
+            for c in cells {
+                wtr.serialize(c)?;
+            }
+            wtr.flush()?;
+        }
+        "json" => serde_json::to_writer_pretty(file, cells)?,
+        _ => file.write_all(serde_yaml::to_string(cells)?.as_bytes())?,
+    }
+    Ok(())
+}
+
+/// Settings that control how results are exported.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Options<'a> {
+    /// Fit direct output to the terminal width (see [`fmt_compact`]).
+    pub compact: bool,
+    /// Fatal defect densities (defects/cm²) of the configurations that give
+    /// one, used to report yield-adjusted effective areas.
+    pub defect_density: Option<&'a HashMap<String, Float>>,
+    /// Add each report's share of its configuration's area (see [`share`])
+    /// to CSV, JSON and YAML exports.
+    pub share: bool,
+    /// Output format, overriding the one implied by the file extension
+    /// (see [`format_of`]).
+    pub format: Option<&'a str>,
+    /// Head each location's rows in direct output with the location name
+    /// (see [`fmt_grouped`]).
+    pub group_by_location: bool,
+    /// Warnings raised while tabulating each configuration (see
+    /// [`crate::tabulate::tabulate_warned`]), included in JSON and YAML
+    /// exports.
+    pub warnings: Option<&'a HashMap<String, Vec<String>>>,
+    /// Number of bits each configuration stores, used to report capacity
+    /// and area per bit (see [`capacity`]).
+    pub capacity: Option<&'a HashMap<String, usize>>,
+}
+
+impl<'a> Options<'a> {
+    /// Yield-adjusted effective area of a configuration, if it has a defect density.
+    fn effective_area(&self, name: &str, reports: &Reports) -> Option<Float> {
+        let density = self.defect_density?.get(name)?;
+        Some(crate::analysis::yield_adjust(area(reports), *density))
+    }
+
+    /// Tabulation warnings of a configuration, if it has any.
+    fn warnings_of(&self, name: &str) -> Option<&'a Vec<String>> {
+        self.warnings?.get(name).filter(|w| !w.is_empty())
+    }
+
+    /// Capacity of a configuration, if it is known.
+    fn capacity_of(&self, name: &str, reports: &Reports) -> Option<Capacity> {
+        let bits = self.capacity?.get(name)?;
+        Some(capacity(*bits, area(reports)))
+    }
+}
+
 /// Exports analysis results to various formats based on file extension.
 ///
-/// This function determines the output format from the file extension and handles
-/// file creation with overwrite confirmation. Supported formats include CSV, JSON,
-/// YAML, and direct console output.
+/// This function determines the output format from the file extension, unless
+/// `opts.format` overrides it, and handles file creation with overwrite
+/// confirmation. Supported formats include CSV, JSON, YAML, DEF area hints,
+/// plaintext summaries, Markdown tables (`md`), and the direct breakdown table.
+/// Without a file, output goes to stdout.
 ///
 /// # Arguments
 /// * `reports` - HashMap of configuration names to their corresponding reports
 /// * `filename` - Optional output file path. If None, outputs to stdout
+/// * `opts` - Export settings
 ///
 /// # Returns
 /// * `Ok(())` - Export completed successfully
@@ -41,63 +534,332 @@ pub fn area(reports: &Reports) -> Float {
 ///
 /// # Examples
 /// ```no_run
-/// use memea::export::export;
+/// use memea::export::{export, Options};
 /// use std::path::PathBuf;
 /// use std::collections::HashMap;
 ///
 /// let reports = HashMap::new(); // populated with analysis results
 /// let output_file = Some(PathBuf::from("results.csv"));
-/// export(&reports, &output_file).expect("Export failed");
+/// export(&reports, &output_file, &Options::default()).expect("Export failed");
 /// ```
 pub fn export(
     reports: &HashMap<String, Reports>,
     filename: &Option<PathBuf>,
+    opts: &Options,
 ) -> Result<(), MemeaError> {
     let buf = match filename {
-        Some(x) => {
-            if metadata(x).is_ok() {
-                let allow = query(
-                    format!("'{}' already exists. Overwrite?", x.to_string_lossy()).as_str(),
-                    true,
-                    crate::QueryDefault::Yes,
-                )?;
-                if !allow {
-                    infoln!("Aborting...");
-                    return Ok(());
+        Some(x) => match open_output(x)? {
+            Some(f) => Some(f),
+            None => return Ok(()),
+        },
+        None => None,
+    };
+
+    let format = match opts.format {
+        Some(f) => f.to_lowercase(),
+        None => format_of(filename),
+    };
+
+    match format.as_str() {
+        "csv" => export_csv(reports, buf, opts)?,
+
+        "json" => export_json(reports, buf, opts)?,
+        "yaml" | "yml" => export_yaml(reports, buf, opts)?,
+        "def" => export_def(reports, buf)?,
+        "txt" => export_txt(reports, buf)?,
+        "md" => export_md(reports, buf)?,
+        "direct" => export_direct(reports, buf, opts)?,
+        other => {
+            return Err(DBError::FileType(other.to_string()).into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Exports each configuration to its own file in a directory.
+///
+/// Every configuration is written to `<dir>/<name>.<format>`, where the name
+/// is made filesystem-safe with [`sanitize_filename`] and suffixed if it
+/// collides with another (see [`output_paths`]). The directory is created if
+/// needed, and overwrite confirmation applies to each file.
+///
+/// # Arguments
+/// * `reports` - HashMap of configuration names to their corresponding reports
+/// * `dir` - Output directory
+/// * `format` - File extension selecting the format (e.g. `csv`, `yaml`)
+/// * `opts` - Export settings
+///
+/// # Returns
+/// * `Ok(())` - All files written (or skipped at the user's request)
+/// * `Err(MemeaError)` - File I/O error, serialization error, or unsupported format
+///
+/// # Examples
+/// ```no_run
+/// use memea::export::{export_dir, Options};
+/// use std::collections::HashMap;
+/// use std::path::Path;
+///
+/// let reports = HashMap::new(); // populated with analysis results
+/// export_dir(&reports, Path::new("results"), "json", &Options::default())
+///     .expect("Export failed");
+/// ```
+pub fn export_dir(
+    reports: &HashMap<String, Reports>,
+    dir: &Path,
+    format: &str,
+    opts: &Options,
+) -> Result<(), MemeaError> {
+    create_dir_all(dir)?;
+
+    let names: Vec<&str> = sorted(reports).into_keys().collect();
+    for (name, path) in output_paths(&names, dir, format) {
+        let single = HashMap::from([(name.to_string(), reports[name].clone())]);
+        export(&single, &Some(path), opts)?;
+    }
+
+    Ok(())
+}
+
+/// Picks a distinct output file for each configuration name.
+///
+/// Each name is made filesystem-safe with [`sanitize_filename`]. When two
+/// names map to the same file (ignoring case, for case-insensitive
+/// filesystems), the later one gets a numeric suffix (`_2`, `_3`, ...) and
+/// a warning, so that no file overwrites another.
+///
+/// # Arguments
+/// * `names` - Configuration names, in output order
+/// * `dir` - Output directory
+/// * `format` - File extension
+///
+/// # Returns
+/// Each name with its output path, in the order given
+///
+/// # Examples
+/// ```
+/// use memea::export::output_paths;
+/// use std::path::{Path, PathBuf};
+///
+/// let paths = output_paths(&["a/b", "a_b", "c"], Path::new("out"), "csv");
+/// let files: Vec<PathBuf> = paths.into_iter().map(|(_, p)| p).collect();
+/// assert_eq!(files, ["out/a_b.csv", "out/a_b_2.csv", "out/c.csv"].map(PathBuf::from));
+/// ```
+pub fn output_paths<'a>(names: &[&'a str], dir: &Path, format: &str) -> Vec<(&'a str, PathBuf)> {
+    let mut taken: HashMap<String, &str> = HashMap::new();
+
+    names
+        .iter()
+        .map(|name| {
+            let base = sanitize_filename(name);
+            let mut stem = base.clone();
+            let mut k = 2;
+            while let Some(other) = taken.get(&stem.to_lowercase()) {
+                if k == 2 {
+                    warnln!(
+                        "Configurations '{}' and '{}' map to the same file name",
+                        other,
+                        name
+                    );
                 }
+                stem = format!("{base}_{k}");
+                k += 1;
+            }
+            if stem != base {
+                warnln!("Writing configuration '{}' to {}.{}", name, stem, format);
             }
+            taken.insert(stem.to_lowercase(), name);
+            (*name, dir.join(format!("{stem}.{format}")))
+        })
+        .collect()
+}
+
+/// Replaces characters that are unsafe in file names with underscores.
+///
+/// Only ASCII letters, digits, `-`, `_`, and `.` are kept. Leading dots are
+/// also replaced so that the result is never hidden or a relative path.
+///
+/// # Examples
+/// ```
+/// use memea::export::sanitize_filename;
+///
+/// assert_eq!(sanitize_filename("FeRAM 128x64"), "FeRAM_128x64");
+/// assert_eq!(sanitize_filename("../cfg/a.yaml"), "___cfg_a.yaml");
+/// ```
+pub fn sanitize_filename(name: &str) -> String {
+    let safe: String = name
+        .chars()
+        .map(
+            |c| match c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                true => c,
+                false => '_',
+            },
+        )
+        .collect();
 
-            let f = OpenOptions::new()
-                .write(true)
-                .create(true)
-                .truncate(true)
-                .open(x)?;
+    let trimmed = safe.trim_start_matches('.');
+    "_".repeat(safe.len() - trimmed.len()) + trimmed
+}
 
-            infoln!("Wrote output to {:#?}", x);
+/// Turns a configuration name into a DEF design name.
+///
+/// Everything but ASCII letters, digits and `_` is replaced with an
+/// underscore, so spaces, `;` and `#` cannot break the statement.
+///
+/// # Examples
+/// ```
+/// use memea::export::def_name;
+///
+/// assert_eq!(def_name("FeRAM_128"), "FeRAM_128");
+/// assert_eq!(def_name("FeRAM 128-64; #2"), "FeRAM_128_64___2");
+/// assert_eq!(def_name(""), "_");
+/// ```
+pub fn def_name(name: &str) -> String {
+    match name.is_empty() {
+        true => "_".to_string(),
+        false => name
+            .chars()
+            .map(|c| match c.is_ascii_alphanumeric() || c == '_' {
+                true => c,
+                false => '_',
+            })
+            .collect(),
+    }
+}
 
-            Some(f)
-        }
-        None => None,
+/// Formats [`export`] can write a full breakdown in.
+pub const REPORT_FORMATS: [&str; 8] = ["csv", "json", "yaml", "yml", "def", "txt", "md", "direct"];
+
+/// Formats [`fmt_totals`] can write `--area-only` totals in.
+pub const TOTALS_FORMATS: [&str; 4] = ["tsv", "json", "yaml", "yml"];
+
+/// Checks up front that the output format suits the kind of output.
+///
+/// With `area_only`, only [`TOTALS_FORMATS`] are accepted; otherwise the
+/// format (or, without one, the extension of `filename`) must be one of
+/// [`REPORT_FORMATS`]. Checking before tabulating avoids failing only after
+/// every configuration has been estimated.
+///
+/// # Arguments
+/// * `format` - Explicit output format, if any
+/// * `filename` - Export file, whose extension gives the format otherwise
+/// * `area_only` - Whether only totals are printed
+///
+/// # Returns
+/// * `Ok(())` - The format can be written
+/// * `Err(MemeaError)` - The format does not apply to this kind of output
+///
+/// # Examples
+/// ```
+/// use memea::export::check_format;
+/// use std::path::PathBuf;
+///
+/// assert!(check_format(Some("tsv"), &None, true).is_ok());
+/// assert!(check_format(Some("md"), &None, false).is_ok());
+/// assert!(check_format(None, &Some(PathBuf::from("out.csv")), false).is_ok());
+///
+/// assert!(check_format(Some("tsv"), &None, false).is_err());
+/// assert!(check_format(Some("md"), &None, true).is_err());
+/// assert!(check_format(None, &Some(PathBuf::from("out.xlsx")), false).is_err());
+/// ```
+pub fn check_format(
+    format: Option<&str>,
+    filename: &Option<PathBuf>,
+    area_only: bool,
+) -> Result<(), MemeaError> {
+    let (format, allowed, what) = match (format, area_only) {
+        (Some(f), true) => (f.to_lowercase(), &TOTALS_FORMATS[..], "with --area-only"),
+        (None, true) => return Ok(()),
+        (Some(f), false) => (f.to_lowercase(), &REPORT_FORMATS[..], "without --area-only"),
+        (None, false) => (
+            format_of(filename),
+            &REPORT_FORMATS[..],
+            "for a breakdown export",
+        ),
     };
 
-    let format = filename
+    match allowed.contains(&format.as_str()) {
+        true => Ok(()),
+        false => Err(MemeaError::ParseError(format!(
+            "format '{format}' is not available {what} (use one of: {})",
+            allowed.join(", ")
+        ))),
+    }
+}
+
+/// Determines the export format from an optional output path.
+///
+/// # Arguments
+/// * `filename` - Optional output file path
+///
+/// # Returns
+/// Lowercase file extension, or `"direct"` when there is no path or extension
+pub fn format_of(filename: &Option<PathBuf>) -> String {
+    filename
         .as_ref()
         .and_then(|f| f.extension().and_then(|s| s.to_str()))
         .unwrap_or("direct")
-        .to_lowercase();
+        .to_lowercase()
+}
+
+/// Asks the user whether an existing output file may be overwritten.
+///
+/// Returns `true` without asking if the file does not exist or if
+/// [`crate::set_overwrite`] has been enabled.
+///
+/// # Arguments
+/// * `filename` - Output file path
+///
+/// # Returns
+/// * `Ok(true)` - The file may be written
+/// * `Ok(false)` - User declined to overwrite an existing file
+/// * `Err(MemeaError)` - I/O error during user interaction
+pub fn confirm_overwrite(filename: &Path) -> Result<bool, MemeaError> {
+    if metadata(filename).is_err() || overwrite_enabled() {
+        return Ok(true);
+    }
+
+    let allow = query(
+        format!(
+            "'{}' already exists. Overwrite?",
+            filename.to_string_lossy()
+        )
+        .as_str(),
+        true,
+        crate::QueryDefault::Yes,
+    )?;
+    if !allow {
+        infoln!("Aborting...");
+    }
+
+    Ok(allow)
+}
+
+/// Opens an output file for writing, confirming overwrite if it exists.
+///
+/// Confirmation is skipped when [`crate::set_overwrite`] has been enabled.
+///
+/// # Arguments
+/// * `filename` - Output file path
+///
+/// # Returns
+/// * `Ok(Some(File))` - File opened and truncated for writing
+/// * `Ok(None)` - User declined to overwrite an existing file
+/// * `Err(MemeaError)` - I/O error
+fn open_output(filename: &PathBuf) -> Result<Option<File>, MemeaError> {
+    if !confirm_overwrite(filename)? {
+        return Ok(None);
+    }
 
-    match format.as_str() {
-        "csv" => export_csv(reports, buf)?,
+    let f = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(filename)?;
 
-        "json" => export_json(reports, buf)?,
-        "yaml" | "yml" => export_yaml(reports, buf)?,
-        "direct" => export_direct(reports)?,
-        other => {
-            return Err(DBError::FileType(other.to_string()).into());
-        }
-    }
+    infoln!("Wrote output to {:#?}", filename);
 
-    Ok(())
+    Ok(Some(f))
 }
 
 #[derive(serde::Serialize)]
@@ -114,10 +876,14 @@ struct Row<'a> {
     location: &'a str,
     #[serde(rename = "Area (μm2)")]
     area: Float,
+    #[serde(rename = "Leakage (W)")]
+    leakage: Option<Float>,
+    #[serde(rename = "Share (%)", skip_serializing_if = "Option::is_none")]
+    share: Option<Float>,
 }
 
 impl<'a> Row<'a> {
-    fn from_report(config: &'a str, rep: &'a Report) -> Self {
+    fn from_report(config: &'a str, rep: &'a Report, share: Option<Float>) -> Self {
         Row {
             configuration: config,
             name: &rep.name,
@@ -125,10 +891,43 @@ impl<'a> Row<'a> {
             count: rep.count,
             location: &rep.loc,
             area: rep.area,
+            leakage: rep.leakage,
+            share,
         }
     }
 }
 
+/// A report as written to JSON and YAML, optionally with its share of the
+/// configuration's area.
+#[derive(serde::Serialize)]
+struct Entry<'a> {
+    #[serde(flatten)]
+    report: &'a Report,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    share: Option<Float>,
+}
+
+/// Pairs every report with its share of its configuration's area, if enabled.
+fn entries<'a>(
+    reports: &'a HashMap<String, Reports>,
+    opts: &Options,
+) -> BTreeMap<&'a str, Vec<Entry<'a>>> {
+    sorted(reports)
+        .into_iter()
+        .map(|(name, reps)| {
+            let total = area(reps);
+            let entries = reps
+                .iter()
+                .map(|report| Entry {
+                    report,
+                    share: opts.share.then(|| share(report.area, total)),
+                })
+                .collect();
+            (name, entries)
+        })
+        .collect()
+}
+
 /// Exports reports to CSV format with configuration names included.
 ///
 /// Each row in the CSV contains a configuration name along with flattened
@@ -137,93 +936,671 @@ impl<'a> Row<'a> {
 /// # Arguments
 /// * `reports` - HashMap of configuration names to reports
 /// * `buf` - Optional file buffer, uses stdout if None
+/// * `opts` - Export settings
 ///
 /// # Returns
 /// * `Ok(())` - CSV export completed successfully
 /// * `Err(MemeaError)` - Serialization or I/O error
-fn export_csv(reports: &HashMap<String, Reports>, buf: Option<File>) -> Result<(), MemeaError> {
+fn export_csv(
+    reports: &HashMap<String, Reports>,
+    buf: Option<File>,
+    opts: &Options,
+) -> Result<(), MemeaError> {
     let writer: Box<dyn Write> = match buf {
         Some(file) => Box::new(file),
         None => Box::new(io::stdout()),
     };
 
-    let mut wtr = csv::WriterBuilder::new()
-        .has_headers(true)
-        .from_writer(writer);
+    let mut sink = CsvSink::from_writer(writer).with_share(opts.share);
+
+    for (config, reps) in sorted(reports) {
+        sink.write(config, reps)?;
+    }
+
+    sink.finish()
+}
+
+/// Incremental CSV writer for streaming reports as they are produced.
+///
+/// Unlike [`export`], which needs every report in memory before writing,
+/// a `CsvSink` is created up front and fed one configuration at a time, so
+/// memory use stays bounded for very large batches.
+///
+/// # Examples
+/// ```no_run
+/// use memea::export::CsvSink;
+/// use std::path::PathBuf;
+///
+/// if let Some(mut sink) = CsvSink::create(&PathBuf::from("out.csv"))? {
+///     let reports = Vec::new(); // produced by tabulate
+///     sink.write("config", &reports)?;
+///     sink.finish()?;
+/// }
+/// # Ok::<(), memea::MemeaError>(())
+/// ```
+pub struct CsvSink {
+    wtr: csv::Writer<Box<dyn Write>>,
+    share: bool,
+}
+
+impl CsvSink {
+    /// Creates a sink writing to the given file, confirming overwrite if it exists.
+    ///
+    /// # Arguments
+    /// * `filename` - Output CSV file path
+    ///
+    /// # Returns
+    /// * `Ok(Some(CsvSink))` - Sink ready to receive reports
+    /// * `Ok(None)` - User declined to overwrite an existing file
+    /// * `Err(MemeaError)` - I/O error
+    pub fn create(filename: &PathBuf) -> Result<Option<CsvSink>, MemeaError> {
+        Ok(open_output(filename)?.map(|f| CsvSink::from_writer(Box::new(f))))
+    }
+
+    /// Creates a sink writing to an arbitrary writer.
+    ///
+    /// # Arguments
+    /// * `writer` - Destination for the CSV rows
+    pub fn from_writer(writer: Box<dyn Write>) -> CsvSink {
+        let wtr = csv::WriterBuilder::new()
+            .has_headers(true)
+            .from_writer(writer);
+
+        CsvSink { wtr, share: false }
+    }
+
+    /// Adds a `Share (%)` column with each report's share of its
+    /// configuration's area (see [`share`]).
+    ///
+    /// # Arguments
+    /// * `share` - Whether to write the column
+    pub fn with_share(mut self, share: bool) -> CsvSink {
+        self.share = share;
+        self
+    }
 
-    for (config, reps) in reports {
-        for rep in reps {
+    /// Writes all reports of one configuration as CSV rows.
+    ///
+    /// # Arguments
+    /// * `config` - Configuration name for the rows
+    /// * `reports` - Reports of the configuration
+    ///
+    /// # Returns
+    /// * `Ok(())` - Rows written successfully
+    /// * `Err(MemeaError)` - Serialization or I/O error
+    pub fn write(&mut self, config: &str, reports: &Reports) -> Result<(), MemeaError> {
+        let total = area(reports);
+        for rep in reports {
             // TODO: Cannot serialize maps
-            wtr.serialize(Row::from_report(config, rep))?;
+            let share = self.share.then(|| share(rep.area, total));
+            self.wtr.serialize(Row::from_report(config, rep, share))?;
         }
+        Ok(())
     }
 
-    wtr.flush()?;
-    Ok(())
+    /// Flushes any buffered rows to the underlying writer.
+    ///
+    /// # Returns
+    /// * `Ok(())` - Flush completed successfully
+    /// * `Err(MemeaError)` - I/O error
+    pub fn finish(mut self) -> Result<(), MemeaError> {
+        self.wtr.flush()?;
+        Ok(())
+    }
+}
+
+/// Area subtotal of one location, as exported to JSON and YAML.
+#[derive(serde::Serialize)]
+struct Subtotal<'a> {
+    loc: &'a str,
+    area: Float,
+}
+
+/// Per-location subtotals of a configuration (see [`subtotals`]).
+fn subtotal_list(reports: &Reports) -> Vec<Subtotal<'_>> {
+    subtotals(reports)
+        .into_iter()
+        .map(|(loc, area)| Subtotal { loc, area })
+        .collect()
+}
+
+/// JSON and YAML export layout: reports keyed by configuration, then the
+/// metadata under [`META_KEY`].
+#[derive(serde::Serialize)]
+struct Export<'a> {
+    #[serde(flatten)]
+    configs: BTreeMap<&'a str, Vec<Entry<'a>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    meta: Option<Meta<'a>>,
+}
+
+/// Export metadata: the grand total, then derived values keyed by
+/// configuration.
+#[derive(serde::Serialize)]
+struct Meta<'a> {
+    /// Sum over every configuration (see [`grand_total`])
+    grand_total: GrandTotal,
+    /// Estimated macro outline (see [`floorplan`])
+    floorplan: BTreeMap<&'a str, Floorplan>,
+    /// Area of each location, in the order the locations first appear
+    subtotals: BTreeMap<&'a str, Vec<Subtotal<'a>>>,
+    /// Yield-adjusted area of configurations that give a defect density
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    effective_area: BTreeMap<&'a str, Float>,
+    /// Storage capacity and area per bit, where known
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    capacity: BTreeMap<&'a str, Capacity>,
+    /// Warnings raised while tabulating each configuration, if any
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    warnings: BTreeMap<&'a str, &'a Vec<String>>,
+}
+
+/// Lays out reports for a JSON or YAML export.
+///
+/// Each configuration name maps to its reports, and the reserved key
+/// [`META_KEY`] holds the grand total (see [`grand_total`]) and each
+/// configuration's floorplan (see [`floorplan`]), per-location subtotals
+/// (see [`subtotals`]), effective area, capacity (see [`capacity`]) and
+/// tabulation warnings, where known. The metadata is left out, with a
+/// warning, if a configuration has that name.
+fn layout<'a>(reports: &'a HashMap<String, Reports>, opts: &Options<'a>) -> Export<'a> {
+    let taken = reports.contains_key(META_KEY);
+    if taken {
+        warnln!(
+            "A configuration is named '{}'; omitting metadata from output",
+            META_KEY
+        );
+    }
+    let configs = sorted(reports);
+
+    Export {
+        configs: entries(reports, opts),
+        meta: (!taken).then(|| Meta {
+            grand_total: grand_total(reports),
+            floorplan: configs
+                .iter()
+                .map(|(name, r)| (*name, floorplan(r)))
+                .collect(),
+            subtotals: configs
+                .iter()
+                .map(|(name, r)| (*name, subtotal_list(r)))
+                .collect(),
+            effective_area: configs
+                .iter()
+                .filter_map(|(name, r)| Some((*name, opts.effective_area(name, r)?)))
+                .collect(),
+            capacity: configs
+                .iter()
+                .filter_map(|(name, r)| Some((*name, opts.capacity_of(name, r)?)))
+                .collect(),
+            warnings: configs
+                .keys()
+                .filter_map(|name| Some((*name, opts.warnings_of(name)?)))
+                .collect(),
+        }),
+    }
 }
 
 /// Exports reports to JSON format with pretty printing.
 ///
+/// The layout matches YAML exports (see [`layout`]).
+///
 /// # Arguments
 /// * `reports` - HashMap of configuration names to reports
 /// * `buf` - Optional file buffer, uses stdout if None
+/// * `opts` - Export settings
 ///
 /// # Returns
 /// * `Ok(())` - JSON export completed successfully
 /// * `Err(MemeaError)` - Serialization or I/O error
-fn export_json(reports: &HashMap<String, Reports>, buf: Option<File>) -> Result<(), MemeaError> {
+fn export_json(
+    reports: &HashMap<String, Reports>,
+    buf: Option<File>,
+    opts: &Options,
+) -> Result<(), MemeaError> {
+    let export = layout(reports, opts);
+
     match buf {
-        Some(file) => serde_json::to_writer_pretty(file, reports)?,
-        None => serde_json::to_writer_pretty(io::stdout(), reports)?,
+        Some(file) => serde_json::to_writer_pretty(file, &export)?,
+        None => serde_json::to_writer_pretty(io::stdout(), &export)?,
     }
     Ok(())
 }
 
 /// Exports reports to YAML format.
 ///
+/// The layout matches JSON exports (see [`layout`]).
+///
 /// # Arguments
 /// * `reports` - HashMap of configuration names to reports
 /// * `buf` - Optional file buffer, uses stdout if None
+/// * `opts` - Export settings
 ///
 /// # Returns
 /// * `Ok(())` - YAML export completed successfully
 /// * `Err(MemeaError)` - Serialization or I/O error
-fn export_yaml(reports: &HashMap<String, Reports>, buf: Option<File>) -> Result<(), MemeaError> {
+fn export_yaml(
+    reports: &HashMap<String, Reports>,
+    buf: Option<File>,
+    opts: &Options,
+) -> Result<(), MemeaError> {
+    let s = serde_yaml::to_string(&layout(reports, opts))?;
     match buf {
-        Some(mut file) => {
-            let s = serde_yaml::to_string(reports)?;
-            file.write_all(s.as_bytes())?;
-        }
-        None => {
-            let s = serde_yaml::to_string(reports)?;
-            println!("{s}");
+        Some(mut file) => file.write_all(s.as_bytes())?,
+        None => println!("{s}"),
+    }
+    Ok(())
+}
+
+/// Number of largest contributors listed per configuration in a summary.
+const SUMMARY_TOP: usize = 3;
+
+/// Formats a terse plaintext summary of every configuration.
+///
+/// Each configuration gets one line with its total area and its largest
+/// contributors (by cell name, see [`crate::analysis::area_by_cell_name`]) as
+/// a share of that total, followed by a grand total line. The output is plain
+/// ASCII without color codes, so it can be pasted into an email.
+///
+/// # Arguments
+/// * `reports` - HashMap of configuration names to reports
+///
+/// # Returns
+/// Summary text, with configurations sorted by name
+///
+/// # Examples
+/// ```
+/// use std::collections::HashMap;
+/// use memea::db::CellType;
+/// use memea::export::export_summary;
+/// use memea::tabulate::Report;
+///
+/// let report = |name: &str, area| Report {
+///     name: name.to_string(),
+///     count: 1,
+///     celltype: CellType::Switch,
+///     loc: "WL".to_string(),
+///     area,
+///     copies: 1,
+///     dx_required: None,
+///     dx_selected: None,
+///     leakage: None,
+///     extent: [0.0, 0.0],
+/// };
+/// let reports = HashMap::from([(
+///     "a".to_string(),
+///     vec![report("core", 60.0), report("sw", 30.0), report("dec", 10.0)],
+/// )]);
+///
+/// assert_eq!(
+///     export_summary(&reports),
+///     "a: 100.0 um2 (core 60.0%, sw 30.0%, dec 10.0%)\nTotal: 100.0 um2 across 1 configuration(s)\n"
+/// );
+/// ```
+pub fn export_summary(reports: &HashMap<String, Reports>) -> String {
+    let mut content = String::new();
+    for (name, reps) in sorted(reports) {
+        let config_area = area(reps);
+
+        let top: Vec<String> = crate::analysis::area_by_cell_name(reps)
+            .into_iter()
+            .take(SUMMARY_TOP)
+            .map(|(cell, a)| match config_area > 0.0 {
+                true => format!("{cell} {:.1}%", 100.0 * a / config_area),
+                false => cell,
+            })
+            .collect();
+
+        content.push_str(&format!(
+            "{name}: {config_area:.1} um2 ({})\n",
+            top.join(", ")
+        ));
+    }
+
+    let total = grand_total(reports);
+    content.push_str(&format!(
+        "Total: {:.1} um2 across {} configuration(s)\n",
+        total.area, total.configs
+    ));
+
+    content
+}
+
+/// Writes the plaintext summary from [`export_summary`].
+///
+/// # Arguments
+/// * `reports` - HashMap of configuration names to reports
+/// * `buf` - Optional file buffer, uses stdout if None
+///
+/// # Returns
+/// * `Ok(())` - Summary written successfully
+/// * `Err(MemeaError)` - I/O error
+fn export_txt(reports: &HashMap<String, Reports>, buf: Option<File>) -> Result<(), MemeaError> {
+    let summary = export_summary(reports);
+    match buf {
+        Some(mut file) => file.write_all(summary.as_bytes())?,
+        None => print!("{summary}"),
+    }
+    Ok(())
+}
+
+/// Database units per micron assumed by the DEF export.
+const DEF_DBU: Float = 1000.0;
+
+/// Exports reports as a minimal DEF-style floorplan fragment.
+///
+/// Each configuration becomes a `DESIGN` block whose `DIEAREA` is a square
+/// with the same total area as the configuration, followed by one comment per
+/// location giving its area subtotal. The output is not a complete, valid DEF
+/// file; it only carries area hints for floorplanning scripts.
+///
+/// Coordinates are in database units (DBU) with `UNITS DISTANCE MICRONS 1000`,
+/// i.e. 1 DBU = 1 nm. Region areas are given in μm². Design names are the
+/// configuration names made DEF-safe with [`def_name`].
+///
+/// # Arguments
+/// * `reports` - HashMap of configuration names to reports
+/// * `buf` - Optional file buffer, uses stdout if None
+///
+/// # Returns
+/// * `Ok(())` - DEF export completed successfully
+/// * `Err(MemeaError)` - I/O error
+fn export_def(reports: &HashMap<String, Reports>, buf: Option<File>) -> Result<(), MemeaError> {
+    let mut writer: Box<dyn Write> = match buf {
+        Some(file) => Box::new(file),
+        None => Box::new(io::stdout()),
+    };
+
+    writeln!(
+        writer,
+        "# MemEA floorplan area estimate (not a complete DEF)"
+    )?;
+    writeln!(writer, "VERSION 5.8 ;")?;
+    writeln!(writer, "UNITS DISTANCE MICRONS {DEF_DBU} ;\n")?;
+
+    for (config, reps) in sorted(reports) {
+        let side = (area(reps).sqrt() * DEF_DBU).round() as u64;
+
+        writeln!(writer, "DESIGN {} ;", def_name(config))?;
+        writeln!(writer, "DIEAREA ( 0 0 ) ( {side} {side} ) ;")?;
+        for (loc, subtotal) in subtotals(reps) {
+            writeln!(writer, "# REGION {loc} AREA {subtotal:.3} ;")?;
         }
+        writeln!(writer, "END DESIGN\n")?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Formats reports as a Markdown table.
+///
+/// The configuration name becomes a heading, followed by one table row per
+/// report and a bold total row. Pipes in cell names are escaped.
+///
+/// # Arguments
+/// * `input` - Configuration name to use as heading
+/// * `reports` - Collection of reports to format
+///
+/// # Returns
+/// Markdown text
+///
+/// # Examples
+/// ```
+/// use memea::db::CellType;
+/// use memea::export::fmt_markdown;
+/// use memea::tabulate::Report;
+///
+/// let reports = vec![Report {
+///     name: "sw".to_string(),
+///     count: 4,
+///     celltype: CellType::Switch,
+///     loc: "WL".to_string(),
+///     area: 10.0,
+///     copies: 1,
+///     dx_required: None,
+///     dx_selected: None,
+///     leakage: None,
+///     extent: [1.0, 10.0],
+/// }];
+///
+/// assert_eq!(
+///     fmt_markdown("demo", &reports),
+///     "## demo\n\n\
+///      | Name | Type | Count | Location | Area (μm²) |\n\
+///      | --- | --- | ---: | --- | ---: |\n\
+///      | sw | Switch | 4 | WL | 10.0 |\n\
+///      | **Total** | | | | **10.0** |\n"
+/// );
+/// ```
+pub fn fmt_markdown(input: &str, reports: &Reports) -> String {
+    let mut content = format!(
+        "## {input}\n\n\
+        | Name | Type | Count | Location | Area (μm²) |\n\
+        | --- | --- | ---: | --- | ---: |\n"
+    );
+
+    for r in reports {
+        content.push_str(&format!(
+            "| {} | {} | {} | {} | {:.1} |\n",
+            r.name.replace('|', "\\|"),
+            r.celltype,
+            r.count,
+            r.loc,
+            r.area
+        ));
+    }
+
+    content.push_str(&format!("| **Total** | | | | **{:.1}** |\n", area(reports)));
+    content
+}
+
+/// Exports reports as Markdown tables, one per configuration.
+///
+/// # Arguments
+/// * `reports` - HashMap of configuration names to reports
+/// * `buf` - Optional file buffer, uses stdout if None
+///
+/// # Returns
+/// * `Ok(())` - Markdown export completed successfully
+/// * `Err(MemeaError)` - I/O error
+fn export_md(reports: &HashMap<String, Reports>, buf: Option<File>) -> Result<(), MemeaError> {
+    let content: Vec<String> = sorted(reports)
+        .into_iter()
+        .map(|(name, r)| fmt_markdown(name, r))
+        .collect();
+    let content = content.join("\n");
+
+    match buf {
+        Some(mut file) => file.write_all(content.as_bytes())?,
+        None => print!("{content}"),
     }
     Ok(())
 }
 
-/// Exports reports in human-readable table format to stdout.
+/// Exports reports in human-readable table format.
 ///
 /// This format provides a clean, formatted table showing area breakdown
-/// by component type with totals for each configuration.
+/// by component type with totals for each configuration, followed by a grand
+/// total line when there are several configurations.
 ///
 /// # Arguments
 /// * `reports` - HashMap of configuration names to reports
+/// * `buf` - Optional file buffer, uses stdout if None
+/// * `opts` - Export settings
 ///
 /// # Returns
 /// * `Ok(())` - Direct export completed successfully
 /// * `Err(MemeaError)` - Formatting or I/O error
-fn export_direct(reports: &HashMap<String, Reports>) -> Result<(), MemeaError> {
-    for (name, r) in reports {
-        println!("{}", fmt_direct(name, r));
+fn export_direct(
+    reports: &HashMap<String, Reports>,
+    buf: Option<File>,
+    opts: &Options,
+) -> Result<(), MemeaError> {
+    let mut writer: Box<dyn Write> = match buf {
+        Some(file) => Box::new(file),
+        None => Box::new(io::stdout()),
+    };
+    let width = terminal_width();
+
+    for (name, r) in sorted(reports) {
+        let mut table = match opts.compact {
+            true => fmt_compact(name, r, width),
+            false if opts.group_by_location => fmt_grouped(name, r),
+            false => fmt_direct(name, r),
+        };
+        if let Some(effective) = opts.effective_area(name, r) {
+            table.push_str(&format!(
+                "Effective area: {effective:.1} μm² (yield-adjusted)\n"
+            ));
+        }
+        if let Some(cap) = opts.capacity_of(name, r) {
+            table.push_str(&format!("Capacity: {} bits", cap.bits));
+            if let Some(per_bit) = cap.area_per_bit {
+                table.push_str(&format!(" ({per_bit:.3} μm²/bit)"));
+            }
+            table.push('\n');
+        }
+        writeln!(writer, "{table}")?;
+    }
+
+    if reports.len() > 1 {
+        let total = grand_total(reports);
+        writeln!(
+            writer,
+            "Grand total: {:.1} μm² across {} configurations",
+            total.area, total.configs
+        )?;
     }
+
+    writer.flush()?;
     Ok(())
 }
 
+/// Groups reports by location, preserving insertion order.
+///
+/// Groups appear in the order their location is first seen, and reports keep
+/// their original order within each group. Used to produce per-region
+/// subtotals in any export format.
+///
+/// # Arguments
+/// * `reports` - Collection of reports to group
+///
+/// # Returns
+/// Vector of (location, reports at that location) pairs
+pub fn group_by_location(reports: &Reports) -> Vec<(&str, Vec<&Report>)> {
+    let mut groups: Vec<(&str, Vec<&Report>)> = Vec::new();
+
+    for report in reports.iter() {
+        match groups.iter_mut().find(|(loc, _)| *loc == report.loc) {
+            Some((_, group)) => group.push(report),
+            None => groups.push((&report.loc, vec![report])),
+        }
+    }
+
+    groups
+}
+
+/// Calculates the area subtotal for each location.
+///
+/// # Arguments
+/// * `reports` - Collection of reports to subtotal
+///
+/// # Returns
+/// Vector of (location, area) pairs in the order given by [`group_by_location`]
+pub fn subtotals(reports: &Reports) -> Vec<(&str, Float)> {
+    group_by_location(reports)
+        .into_iter()
+        .map(|(loc, group)| (loc, group.iter().map(|r| r.area).sum()))
+        .collect()
+}
+
 /// Formats reports into a human-readable table string.
 ///
 /// Creates a formatted table showing component breakdown with columns for
-/// name, type, count, location, and area. Includes a total area summary.
+/// name, type, count, location, area, and share of the total area. Rows are
+/// grouped by location, each group followed by its subtotal, and the table
+/// ends with a total area summary.
+///
+/// # Arguments
+/// * `input` - Configuration name to display as header
+/// * `reports` - Collection of reports to format
+///
+/// # Returns
+/// Formatted string containing the complete table
+///
+/// # Examples
+/// ```
+/// use memea::db::CellType;
+/// use memea::export::fmt_direct;
+/// use memea::tabulate::Report;
+///
+/// let report = |name: &str, celltype, loc: &str, area| Report {
+///     name: name.to_string(),
+///     count: 4,
+///     celltype,
+///     loc: loc.to_string(),
+///     area,
+///     copies: 1,
+///     dx_required: None,
+///     dx_selected: None,
+///     leakage: None,
+///     extent: [area / 10.0, 10.0],
+/// };
+/// let reports = vec![
+///     report("cell", CellType::Core, "Array", 100.0),
+///     report("sw", CellType::Switch, "WL", 10.0),
+///     report("dec", CellType::Logic, "WL", 5.0),
+/// ];
+///
+/// let expected = "
+/// Configuration: demo
+/// Area breakdown:
+///     Name                 | Type     | Count    | Location | Area (μm²)  | Share (%)
+///     ---------------------|----------|----------|----------|-------------|----------
+///     cell                 | Core     | 4        | Array    |       100.0 |      87.0
+///     Array subtotal                                        |       100.0 |      87.0
+///     ---------------------|----------|----------|----------|-------------|----------
+///     sw                   | Switch   | 4        | WL       |        10.0 |       8.7
+///     dec                  | Logic    | 4        | WL       |         5.0 |       4.3
+///     WL subtotal                                           |        15.0 |      13.0
+///     ---------------------|----------|----------|----------|-------------|----------
+/// Total area: 115.0 μm²
+/// Macro: 11.5 x 10.0 μm (AR 1.15)
+/// ";
+/// assert_eq!(fmt_direct("demo", &reports), expected);
+///
+/// // Huge areas widen the column, keeping every separator aligned
+/// let mixed = vec![
+///     report("tiny", CellType::Logic, "WL", 0.1),
+///     report("huge", CellType::Core, "WL", 123456789.0),
+/// ];
+/// let table = fmt_direct("demo", &mixed);
+/// let bars: Vec<usize> = table
+///     .lines()
+///     .filter(|l| l.starts_with("    ") && !l.contains("Name"))
+///     .map(|l| l.rfind('|').unwrap())
+///     .collect();
+/// assert!(bars.windows(2).all(|w| w[0] == w[1]));
+/// let widths: Vec<usize> = table
+///     .lines()
+///     .filter(|l| l.starts_with("    ") && !l.contains("Name"))
+///     .map(|l| l.chars().count())
+///     .collect();
+/// assert!(widths.windows(2).all(|w| w[0] == w[1]));
+///
+/// // Without any area, every share is zero
+/// let empty = vec![report("cell", CellType::Core, "Array", 0.0)];
+/// assert!(fmt_direct("demo", &empty).contains("|         0.0 |       0.0\n"));
+/// ```
+pub fn fmt_direct(input: &str, reports: &Reports) -> String {
+    fmt_table(input, reports, false)
+}
+
+/// Formats reports into a table with a heading for each location.
+///
+/// The table is the same as [`fmt_direct`], except that each location's rows
+/// (Array, WL, BL, Well, ...) are preceded by a line naming the location.
 ///
 /// # Arguments
 /// * `input` - Configuration name to display as header
@@ -231,27 +1608,269 @@ fn export_direct(reports: &HashMap<String, Reports>) -> Result<(), MemeaError> {
 ///
 /// # Returns
 /// Formatted string containing the complete table
-fn fmt_direct(input: &str, reports: &Reports) -> String {
+///
+/// # Examples
+/// ```
+/// use memea::db::CellType;
+/// use memea::export::{fmt_direct, fmt_grouped};
+/// use memea::tabulate::Report;
+///
+/// let report = |name: &str, celltype, loc: &str, area| Report {
+///     name: name.to_string(),
+///     count: 4,
+///     celltype,
+///     loc: loc.to_string(),
+///     area,
+///     copies: 1,
+///     dx_required: None,
+///     dx_selected: None,
+///     leakage: None,
+///     extent: [area / 10.0, 10.0],
+/// };
+/// let reports = vec![
+///     report("cell", CellType::Core, "Array", 100.0),
+///     report("sw", CellType::Switch, "WL", 10.0),
+/// ];
+///
+/// let table = fmt_grouped("demo", &reports);
+/// assert!(table.contains("|----------\n    [Array]\n    cell "));
+/// assert!(table.contains("|----------\n    [WL]\n    sw "));
+///
+/// // Apart from the headings, the table is unchanged
+/// let headings = ["    [Array]\n", "    [WL]\n"];
+/// let stripped = headings.iter().fold(table, |t, h| t.replace(h, ""));
+/// assert_eq!(stripped, fmt_direct("demo", &reports));
+/// ```
+pub fn fmt_grouped(input: &str, reports: &Reports) -> String {
+    fmt_table(input, reports, true)
+}
+
+/// Formats the breakdown table, optionally with a heading per location.
+fn fmt_table(input: &str, reports: &Reports, headings: bool) -> String {
+    let note = match reports.first().map(|r| r.copies) {
+        Some(c) if c > 1 => format!(" (x{c} copies)"),
+        _ => String::new(),
+    };
+
+    // Widen the area column to fit the largest value (rows are never larger
+    // than their subtotals)
+    let groups = group_by_location(reports);
+    let area_w = groups
+        .iter()
+        .map(|(_, g)| format!("{:.1}", g.iter().map(|r| r.area).sum::<Float>()).len())
+        .max()
+        .unwrap_or(0)
+        .max(11);
+
+    let rule = format!(
+        "    ---------------------|----------|----------|----------|{}|----------\n",
+        "-".repeat(area_w + 2)
+    );
     let mut content = format!(
-        "\nConfiguration: {input}\n\
+        "\nConfiguration: {input}{note}\n\
         Area breakdown:\n    \
-        Name                 | Type     | Count    | Location | Area (μm²)\n    \
-        ---------------------|----------|----------|----------|------------\n"
+        Name                 | Type     | Count    | Location | {:<area_w$} | Share (%)\n\
+        {rule}",
+        "Area (μm²)"
     );
 
-    for report in reports.iter() {
+    let total = area(reports);
+    for (loc, group) in groups {
+        if headings {
+            content = format!("{content}    [{loc}]\n");
+        }
+        for report in group.iter() {
+            content = format!(
+                "{}    {:<20} | {:<8} | {:<8} | {:<8} | {:>area_w$.1} | {:>9.1}\n",
+                content,
+                report.name,
+                report.celltype.to_string(),
+                report.count,
+                report.loc,
+                report.area,
+                share(report.area, total)
+            );
+        }
+
+        let subtotal: Float = group.iter().map(|r| r.area).sum();
         content = format!(
-            "{}    {:<20} | {:<8} | {:<8} | {:<8} | {:>11.1}\n",
+            "{}    {:<53} | {:>area_w$.1} | {:>9.1}\n{}",
             content,
-            report.name,
-            report.celltype.to_string(),
-            report.count,
-            report.loc,
-            report.area
+            format!("{loc} subtotal"),
+            subtotal,
+            share(subtotal, total),
+            rule
+        );
+    }
+
+    content = format!("{}Total area: {:.1} μm²\n", content, total);
+    content.push_str(&fmt_floorplan(reports));
+    if let Some(l) = leakage(reports) {
+        content = format!("{content}Total leakage: {l:.3e} W\n");
+    }
+
+    content
+}
+
+/// Minimum width of the name column in compact output.
+const MIN_NAME_WIDTH: usize = 8;
+
+/// Truncates text to a maximum number of characters, ending with an ellipsis.
+fn ellipsize(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        text.to_string()
+    } else {
+        let kept: String = text.chars().take(width.saturating_sub(1)).collect();
+        format!("{kept}…")
+    }
+}
+
+/// Formats reports into a table that fits a given terminal width.
+///
+/// Column widths are sized to their contents, and the name column takes the
+/// remaining width, truncating long names with an ellipsis. When the name
+/// column would become too narrow, the location column is dropped (rows stay
+/// grouped under per-location subtotals). Like the full table, each row ends
+/// with its share of the total area in percent. The total area line is never
+/// truncated.
+///
+/// # Arguments
+/// * `input` - Configuration name to display as header
+/// * `reports` - Collection of reports to format
+/// * `width` - Available width in characters
+///
+/// # Returns
+/// Formatted string containing the complete table
+///
+/// # Examples
+/// ```
+/// use memea::db::CellType;
+/// use memea::export::fmt_compact;
+/// use memea::tabulate::Report;
+///
+/// let reports = vec![Report {
+///     name: "a_very_long_switch_cell_name_x16".to_string(),
+///     count: 128,
+///     celltype: CellType::Switch,
+///     loc: "WL".to_string(),
+///     area: 1234.5,
+///     copies: 1,
+///     dx_required: None,
+///     dx_selected: None,
+///     leakage: None,
+///     extent: [0.0, 0.0],
+/// }];
+///
+/// let table = fmt_compact("demo", &reports, 48);
+/// assert!(table.contains("a_very_long…"));
+/// assert!(!table.contains("Loc"));
+/// assert!(table.lines().all(|l| l.chars().count() <= 48));
+/// assert!(table.contains("Total area: 1234.5 μm²"));
+/// assert!(table.lines().nth(3).unwrap().ends_with("| 1234.5 | 100.0"));
+///
+/// // Rows of a location are gathered even when they are not adjacent, and
+/// // the area column fits the subtotals
+/// let row = |name: &str, loc: &str, area| Report {
+///     name: name.to_string(),
+///     loc: loc.to_string(),
+///     area,
+///     ..reports[0].clone()
+/// };
+/// let mixed = vec![row("wl_a", "WL", 9999.0), row("bl", "BL", 1.0), row("wl_b", "WL", 2.0)];
+/// let table = fmt_compact("demo", &mixed, 80);
+/// let lines: Vec<&str> = table.lines().collect();
+/// assert!(lines[4].starts_with("  wl_b") && lines[4].contains("|     2.0 |"));
+/// assert!(lines[5].contains("WL subtotal") && lines[5].contains("| 10001.0 |"));
+/// assert!(lines[6].starts_with("  bl") && lines[6].contains("|     1.0 |"));
+/// ```
+pub fn fmt_compact(input: &str, reports: &Reports, width: usize) -> String {
+    let total = area(reports);
+    let groups = group_by_location(reports);
+
+    // Size the area column by the subtotals, which rows never exceed
+    let area_w = groups
+        .iter()
+        .map(|(_, g)| format!("{:.1}", g.iter().map(|r| r.area).sum::<Float>()).len())
+        .max()
+        .unwrap_or(0)
+        .max(4);
+    let count_w = reports
+        .iter()
+        .map(|r| r.count.to_string().len())
+        .max()
+        .unwrap_or(0)
+        .max(5);
+    let type_w = 6;
+    let share_w = 5;
+    let loc_w = reports
+        .iter()
+        .map(|r| r.loc.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(3);
+
+    // Indent plus type, count, area and share columns with their separators
+    let fixed = 2 + type_w + count_w + area_w + share_w + 4 * 3;
+    let show_loc = width.saturating_sub(fixed + loc_w + 3) >= MIN_NAME_WIDTH;
+    let longest = reports
+        .iter()
+        .map(|r| r.name.chars().count().max(r.loc.chars().count() + 9))
+        .max()
+        .unwrap_or(0)
+        .max(4);
+    let name_w = match show_loc {
+        true => width - fixed - loc_w - 3,
+        false => width.saturating_sub(fixed).max(MIN_NAME_WIDTH),
+    }
+    .min(longest);
+
+    let row = |name: &str, celltype: &str, count: &str, loc: &str, area: &str, share: &str| {
+        let mut line = format!(
+            "  {:<name_w$} | {:<type_w$} | {:>count_w$}",
+            ellipsize(name, name_w),
+            celltype,
+            count
+        );
+        if show_loc {
+            line = format!("{line} | {loc:<loc_w$}");
+        }
+        format!("{line} | {area:>area_w$} | {share:>share_w$}\n")
+    };
+
+    let mut content = format!(
+        "\n{}\n",
+        ellipsize(&format!("Configuration: {input}"), width)
+    );
+    content += &row("Name", "Type", "Count", "Loc", "μm²", "%");
+
+    for (loc, group) in groups {
+        for report in group.iter() {
+            content += &row(
+                &report.name,
+                &report.celltype.to_string(),
+                &report.count.to_string(),
+                &report.loc,
+                &format!("{:.1}", report.area),
+                &format!("{:.1}", share(report.area, total)),
+            );
+        }
+
+        let subtotal: Float = group.iter().map(|r| r.area).sum();
+        content += &row(
+            &format!("{loc} subtotal"),
+            "",
+            "",
+            "",
+            &format!("{subtotal:.1}"),
+            &format!("{:.1}", share(subtotal, total)),
         );
     }
 
-    content = format!("{}Total area: {:.1} μm²\n", content, area(reports));
+    content = format!("{}Total area: {:.1} μm²\n", content, total);
+    content.push_str(&fmt_floorplan(reports));
+    if let Some(l) = leakage(reports) {
+        content = format!("{content}Total leakage: {l:.3e} W\n");
+    }
 
     content
 }
@@ -2,13 +2,20 @@
 //!
 //! This module provides functionality to parse GDS layout files, inspect all
 //! layers, and calculate enclosure size based on the relative difference
-//! between the cell footprint and PR boundary.
-use gds21::{GdsElement, GdsLibrary};
+//! between the cell footprint and PR boundary. It can also measure the true
+//! (polygon union) area of non-rectangular cells on a chosen layer. Libraries
+//! may be gzip-compressed.
+use flate2::read::GzDecoder;
+use gds21::{GdsArrayRef, GdsElement, GdsLibrary, GdsPoint, GdsStrans};
+use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
 use thiserror::Error;
 
 use crate::db::Dims;
-use crate::{errorln, vprintln, Float, MemeaError};
+use crate::{errorln, vprintln, warnln, Float, MemeaError};
 
 /// Errors that can occur during GDS layout processing.
 #[derive(Debug, Error)]
@@ -21,6 +28,131 @@ pub enum GdsError {
     EmptyElement(String),
 }
 
+/// A GDS layer, optionally restricted to one datatype.
+///
+/// Parsed from `LAYER` (any datatype) or `LAYER/DATATYPE`.
+///
+/// # Examples
+/// ```
+/// use memea::gds::LayerSpec;
+///
+/// let any: LayerSpec = "108".parse().unwrap();
+/// assert!(any.matches(108, 0) && any.matches(108, 250));
+///
+/// let exact: LayerSpec = "108/250".parse().unwrap();
+/// assert!(exact.matches(108, 250) && !exact.matches(108, 0));
+/// assert_eq!(exact.to_string(), "108/250");
+///
+/// assert!("pr".parse::<LayerSpec>().is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LayerSpec {
+    /// GDS layer number
+    pub layer: i16,
+    /// GDS datatype, or any datatype if unset
+    pub datatype: Option<i16>,
+}
+
+impl LayerSpec {
+    /// Checks whether an element on `layer`/`datatype` belongs to this layer.
+    pub fn matches(&self, layer: i16, datatype: i16) -> bool {
+        self.layer == layer && self.datatype.is_none_or(|d| d == datatype)
+    }
+}
+
+impl std::str::FromStr for LayerSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |n: &str| {
+            n.trim()
+                .parse::<i16>()
+                .map_err(|e| format!("invalid layer '{s}': {e}"))
+        };
+
+        match s.split_once('/') {
+            Some((layer, datatype)) => Ok(LayerSpec {
+                layer: parse(layer)?,
+                datatype: Some(parse(datatype)?),
+            }),
+            None => Ok(LayerSpec {
+                layer: parse(s)?,
+                datatype: None,
+            }),
+        }
+    }
+}
+
+impl std::fmt::Display for LayerSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.datatype {
+            Some(d) => write!(f, "{}/{}", self.layer, d),
+            None => write!(f, "{}", self.layer),
+        }
+    }
+}
+
+/// Settings for extracting enclosures from GDS cells.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EncOptions {
+    /// Layer holding the place-and-route boundary; all layers are used if
+    /// unset or if a cell has no boundary on it
+    pub boundary_layer: Option<LayerSpec>,
+    /// Fail if a cell is missing instead of using zero enclosure
+    pub strict: bool,
+}
+
+/// First two bytes of every gzip stream.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Loads a GDS library, decompressing it first if it is gzipped.
+///
+/// Compression is detected from the gzip magic bytes, so `.gds.gz` files
+/// (or gzipped files under any other name) are read through an in-memory
+/// buffer; other files are parsed directly by [`GdsLibrary::load`].
+///
+/// # Arguments
+/// * `path` - Path to a GDS or gzip-compressed GDS file
+///
+/// # Returns
+/// * `Ok(GdsLibrary)` - Parsed library
+/// * `Err(MemeaError)` - File I/O, decompression or GDS parsing error
+///
+/// # Examples
+/// ```
+/// use flate2::{write::GzEncoder, Compression};
+/// use gds21::{GdsLibrary, GdsStruct};
+/// use memea::gds::load_library;
+/// use std::fs::File;
+///
+/// let mut lib = GdsLibrary::new("cells");
+/// lib.structs.push(GdsStruct::new("bitcell"));
+///
+/// let dir = std::env::temp_dir();
+/// let plain = dir.join("memea_load_library.gds");
+/// let zipped = dir.join("memea_load_library.gds.gz");
+/// lib.save(&plain).unwrap();
+/// let mut gz = GzEncoder::new(File::create(&zipped).unwrap(), Compression::default());
+/// lib.write(&mut gz).unwrap();
+/// gz.finish().unwrap();
+///
+/// for path in [&plain, &zipped] {
+///     let loaded = load_library(path).unwrap();
+///     assert_eq!(loaded.structs[0].name, "bitcell");
+/// }
+/// ```
+pub fn load_library(path: &Path) -> Result<GdsLibrary, MemeaError> {
+    let mut magic = [0u8; 2];
+    let gzipped = File::open(path)?.read_exact(&mut magic).is_ok() && magic == GZIP_MAGIC;
+    if !gzipped {
+        return Ok(GdsLibrary::load(path)?);
+    }
+
+    let mut bytes = Vec::new();
+    GzDecoder::new(File::open(path)?).read_to_end(&mut bytes)?;
+    Ok(GdsLibrary::from_bytes(bytes)?)
+}
+
 /// Creates a hashmap of GDS library cells indexed by name for fast lookup.
 ///
 /// This function transforms a GDS library into a HashMap where each cell name
@@ -47,28 +179,436 @@ pub fn hash_lib(lib: GdsLibrary) -> HashMap<String, Vec<GdsElement>> {
     lib.structs.into_iter().map(|s| (s.name, s.elems)).collect()
 }
 
+/// Affine transform from a referenced cell's coordinates into its parent's,
+/// stored row-major as `[a, b, c, d, e, f]` for `x' = ax + by + c`,
+/// `y' = dx + ey + f`.
+#[derive(Debug, Clone, Copy)]
+struct Affine([f64; 6]);
+
+impl Affine {
+    const IDENTITY: Affine = Affine([1.0, 0.0, 0.0, 0.0, 1.0, 0.0]);
+
+    /// Placement of a reference at `origin`: reflection about the x-axis,
+    /// then magnification and rotation, then translation. Absolute
+    /// magnification and angle flags are treated as relative.
+    fn placement(origin: (f64, f64), strans: Option<&GdsStrans>) -> Self {
+        let (flip, mag, angle) = strans.map_or((1.0, 1.0, 0.0), |s| {
+            (
+                if s.reflected { -1.0 } else { 1.0 },
+                s.mag.unwrap_or(1.0),
+                s.angle.unwrap_or(0.0).to_radians(),
+            )
+        });
+        let (sin, cos) = angle.sin_cos();
+
+        Affine([
+            mag * cos,
+            -mag * sin * flip,
+            origin.0,
+            mag * sin,
+            mag * cos * flip,
+            origin.1,
+        ])
+    }
+
+    /// Composes two transforms, applying `inner` first.
+    fn compose(&self, inner: &Affine) -> Affine {
+        let [a, b, c, d, e, f] = self.0;
+        let [p, q, r, s, t, u] = inner.0;
+
+        Affine([
+            a * p + b * s,
+            a * q + b * t,
+            a * r + b * u + c,
+            d * p + e * s,
+            d * q + e * t,
+            d * r + e * u + f,
+        ])
+    }
+
+    fn map(&self, x: f64, y: f64) -> (f64, f64) {
+        let [a, b, c, d, e, f] = self.0;
+        (a * x + b * y + c, d * x + e * y + f)
+    }
+
+    fn apply(&self, pt: &GdsPoint) -> GdsPoint {
+        let (x, y) = self.map(pt.x as f64, pt.y as f64);
+        GdsPoint::new(x.round() as i32, y.round() as i32)
+    }
+
+    /// Linear magnification, for scaling path widths.
+    fn mag(&self) -> f64 {
+        (self.0[0] * self.0[4] - self.0[1] * self.0[3]).abs().sqrt()
+    }
+}
+
+/// Moves a boundary, box or path into its parent's coordinates.
+fn transform(elem: &GdsElement, xf: &Affine) -> Option<GdsElement> {
+    match elem {
+        GdsElement::GdsBoundary(b) => {
+            let mut b = b.clone();
+            b.xy = b.xy.iter().map(|p| xf.apply(p)).collect();
+            Some(GdsElement::GdsBoundary(b))
+        }
+        GdsElement::GdsBox(b) => {
+            let mut b = b.clone();
+            b.xy = b.xy.map(|p| xf.apply(&p));
+            Some(GdsElement::GdsBox(b))
+        }
+        GdsElement::GdsPath(p) => {
+            let mut p = p.clone();
+            p.xy = p.xy.iter().map(|pt| xf.apply(pt)).collect();
+            p.width = p.width.map(|w| (w as f64 * xf.mag()).round() as i32);
+            Some(GdsElement::GdsPath(p))
+        }
+        _ => None,
+    }
+}
+
+/// Origin of the instance in column `i`, row `j` of an array reference.
+fn lattice(r: &GdsArrayRef, i: i16, j: i16) -> (f64, f64) {
+    // xy holds the origin and the far corners along columns and rows
+    let [o, c, w] = &r.xy;
+    let (cols, rows) = (r.cols.max(1) as f64, r.rows.max(1) as f64);
+    let (i, j) = (i as f64, j as f64);
+    (
+        o.x as f64 + i * (c.x - o.x) as f64 / cols + j * (w.x - o.x) as f64 / rows,
+        o.y as f64 + i * (c.y - o.y) as f64 / cols + j * (w.y - o.y) as f64 / rows,
+    )
+}
+
+/// Appends the shapes of `name` and everything it references to `out`.
+///
+/// `visiting` holds the cells on the current reference chain, so a cell may
+/// be placed many times but a cycle is cut where it closes.
+fn resolve<'a>(
+    map: &'a HashMap<String, Vec<GdsElement>>,
+    name: &'a str,
+    xf: Affine,
+    visiting: &mut HashSet<&'a str>,
+    out: &mut Vec<GdsElement>,
+) {
+    let Some(elems) = map.get(name) else {
+        warnln!("Referenced GDS cell {} not found; skipping", name);
+        return;
+    };
+    if !visiting.insert(name) {
+        warnln!("Cyclic reference to GDS cell {}; skipping", name);
+        return;
+    }
+
+    for elem in elems {
+        match elem {
+            GdsElement::GdsStructRef(r) => {
+                let origin = (r.xy.x as f64, r.xy.y as f64);
+                let place = Affine::placement(origin, r.strans.as_ref());
+                resolve(map, &r.name, xf.compose(&place), visiting, out);
+            }
+            GdsElement::GdsArrayRef(r) => {
+                let (cols, rows) = (r.cols.max(1), r.rows.max(1));
+                for i in 0..cols {
+                    for j in 0..rows {
+                        let place = Affine::placement(lattice(r, i, j), r.strans.as_ref());
+                        resolve(map, &r.name, xf.compose(&place), visiting, out);
+                    }
+                }
+            }
+            other => out.extend(transform(other, &xf)),
+        }
+    }
+
+    visiting.remove(name);
+}
+
+/// Looks up a cell and resolves its structure references.
+///
+/// Cells placing geometry through SREF/AREF elements are flattened: every
+/// referenced cell's boundaries, boxes and paths are included with its
+/// translation, reflection, rotation and magnification applied. Cyclic
+/// references are reported and cut. Flat cells are returned as-is without
+/// copying.
+///
+/// # Arguments
+/// * `map` - HashMap of cell names to GDS elements (from `hash_lib`)
+/// * `cell` - Name of the cell to look up
+///
+/// # Returns
+/// The cell's shapes in its own coordinates, or `None` if it is not in `map`
+///
+/// # Examples
+/// ```
+/// use gds21::{GdsArrayRef, GdsBoundary, GdsElement, GdsPoint, GdsStrans, GdsStructRef};
+/// use memea::gds::{compute_enc, flatten};
+/// use std::collections::HashMap;
+///
+/// // 100 x 50 unit cell, tiled 4 x 2 and placed again rotated 90° to the right
+/// let unit = GdsElement::GdsBoundary(GdsBoundary {
+///     layer: 1,
+///     xy: GdsPoint::vec(&[(0, 0), (100, 0), (100, 50), (0, 50), (0, 0)]),
+///     ..Default::default()
+/// });
+/// let tile = GdsElement::GdsArrayRef(GdsArrayRef {
+///     name: "unit".into(),
+///     xy: [GdsPoint::new(0, 0), GdsPoint::new(400, 0), GdsPoint::new(0, 100)],
+///     cols: 4,
+///     rows: 2,
+///     ..Default::default()
+/// });
+/// let turned = GdsElement::GdsStructRef(GdsStructRef {
+///     name: "unit".into(),
+///     xy: GdsPoint::new(500, 0),
+///     strans: Some(GdsStrans { angle: Some(90.0), ..Default::default() }),
+///     ..Default::default()
+/// });
+///
+/// let mut map = HashMap::new();
+/// map.insert("unit".to_string(), vec![unit]);
+/// map.insert("top".to_string(), vec![tile, turned]);
+///
+/// let elems = flatten(&map, "top").unwrap();
+/// assert_eq!(elems.len(), 9);
+///
+/// // Spans x from 0 to 500 (rotated cell covers 450..500) and y from 0 to 100
+/// let (enc_x, enc_y) = compute_enc(&elems, 0.3, 0.06, 1e-9, None, false).unwrap();
+/// assert!((enc_x - 0.1).abs() < 1e-4 && (enc_y - 0.02).abs() < 1e-4);
+///
+/// // Cycles are cut rather than followed forever
+/// let back = GdsElement::GdsStructRef(GdsStructRef { name: "top".into(), ..Default::default() });
+/// map.get_mut("unit").unwrap().push(back);
+/// assert_eq!(flatten(&map, "top").unwrap().len(), 9);
+/// assert!(flatten(&map, "missing").is_none());
+/// ```
+pub fn flatten<'a>(
+    map: &'a HashMap<String, Vec<GdsElement>>,
+    cell: &str,
+) -> Option<Cow<'a, [GdsElement]>> {
+    let (name, elems) = map.get_key_value(cell)?;
+    let hierarchical = elems.iter().any(|elem| {
+        matches!(
+            elem,
+            GdsElement::GdsStructRef(_) | GdsElement::GdsArrayRef(_)
+        )
+    });
+    if !hierarchical {
+        return Some(Cow::Borrowed(elems));
+    }
+
+    let mut out = Vec::new();
+    resolve(map, name, Affine::IDENTITY, &mut HashSet::new(), &mut out);
+    Some(Cow::Owned(out))
+}
+
+/// Extracts the layer, datatype, points and half-width of a shape element.
+///
+/// Boundaries and boxes have no width; a path's points are its centerline,
+/// so its half-width (in database units) is returned for the caller to pad
+/// by. Box types stand in for datatypes. Other elements yield `None`.
+fn shape(elem: &GdsElement) -> Option<(i16, i16, &[GdsPoint], i32)> {
+    match elem {
+        GdsElement::GdsBoundary(b) => Some((b.layer, b.datatype, &b.xy[..], 0)),
+        GdsElement::GdsBox(b) => Some((b.layer, b.boxtype, &b.xy[..], 0)),
+        GdsElement::GdsPath(p) => Some((
+            p.layer,
+            p.datatype,
+            &p.xy[..],
+            p.width.unwrap_or(0).abs() / 2,
+        )),
+        _ => None,
+    }
+}
+
+/// Bounding box (in database units) and tally of a cell's shapes.
+#[derive(Debug, Clone, Default)]
+struct Extent {
+    bbox: Option<[f64; 4]>,
+    shapes: usize,
+    layers: HashSet<i16>,
+    /// Summed area of the boundaries (see [`shoelace`]), in database units
+    /// squared
+    polygon: f64,
+}
+
+impl Extent {
+    /// Measures the boundaries, boxes and paths in `elems`, widening paths
+    /// by half their width, and sums the area of the boundaries, optionally
+    /// only those on `layer`. References are not followed.
+    fn of(elems: &[GdsElement], layer: Option<LayerSpec>) -> Self {
+        let mut ext = Extent::default();
+        for (elem, (l, _, xy, half)) in elems
+            .iter()
+            .filter_map(|elem| Some((elem, shape(elem)?)))
+            .filter(|&(_, (l, d, _, _))| layer.is_none_or(|spec| spec.matches(l, d)))
+        {
+            if let GdsElement::GdsBoundary(_) = elem {
+                let points: Vec<(f64, f64)> = xy.iter().map(|p| (p.x as f64, p.y as f64)).collect();
+                ext.polygon += shoelace(&points);
+            }
+            ext.shapes += 1;
+            ext.layers.insert(l);
+            let half = half as f64;
+            for p in xy {
+                let (x, y) = (p.x as f64, p.y as f64);
+                ext.cover(x - half, y - half);
+                ext.cover(x + half, y + half);
+            }
+        }
+        ext
+    }
+
+    fn cover(&mut self, x: f64, y: f64) {
+        self.bbox = Some(match self.bbox {
+            Some([x0, y0, x1, y1]) => [x0.min(x), y0.min(y), x1.max(x), y1.max(y)],
+            None => [x, y, x, y],
+        });
+    }
+
+    /// Grows the box to cover `other` placed by `xf`. Shape counts are left
+    /// to the caller, since one placement may stand for a whole array.
+    fn place(&mut self, other: &Extent, xf: &Affine) {
+        self.layers.extend(&other.layers);
+        if let Some([x0, y0, x1, y1]) = other.bbox {
+            for (x, y) in [(x0, y0), (x1, y0), (x1, y1), (x0, y1)] {
+                let (x, y) = xf.map(x, y);
+                self.cover(x, y);
+            }
+        }
+    }
+}
+
+/// Measures `name` and everything it references without copying shapes.
+///
+/// Each cell is measured once in its own coordinates and its box is
+/// transformed into every placement. An array's instances differ only by
+/// translation, so its corner instances bound the rest.
+fn cell_extent<'a>(
+    map: &'a HashMap<String, Vec<GdsElement>>,
+    name: &'a str,
+    layer: Option<LayerSpec>,
+    visiting: &mut HashSet<&'a str>,
+    memo: &mut HashMap<&'a str, Extent>,
+) -> Extent {
+    if let Some(ext) = memo.get(name) {
+        return ext.clone();
+    }
+    let Some(elems) = map.get(name) else {
+        warnln!("Referenced GDS cell {} not found; skipping", name);
+        return Extent::default();
+    };
+    if !visiting.insert(name) {
+        warnln!("Cyclic reference to GDS cell {}; skipping", name);
+        return Extent::default();
+    }
+
+    let mut ext = Extent::of(elems, layer);
+    for elem in elems {
+        match elem {
+            GdsElement::GdsStructRef(r) => {
+                let child = cell_extent(map, &r.name, layer, visiting, memo);
+                let origin = (r.xy.x as f64, r.xy.y as f64);
+                let place = Affine::placement(origin, r.strans.as_ref());
+                ext.place(&child, &place);
+                ext.shapes += child.shapes;
+                ext.polygon += child.polygon * place.mag().powi(2);
+            }
+            GdsElement::GdsArrayRef(r) => {
+                let child = cell_extent(map, &r.name, layer, visiting, memo);
+                let (cols, rows) = (r.cols.max(1), r.rows.max(1));
+                for (i, j) in [(0, 0), (cols - 1, 0), (0, rows - 1), (cols - 1, rows - 1)] {
+                    let place = Affine::placement(lattice(r, i, j), r.strans.as_ref());
+                    ext.place(&child, &place);
+                }
+                let copies = cols as usize * rows as usize;
+                let mag = Affine::placement((0.0, 0.0), r.strans.as_ref()).mag();
+                ext.shapes += child.shapes * copies;
+                ext.polygon += child.polygon * mag.powi(2) * copies as f64;
+            }
+            _ => {}
+        }
+    }
+
+    visiting.remove(name);
+    memo.insert(name, ext.clone());
+    ext
+}
+
 /// Computes enclosure requirements from GDS geometry elements.
 ///
-/// This function analyzes the boundary polygons in a GDS cell to determine
-/// the enclosure margins needed around the core component dimensions. It
-/// calculates the bounding box of all geometry and computes the difference
+/// This function analyzes the boundaries, boxes and paths in a GDS cell to
+/// determine the enclosure margins needed around the core component
+/// dimensions. It calculates the bounding box of the geometry, widening
+/// paths by half their width on each side, and computes the difference
 /// between the total span and the core dimensions.
 ///
+/// With a `boundary_layer`, only shapes on that layer are measured, so
+/// routing and fill on other layers do not inflate the enclosure; if the cell
+/// has no shape on the layer, every layer is used instead.
+///
 /// # Arguments
-/// * `elems` - Vector of GDS elements containing boundary polygons
+/// * `elems` - Vector of GDS elements containing boundaries, boxes or paths
 /// * `w` - Core component width in micrometers
 /// * `h` - Core component height in micrometers
 /// * `units` - GDS unit conversion factor (database units to meters)
+/// * `boundary_layer` - Layer holding the place-and-route boundary, if any
 /// * `verbose` - Whether to print detailed computation information
 ///
 /// # Returns
 /// * `Ok((enc_x, enc_y))` - Horizontal and vertical enclosure margins
 /// * `Err(MemeaError)` - Error if no valid geometry is found
-fn compute_enc(
-    elems: &Vec<GdsElement>,
+///
+/// # Examples
+/// ```
+/// use gds21::{GdsBoundary, GdsElement, GdsPoint};
+/// use memea::gds::compute_enc;
+///
+/// // 2 x 1 μm outline (1 nm database units) around a 1.6 x 0.6 μm cell
+/// let outline = GdsElement::GdsBoundary(GdsBoundary {
+///     layer: 1,
+///     xy: GdsPoint::vec(&[(0, 0), (2000, 0), (2000, 1000), (0, 1000), (0, 0)]),
+///     ..Default::default()
+/// });
+/// // Fill on another layer reaching 0.5 μm further out
+/// let fill = GdsElement::GdsBoundary(GdsBoundary {
+///     layer: 2,
+///     xy: GdsPoint::vec(&[(-500, -500), (2500, -500), (2500, 1500), (-500, 1500), (-500, -500)]),
+///     ..Default::default()
+/// });
+/// let elems = vec![outline, fill];
+///
+/// let pr = Some("1".parse().unwrap());
+/// let (enc_x, enc_y) = compute_enc(&elems, 1.6, 0.6, 1e-9, pr, false).unwrap();
+/// assert!((enc_x - 0.2).abs() < 1e-4 && (enc_y - 0.2).abs() < 1e-4);
+///
+/// // Without the layer (or with a layer the cell lacks), fill counts too
+/// let (enc_x, _) = compute_enc(&elems, 1.6, 0.6, 1e-9, None, false).unwrap();
+/// assert!((enc_x - 0.7).abs() < 1e-4);
+/// let missing = Some("9".parse().unwrap());
+/// assert_eq!(compute_enc(&elems, 1.6, 0.6, 1e-9, missing, false).unwrap().0, enc_x);
+///
+/// // The same outline drawn as a box, plus a 0.2 μm wide path along its top
+/// use gds21::{GdsBox, GdsPath};
+/// let outline = GdsElement::GdsBox(GdsBox {
+///     layer: 1,
+///     xy: [(0, 0), (2000, 0), (2000, 1000), (0, 1000), (0, 0)].map(|(x, y)| GdsPoint::new(x, y)),
+///     ..Default::default()
+/// });
+/// let (enc_x, enc_y) = compute_enc(&vec![outline.clone()], 1.6, 0.6, 1e-9, None, false).unwrap();
+/// assert!((enc_x - 0.2).abs() < 1e-4 && (enc_y - 0.2).abs() < 1e-4);
+///
+/// let rail = GdsElement::GdsPath(GdsPath {
+///     layer: 3,
+///     xy: GdsPoint::vec(&[(0, 1000), (2000, 1000)]),
+///     width: Some(200),
+///     ..Default::default()
+/// });
+/// let (enc_x, enc_y) = compute_enc(&vec![outline, rail], 1.6, 0.6, 1e-9, None, false).unwrap();
+/// assert!((enc_x - 0.3).abs() < 1e-4 && (enc_y - 0.25).abs() < 1e-4);
+/// ```
+pub fn compute_enc(
+    elems: &[GdsElement],
     w: Float,
     h: Float,
     units: f64,
+    boundary_layer: Option<LayerSpec>,
     verbose: bool,
 ) -> Result<(Float, Float), MemeaError> {
     if elems.is_empty() {
@@ -76,44 +616,46 @@ fn compute_enc(
         return Ok((0.0, 0.0));
     }
 
-    let mut boundaries: usize = 0;
-    let mut layers = HashSet::new();
+    let enc = enclosure(
+        |layer| Extent::of(elems, layer),
+        w,
+        h,
+        units,
+        boundary_layer,
+        verbose,
+    );
+    enc.ok_or_else(|| GdsError::EmptyElement(format!("{elems:?}")).into())
+}
 
-    let mut iter = elems
-        .iter()
-        .filter_map(|elem| {
-            if let GdsElement::GdsBoundary(b) = elem {
-                boundaries += 1;
-                layers.insert(b.layer);
-                Some(b.xy.iter())
-            } else {
-                None
+/// Turns the extent measured by `extent` into enclosure margins around a
+/// `w` x `h` μm core, preferring shapes on `boundary_layer` when there are
+/// any. Returns `None` if the cell has no shapes at all.
+fn enclosure(
+    mut extent: impl FnMut(Option<LayerSpec>) -> Extent,
+    w: Float,
+    h: Float,
+    units: f64,
+    boundary_layer: Option<LayerSpec>,
+    verbose: bool,
+) -> Option<(Float, Float)> {
+    // Restrict to the PR boundary layer if the cell has geometry on it
+    let on_layer = boundary_layer
+        .map(|spec| (spec, extent(Some(spec))))
+        .filter(|(_, ext)| ext.shapes > 0);
+    let (layer, ext) = match on_layer {
+        Some((spec, ext)) => (Some(spec), ext),
+        None => {
+            if let Some(spec) = boundary_layer {
+                vprintln!(
+                    verbose,
+                    "No shapes on layer {}; using all layers for enclosure",
+                    spec
+                );
             }
-        })
-        .flatten();
-
-    let first = iter
-        .next()
-        .ok_or(GdsError::EmptyElement(format!("{elems:?}")))?;
-    let mut min_x = first.x;
-    let mut max_x = first.x;
-    let mut min_y = first.y;
-    let mut max_y = first.y;
-
-    for p in iter.skip(1) {
-        if p.x < min_x {
-            min_x = p.x;
-        }
-        if p.x > max_x {
-            max_x = p.x;
-        }
-        if p.y < min_y {
-            min_y = p.y;
+            (None, extent(None))
         }
-        if p.y > max_y {
-            max_y = p.y;
-        }
-    }
+    };
+    let [min_x, min_y, max_x, max_y] = ext.bbox?;
 
     let scale = units as f32 / 1e-6;
     let (span_x, span_y) = (
@@ -122,24 +664,267 @@ fn compute_enc(
     );
     let (enc_x, enc_y) = ((span_x - w) / 2.0, (span_y - h) / 2.0);
 
-    vprintln!(
-        verbose,
-        "Computed enclosure [{:.4}, {:.4}] from {} polygons across {} layers",
-        enc_x,
-        enc_y,
-        boundaries,
-        layers.len()
-    );
+    match layer {
+        Some(spec) => vprintln!(
+            verbose,
+            "Computed enclosure [{:.4}, {:.4}] from {} shapes on boundary layer {}",
+            enc_x,
+            enc_y,
+            ext.shapes,
+            spec
+        ),
+        None => vprintln!(
+            verbose,
+            "Computed enclosure [{:.4}, {:.4}] from {} shapes across {} layers",
+            enc_x,
+            enc_y,
+            ext.shapes,
+            ext.layers.len()
+        ),
+    }
+
+    // Non-rectangular outlines cover less than their bounding box
+    if let Some(spec) = layer {
+        vprintln!(
+            verbose,
+            "Polygon area on layer {}: {:.4} μm² (bounding box {:.4} μm²)",
+            spec,
+            ext.polygon as f32 * scale * scale,
+            span_x * span_y
+        );
+    }
+
+    Some((enc_x as Float, enc_y as Float))
+}
+
+/// Computes the area of the boundaries on one layer.
+///
+/// Each boundary's area is found with the [`shoelace`] formula and the areas
+/// are summed, so overlapping boundaries count twice (see [`footprint`] for
+/// their union). Unlike a bounding box, this follows non-rectangular (e.g.
+/// L-shaped) outlines. References are not followed; [`augment_dims`] reports
+/// the same area for whole hierarchies in verbose mode.
+///
+/// # Arguments
+/// * `elems` - GDS elements of the cell
+/// * `layer` - Layer (and optionally datatype) to measure
+/// * `units` - GDS unit conversion factor (database units to meters)
+///
+/// # Returns
+/// Summed boundary area in μm² (zero if the layer has no boundaries)
+///
+/// # Examples
+/// ```
+/// use gds21::{GdsBoundary, GdsElement, GdsPoint};
+/// use memea::gds::compute_polygon_area;
+///
+/// let boundary = |layer, xy: &[(i32, i32)]| {
+///     GdsElement::GdsBoundary(GdsBoundary { layer, xy: GdsPoint::vec(xy), ..Default::default() })
+/// };
+/// let layer = "1".parse().unwrap();
+///
+/// // 2 x 1 μm rectangle (1 nm database units): area is w * h
+/// let rect = boundary(1, &[(0, 0), (2000, 0), (2000, 1000), (0, 1000), (0, 0)]);
+/// assert!((compute_polygon_area(&[rect.clone()], layer, 1e-9) - 2.0).abs() < 1e-4);
+///
+/// // 2 x 2 μm L shape missing its top-right quadrant, plus a shape on another layer
+/// let l = boundary(1, &[(0, 0), (2000, 0), (2000, 1000), (1000, 1000), (1000, 2000), (0, 2000), (0, 0)]);
+/// let other = boundary(2, &[(0, 0), (5000, 0), (5000, 5000), (0, 5000), (0, 0)]);
+/// assert!((compute_polygon_area(&[l.clone(), other], layer, 1e-9) - 3.0).abs() < 1e-4);
+///
+/// // Boundaries on the layer are summed
+/// assert!((compute_polygon_area(&[rect, l], layer, 1e-9) - 5.0).abs() < 1e-4);
+/// ```
+pub fn compute_polygon_area(elems: &[GdsElement], layer: LayerSpec, units: f64) -> Float {
+    let scale = units / 1e-6;
+    (Extent::of(elems, Some(layer)).polygon * scale * scale) as Float
+}
+
+/// Computes the area enclosed by a simple polygon with the shoelace formula.
+///
+/// The polygon may be given open or closed (first point repeated at the end,
+/// as in GDS boundaries), in either winding order.
+///
+/// # Arguments
+/// * `points` - Polygon vertices in order
+///
+/// # Returns
+/// Enclosed area, in the square of the input unit
+///
+/// # Examples
+/// ```
+/// use memea::gds::shoelace;
+///
+/// // L shape: 2x2 square with its top-right 1x1 quadrant removed
+/// let l = [(0.0, 0.0), (2.0, 0.0), (2.0, 1.0), (1.0, 1.0), (1.0, 2.0), (0.0, 2.0)];
+/// assert_eq!(shoelace(&l), 3.0);
+/// ```
+pub fn shoelace(points: &[(f64, f64)]) -> f64 {
+    let n = points.len();
+    let twice: f64 = (0..n)
+        .map(|i| {
+            let (x0, y0) = points[i];
+            let (x1, y1) = points[(i + 1) % n];
+            x0 * y1 - x1 * y0
+        })
+        .sum();
+
+    twice.abs() / 2.0
+}
+
+/// Computes the area covered by the union of several polygons.
+///
+/// Overlapping regions are counted once and disjoint polygons are summed.
+/// The plane is cut into vertical slabs at every vertex x-coordinate; within a
+/// slab, each polygon's cross-section is found by even-odd edge crossings at
+/// the slab center, the intervals of all polygons are merged, and the covered
+/// height is multiplied by the slab width. This is exact for Manhattan
+/// geometry and for any polygons whose edges do not cross between vertices.
+///
+/// # Arguments
+/// * `polygons` - Polygons as vertex lists (open or closed)
+///
+/// # Returns
+/// Covered area, in the square of the input unit
+///
+/// # Examples
+/// ```
+/// use memea::gds::union_area;
+///
+/// let square = |x: f64, y: f64, s: f64| vec![(x, y), (x + s, y), (x + s, y + s), (x, y + s)];
+///
+/// // Two overlapping 2x2 squares sharing a 1x1 corner
+/// assert_eq!(union_area(&[square(0.0, 0.0, 2.0), square(1.0, 1.0, 2.0)]), 7.0);
+/// // Two disjoint squares
+/// assert_eq!(union_area(&[square(0.0, 0.0, 1.0), square(5.0, 5.0, 2.0)]), 5.0);
+/// // An L shape built from two abutting rectangles
+/// let l = [vec![(0.0, 0.0), (2.0, 0.0), (2.0, 1.0), (0.0, 1.0)], square(0.0, 1.0, 1.0)];
+/// assert_eq!(union_area(&l), 3.0);
+/// ```
+pub fn union_area(polygons: &[Vec<(f64, f64)>]) -> f64 {
+    let mut xs: Vec<f64> = polygons.iter().flatten().map(|p| p.0).collect();
+    xs.sort_by(f64::total_cmp);
+    xs.dedup();
+
+    let mut area = 0.0;
+    for slab in xs.windows(2) {
+        let x = (slab[0] + slab[1]) / 2.0;
+        let mut spans: Vec<(f64, f64)> = Vec::new();
+
+        for poly in polygons {
+            let n = poly.len();
+            let mut ys: Vec<f64> = (0..n)
+                .filter_map(|i| {
+                    let (x0, y0) = poly[i];
+                    let (x1, y1) = poly[(i + 1) % n];
+                    ((x0 < x) != (x1 < x)).then(|| y0 + (x - x0) * (y1 - y0) / (x1 - x0))
+                })
+                .collect();
+            ys.sort_by(f64::total_cmp);
+            spans.extend(ys.chunks_exact(2).map(|c| (c[0], c[1])));
+        }
+
+        spans.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let mut covered = 0.0;
+        let mut current: Option<(f64, f64)> = None;
+        for (lo, hi) in spans {
+            current = match current {
+                Some((a, b)) if lo <= b => Some((a, b.max(hi))),
+                Some((a, b)) => {
+                    covered += b - a;
+                    Some((lo, hi))
+                }
+                None => Some((lo, hi)),
+            };
+        }
+        if let Some((a, b)) = current {
+            covered += b - a;
+        }
 
-    Ok((enc_x as Float, enc_y as Float))
+        area += covered * (slab[1] - slab[0]);
+    }
+
+    area
+}
+
+/// Measures the true footprint of a cell from its polygons on one layer.
+///
+/// Unlike the bounding box used by [`compute_enc`], this follows L-shaped
+/// or notched outlines, and overlapping polygons are counted once.
+///
+/// # Arguments
+/// * `elems` - GDS elements of the cell
+/// * `layer` - Layer (and optionally datatype) whose boundary polygons
+///   outline the cell
+/// * `units` - GDS unit conversion factor (database units to meters)
+///
+/// # Returns
+/// Union area of the layer's polygons in μm², or `None` if the layer has none
+///
+/// # Examples
+/// ```
+/// use gds21::{GdsBoundary, GdsElement, GdsPoint};
+/// use memea::gds::footprint;
+///
+/// let boundary = |datatype: i16, xy: &[(i32, i32)]| {
+///     GdsElement::GdsBoundary(GdsBoundary {
+///         layer: 108,
+///         datatype,
+///         xy: GdsPoint::vec(xy),
+///         ..Default::default()
+///     })
+/// };
+/// let layer = "108".parse().unwrap();
+///
+/// // 2 x 1 μm rectangle (1 nm database units): area is w * h
+/// let rect = boundary(0, &[(0, 0), (2000, 0), (2000, 1000), (0, 1000), (0, 0)]);
+/// let area = footprint(&[rect.clone()], layer, 1e-9).unwrap();
+/// assert!((area - 2.0).abs() < 1e-6);
+///
+/// // 2 x 2 μm L shape missing its top-right quadrant: 3 μm², not the 4 μm² box
+/// let l = boundary(0, &[(0, 0), (2000, 0), (2000, 1000), (1000, 1000), (1000, 2000), (0, 2000)]);
+/// let area = footprint(&[l.clone()], layer, 1e-9).unwrap();
+/// assert!((area - 3.0).abs() < 1e-6);
+///
+/// // Overlaps count once; other datatypes are skipped when one is given
+/// let area = footprint(&[l.clone(), rect.clone()], layer, 1e-9).unwrap();
+/// assert!((area - 3.0).abs() < 1e-6);
+/// let fill = boundary(5, &[(3000, 0), (4000, 0), (4000, 1000), (3000, 1000)]);
+/// let area = footprint(&[l.clone(), fill], "108/0".parse().unwrap(), 1e-9).unwrap();
+/// assert!((area - 3.0).abs() < 1e-6);
+/// assert_eq!(footprint(&[l], "1".parse().unwrap(), 1e-9), None);
+/// ```
+pub fn footprint(elems: &[GdsElement], layer: LayerSpec, units: f64) -> Option<Float> {
+    let scale = units / 1e-6;
+    let polygons: Vec<Vec<(f64, f64)>> = elems
+        .iter()
+        .filter_map(|elem| match elem {
+            GdsElement::GdsBoundary(b) if layer.matches(b.layer, b.datatype) => Some(
+                b.xy.iter()
+                    .map(|p| (p.x as f64 * scale, p.y as f64 * scale))
+                    .collect(),
+            ),
+            _ => None,
+        })
+        .collect();
+
+    match polygons.is_empty() {
+        true => None,
+        false => Some(union_area(&polygons) as Float),
+    }
 }
 
 /// Augments component dimensions with enclosure data from GDS layout.
 ///
 /// This function looks up a cell in the GDS library hashmap and computes
-/// the required enclosure margins by analyzing the cell's geometry. It
+/// the required enclosure margins by analyzing the cell's geometry,
+/// including geometry placed through structure references. Referenced
+/// cells are measured once and their bounding boxes transformed into each
+/// placement, so large arrays cost no more than a single instance. It
 /// returns a complete `Dims` structure with both core dimensions and
-/// enclosure requirements.
+/// enclosure requirements. With a boundary layer, verbose output also
+/// reports the summed polygon area on that layer (see
+/// [`compute_polygon_area`]) next to the bounding box it is measured from.
 ///
 /// # Arguments
 /// * `map` - HashMap of cell names to GDS elements (from `hash_lib`)
@@ -147,24 +932,51 @@ fn compute_enc(
 /// * `w` - Core component width in micrometers
 /// * `h` - Core component height in micrometers
 /// * `units` - GDS unit conversion factor
+/// * `opts` - Boundary layer, and whether to fail if the cell is missing
+///   instead of using zero enclosure
 /// * `verbose` - Whether to show detailed computation output
 ///
 /// # Returns
-/// * `Ok(Dims)` - Complete dimensions with enclosure data
-/// * `Err(MemeaError)` - Error during geometry analysis
+/// * `Ok(Dims)` - Complete dimensions with enclosure data (zero enclosure if
+///   the cell is missing and `strict` is unset)
+/// * `Err(MemeaError)` - Error during geometry analysis, or the cell is missing
+///   and `strict` is set
 ///
 /// # Examples
-/// ```no_run
-/// use memea::gds::{hash_lib, augment_dims};
-/// use gds21::GdsLibrary;
+/// ```
+/// use gds21::{GdsArrayRef, GdsBoundary, GdsElement, GdsPoint, GdsStrans};
+/// use memea::gds::{augment_dims, EncOptions};
+/// use std::collections::HashMap;
 ///
-/// let library = GdsLibrary::load("cells.gds").expect("Failed to load GDS");
-/// let cell_map = hash_lib(library);
-/// let units = 1e-9; // 1 nm database units
+/// // 100 x 50 unit cell (1 nm database units), tiled 1000 x 1000 and
+/// // mirrored about the x-axis so the array grows downwards
+/// let unit = GdsElement::GdsBoundary(GdsBoundary {
+///     layer: 1,
+///     xy: GdsPoint::vec(&[(0, 0), (100, 0), (100, 50), (0, 50), (0, 0)]),
+///     ..Default::default()
+/// });
+/// let array = GdsElement::GdsArrayRef(GdsArrayRef {
+///     name: "unit".into(),
+///     xy: [GdsPoint::new(0, 0), GdsPoint::new(100_000, 0), GdsPoint::new(0, -50_000)],
+///     cols: 1000,
+///     rows: 1000,
+///     strans: Some(GdsStrans { reflected: true, ..Default::default() }),
+///     ..Default::default()
+/// });
 ///
-/// let dims = augment_dims(&cell_map, "sram_6t", 0.5, 0.8, units, true)
-///     .expect("Failed to compute dimensions");
-/// println!("Cell area: {:.2} μm²", dims.area((1, 1)));
+/// let mut map = HashMap::new();
+/// map.insert("unit".to_string(), vec![unit]);
+/// map.insert("macro".to_string(), vec![array]);
+///
+/// // Spans 100 x 50 μm around a 99 x 49 μm core
+/// let dims = augment_dims(&map, "macro", 99.0, 49.0, 1e-9, EncOptions::default(), false).unwrap();
+/// assert!((dims.enc[0] - 0.5).abs() < 1e-3 && (dims.enc[1] - 0.5).abs() < 1e-3);
+///
+/// // Missing cells get zero enclosure unless strict
+/// let dims = augment_dims(&map, "missing", 1.0, 1.0, 1e-9, EncOptions::default(), false).unwrap();
+/// assert_eq!(dims.enc, [0.0, 0.0]);
+/// let strict = EncOptions { strict: true, ..Default::default() };
+/// assert!(augment_dims(&map, "missing", 1.0, 1.0, 1e-9, strict, false).is_err());
 /// ```
 pub fn augment_dims(
     map: &HashMap<String, Vec<GdsElement>>,
@@ -172,12 +984,27 @@ pub fn augment_dims(
     w: Float,
     h: Float,
     units: f64,
+    opts: EncOptions,
     verbose: bool,
 ) -> Result<Dims, MemeaError> {
     // Lookup cell
-    if let Some(elems) = map.get(cell) {
-        let (enc_x, enc_y) = compute_enc(elems, w, h, units, verbose)?;
+    if let Some((name, elems)) = map.get_key_value(cell) {
+        if elems.is_empty() {
+            errorln!("No geometry data for cell; cannot compute enclosure.");
+            return Ok(Dims::from(w, h, 0.0, 0.0));
+        }
+        let (enc_x, enc_y) = enclosure(
+            |layer| cell_extent(map, name, layer, &mut HashSet::new(), &mut HashMap::new()),
+            w,
+            h,
+            units,
+            opts.boundary_layer,
+            verbose,
+        )
+        .ok_or_else(|| GdsError::EmptyElement(cell.to_string()))?;
         Ok(Dims::from(w, h, enc_x, enc_y))
+    } else if opts.strict {
+        Err(GdsError::InvalidCell(cell.to_string()).into())
     } else {
         errorln!(
             "Could not find matching cell {} in GDS database; cannot compute enclosure",
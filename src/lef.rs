@@ -4,16 +4,21 @@
 //! and create component databases. It extracts cell dimensions from LEF files and
 //! optionally augments them with enclosure data from corresponding GDS layout files.
 //! The resulting data is saved as a component database for use in area estimation.
+//! A companion DEF file, if given, limits the build to instantiated macros and
+//! suggests cell types from how often each macro is placed.
 
 use dialoguer::Input;
-use gds21::GdsLibrary;
+use gds21::GdsElement;
 use regex::Regex;
+use std::collections::HashMap;
 use std::fs::{metadata, File};
 use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
 use thiserror::Error;
 
-use crate::{check_filetype, errorln, query, vprintln, warnln, Float, MemeaError};
+use crate::{
+    check_filetype, errorln, infoln, query, read_text, vprintln, warnln, Float, MemeaError,
+};
 use crate::{db::*, gds, FileCompleter, QueryDefault};
 
 /// Errors that can occur during LEF file parsing.
@@ -25,6 +30,146 @@ pub enum LefError {
     /// Indicates that a SIZE line in the LEF file cannot be parsed.
     #[error("Malformed SIZE line: {0}")]
     InvalidSize(String),
+    /// Indicates that the UNITS block in the LEF file cannot be parsed.
+    #[error("Malformed UNITS block: {0}")]
+    InvalidUnits(String),
+    /// Indicates that a RECT statement in a PIN or OBS block cannot be parsed.
+    #[error("Malformed RECT statement: {0}")]
+    InvalidRect(String),
+}
+
+/// How the cell type of each LEF macro is decided.
+#[derive(Debug, Clone, Default)]
+pub enum Classify {
+    /// Ask for every macro, along with its parameters (default)
+    #[default]
+    Interactive,
+    /// Add every macro as this type
+    All(CellType),
+    /// Add each macro listed in the map as its type; others are skipped
+    Map(HashMap<String, CellType>),
+}
+
+/// Settings for building a database from LEF and GDS files.
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    /// Override for the GDS database unit in meters
+    pub gds_units: Option<f64>,
+    /// Fail when a LEF macro is missing from the GDS file
+    pub strict_gds: bool,
+    /// GDS layer whose polygons give each cell's true footprint
+    pub footprint_layer: Option<gds::LayerSpec>,
+    /// GDS layer holding each cell's place-and-route boundary
+    pub boundary_layer: Option<gds::LayerSpec>,
+    /// How cell types are assigned; non-interactive modes zero all parameters
+    pub classify: Classify,
+    /// Merge new cells into the output database if it already exists
+    pub append: bool,
+    /// Read enclosures of each LEF file from a GDS file of the same stem
+    /// beside it, when no shared GDS file is given
+    pub pair_gds: bool,
+    /// Only import macros whose name this pattern matches (anywhere in the
+    /// name, unless anchored)
+    pub macro_filter: Option<Regex>,
+}
+
+/// Reads a map from macro name to cell type.
+///
+/// The file is YAML with one `macro: type` entry per line, where the type is
+/// any name accepted by [`CellType`]'s `FromStr` (e.g. `core`, `sw`, `adc`).
+///
+/// # Arguments
+/// * `path` - Path to the YAML type map
+///
+/// # Returns
+/// * `Ok(HashMap<String, CellType>)` - Cell type per macro name
+/// * `Err(MemeaError)` - I/O or YAML error, or an unknown cell type
+///
+/// # Examples
+/// ```
+/// use memea::db::CellType;
+/// use memea::lef::read_type_map;
+///
+/// let path = std::env::temp_dir().join("memea_type_map_doc.yaml");
+/// std::fs::write(&path, "bitcell: core\nwldrv: sw\n").unwrap();
+/// let map = read_type_map(&path).unwrap();
+/// assert_eq!(map["bitcell"], CellType::Core);
+/// assert_eq!(map["wldrv"], CellType::Switch);
+///
+/// std::fs::write(&path, "bitcell: memory\n").unwrap();
+/// assert!(read_type_map(&path).is_err());
+/// ```
+pub fn read_type_map(path: &Path) -> Result<HashMap<String, CellType>, MemeaError> {
+    let raw: HashMap<String, String> = serde_yaml::from_str(&read_text(path)?)?;
+
+    raw.into_iter()
+        .map(|(name, t)| Ok((name, t.parse()?)))
+        .collect()
+}
+
+/// Expands LEF inputs, replacing each directory with the LEF files in it.
+///
+/// Files directly inside a directory whose extension is `lef`
+/// (case-insensitive) are used, sorted by name; subdirectories are not
+/// searched. Other inputs are kept as given.
+///
+/// # Arguments
+/// * `inputs` - LEF files and directories of them
+///
+/// # Returns
+/// * `Ok(Vec<PathBuf>)` - LEF files in input order
+/// * `Err(MemeaError)` - A directory could not be read
+///
+/// # Examples
+/// ```
+/// use memea::lef::expand_lefs;
+/// use std::fs;
+///
+/// let dir = std::env::temp_dir().join("memea_expand_lefs_doc");
+/// fs::create_dir_all(&dir).unwrap();
+/// for name in ["b.lef", "a.LEF", "notes.txt"] {
+///     fs::write(dir.join(name), "").unwrap();
+/// }
+///
+/// let lefs = expand_lefs(&[dir.clone()]).unwrap();
+/// assert_eq!(lefs, [dir.join("a.LEF"), dir.join("b.lef")]);
+/// ```
+pub fn expand_lefs(inputs: &[PathBuf]) -> Result<Vec<PathBuf>, MemeaError> {
+    let mut lefs = Vec::new();
+
+    for input in inputs {
+        if !input.is_dir() {
+            lefs.push(input.clone());
+            continue;
+        }
+
+        let mut found: Vec<PathBuf> = std::fs::read_dir(input)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|p| {
+                p.is_file()
+                    && p.extension()
+                        .and_then(|e| e.to_str())
+                        .is_some_and(|e| e.eq_ignore_ascii_case("lef"))
+            })
+            .collect();
+        if found.is_empty() {
+            warnln!("No LEF files in directory {}", input.to_string_lossy());
+        }
+        found.sort();
+        lefs.extend(found);
+    }
+
+    Ok(lefs)
+}
+
+/// Finds a GDS file with the same stem beside a LEF file.
+///
+/// `.gds` and `.GDS` are accepted, as are their gzipped `.gz` forms.
+fn paired_gds(lef: &Path) -> Option<PathBuf> {
+    ["gds", "GDS", "gds.gz", "GDS.gz"]
+        .iter()
+        .map(|ext| lef.with_extension(ext))
+        .find(|p| p.is_file())
 }
 
 /// Interactively adds a cell to the database with user confirmation and type selection.
@@ -37,11 +182,18 @@ pub enum LefError {
 /// * `name` - Name of the cell to add
 /// * `dims` - Physical dimensions of the cell
 /// * `db` - Mutable reference to the database to update
+/// * `suggested` - Cell type offered as the default answer, if any
 ///
 /// # Returns
-/// * `Ok(())` - Cell was successfully processed (added or skipped)
+/// * `Ok(true)` - Cell was added
+/// * `Ok(false)` - User skipped the cell
 /// * `Err(MemeaError)` - Error during user interaction or database update
-fn add_cell(name: &str, dims: Dims, db: &mut Database) -> Result<(), MemeaError> {
+fn add_cell(
+    name: &str,
+    dims: Dims,
+    db: &mut Database,
+    suggested: Option<CellType>,
+) -> Result<bool, MemeaError> {
     println!("\nCell.......: {name}");
     dims.dump();
     println!();
@@ -52,11 +204,17 @@ fn add_cell(name: &str, dims: Dims, db: &mut Database) -> Result<(), MemeaError>
         false,
         QueryDefault::Yes,
     )? {
-        return Ok(());
+        return Ok(false);
     }
 
     loop {
-        let mut celltype: String = prompt("Cell type");
+        let mut celltype: String = match suggested {
+            Some(t) => Input::new()
+                .with_prompt("Cell type...........")
+                .default(t.to_string().to_lowercase())
+                .interact_text()?,
+            None => prompt("Cell type"),
+        };
         celltype = celltype.trim().to_lowercase();
 
         match celltype.as_str() {
@@ -76,16 +234,20 @@ fn add_cell(name: &str, dims: Dims, db: &mut Database) -> Result<(), MemeaError>
                 db.add_adc(name, dims);
                 break;
             }
+            "5" | "sense" | "senseamp" | "sa" => {
+                db.add_sense(name, dims);
+                break;
+            }
             _ => {
                 errorln!(
-                    "Invalid cell type (must be one of 1/core, 2/sw/switch, 3/log/logic, or 4/adc)"
+                    "Invalid cell type (must be one of 1/core, 2/sw/switch, 3/log/logic, 4/adc, or 5/sa/sense)"
                 );
             }
         }
     }
 
     println!("\n{}", crate::bar(None, '-'));
-    Ok(())
+    Ok(true)
 }
 
 /// Interactive LEF file processing workflow.
@@ -93,10 +255,15 @@ fn add_cell(name: &str, dims: Dims, db: &mut Database) -> Result<(), MemeaError>
 /// This function provides an interactive command-line interface for processing
 /// LEF files and creating component databases. It prompts the user for:
 /// - GDS file (optional, for enclosure computation)
-/// - LEF file (required, for cell dimensions)
-/// - Output database file (YAML or JSON format)
+/// - DEF file (optional, to skip unused macros and suggest cell types)
+/// - LEF files or directories of them (at least one, for cell dimensions)
+/// - Output database file (YAML or JSON format); if it already exists, the
+///   new cells can be added to it instead of replacing it
+///
+/// The GDS file is loaded once and shared by every LEF file in the session.
 ///
 /// # Arguments
+/// * `opts` - GDS unit override, strictness and footprint layer
 /// * `verbose` - Whether to show detailed processing information
 ///
 /// # Returns
@@ -105,20 +272,23 @@ fn add_cell(name: &str, dims: Dims, db: &mut Database) -> Result<(), MemeaError>
 ///
 /// # Examples
 /// ```no_run
-/// use memea::lef::lefin;
+/// use memea::lef::{lefin, Options};
 ///
 /// // Start interactive LEF processing
-/// lefin(true).expect("LEF processing failed");
+/// lefin(&Options::default(), true).expect("LEF processing failed");
 /// ```
-pub fn lefin(verbose: bool) -> Result<(), MemeaError> {
+pub fn lefin(opts: &Options, verbose: bool) -> Result<(), MemeaError> {
     let mut gdsfile: String;
-    let mut leffile: String;
+    let mut deffile: String;
+    let mut leffiles: Vec<PathBuf> = Vec::new();
     let mut dbout: String;
+    let mut append = false;
+    let completer = FileCompleter::default();
 
     loop {
         gdsfile = Input::new()
             .with_prompt("GDS file")
-            .completion_with(&FileCompleter)
+            .completion_with(&completer)
             .interact_text()?;
 
         let path = Path::new(&gdsfile);
@@ -126,38 +296,68 @@ pub fn lefin(verbose: bool) -> Result<(), MemeaError> {
         if gdsfile.is_empty() {
             warnln!("No GDS file provided; enclosures will not be computed.");
             break;
-        } else if check_filetype(path, &["gds"]) {
+        } else if check_filetype(path, &["gds", "gds.gz"]) {
             break;
         }
     }
 
     loop {
-        leffile = Input::new()
-            .with_prompt("LEF file")
-            .completion_with(&FileCompleter)
+        deffile = Input::new()
+            .with_prompt("DEF file (blank to skip)")
+            .completion_with(&completer)
+            .allow_empty(true)
             .interact_text()?;
 
-        let path = Path::new(&leffile);
+        if deffile.is_empty() || check_filetype(Path::new(&deffile), &["def"]) {
+            break;
+        }
+    }
 
-        if check_filetype(path, &["lef"]) {
+    loop {
+        let prompt = match leffiles.is_empty() {
+            true => "LEF file",
+            false => "Additional LEF file (blank to finish)",
+        };
+        let leffile: String = Input::new()
+            .with_prompt(prompt)
+            .completion_with(&completer)
+            .allow_empty(!leffiles.is_empty())
+            .interact_text()?;
+
+        if leffile.is_empty() {
             break;
         }
+
+        let path = Path::new(&leffile);
+
+        if path.is_dir() || check_filetype(path, &["lef"]) {
+            leffiles.push(path.to_path_buf());
+        }
     }
 
     loop {
         dbout = Input::new()
             .with_prompt("Output database file")
-            .completion_with(&FileCompleter)
+            .completion_with(&completer)
             .interact_text()?;
 
         let valid = valid_ext(&dbout);
 
         if valid && metadata(&dbout).is_ok() {
-            let allow = query(
-                format!("'{dbout}' already exists. Overwrite?").as_str(),
+            append = query(
+                format!("'{dbout}' already exists. Add the new cells to it?").as_str(),
                 true,
                 crate::QueryDefault::Yes,
             )?;
+            if append {
+                break;
+            }
+
+            let allow = query(
+                format!("Overwrite '{dbout}'?").as_str(),
+                true,
+                crate::QueryDefault::No,
+            )?;
 
             if allow {
                 break;
@@ -180,7 +380,23 @@ pub fn lefin(verbose: bool) -> Result<(), MemeaError> {
         Some(PathBuf::from(&gdsfile))
     };
 
-    read_lef(PathBuf::from(leffile), gdsin, PathBuf::from(dbout), verbose)
+    let defin = match deffile.is_empty() {
+        true => None,
+        false => Some(PathBuf::from(&deffile)),
+    };
+
+    let opts = Options {
+        append,
+        ..opts.clone()
+    };
+    read_lefs(
+        &leffiles,
+        gdsin,
+        defin,
+        PathBuf::from(dbout),
+        &opts,
+        verbose,
+    )
 }
 
 /// Parses width and height from a LEF SIZE line using regex.
@@ -194,15 +410,6 @@ pub fn lefin(verbose: bool) -> Result<(), MemeaError> {
 /// # Returns
 /// * `Ok((width, height))` - Successfully parsed dimensions in micrometers
 /// * `Err(LefError::InvalidSize)` - Line format is invalid or missing numbers
-///
-/// # Examples
-/// ```
-/// use memea::lef::parse_size;
-///
-/// let line = "    SIZE 1.5 BY 2.0 ;";
-/// let (w, h) = parse_size(line).expect("Failed to parse size");
-/// assert_eq!((w, h), (1.5, 2.0));
-/// ```
 fn parse_size(line: &str) -> Result<(Float, Float), LefError> {
     let re = Regex::new(r"([0-9]+\.?[0-9]*)").unwrap();
 
@@ -217,103 +424,646 @@ fn parse_size(line: &str) -> Result<(Float, Float), LefError> {
     }
 }
 
-/// Reads and processes a LEF file to create a component database.
+/// Loads a GDS library and indexes its structures by name.
 ///
-/// This function parses a LEF file line by line, extracting MACRO names and SIZE
-/// information to build component dimensions. If a GDS file is provided, it augments
-/// the dimensions with enclosure data computed from the layout geometry.
+/// # Arguments
+/// * `gdsin` - Path to the GDS file, optionally gzip-compressed
+/// * `gds_units` - Optional override for the GDS database unit in meters
+/// * `verbose` - Whether to show detailed processing information
+///
+/// # Returns
+/// * `Ok((map, units))` - Structure map from [`gds::hash_lib`] and database unit in meters
+/// * `Err(MemeaError)` - File I/O or GDS parsing error
+fn load_gds(
+    gdsin: &Path,
+    gds_units: Option<f64>,
+    verbose: bool,
+) -> Result<(HashMap<String, Vec<GdsElement>>, f64), MemeaError> {
+    let lib = gds::load_library(gdsin)?;
+    let mut gdsunits = lib.units.db_unit();
+
+    if let Some(units) = gds_units {
+        // Flag overrides that disagree with the file by more than 1%
+        if (units / gdsunits - 1.0).abs() > 0.01 {
+            warnln!(
+                "GDS units override ({:e} m) differs from file-reported units ({:e} m)",
+                units,
+                gdsunits
+            );
+        }
+        gdsunits = units;
+    }
+
+    vprintln!(
+        verbose,
+        "GDS library {} loaded, found {} cells",
+        gdsin.to_string_lossy(),
+        lib.structs.len()
+    );
+
+    Ok((gds::hash_lib(lib), gdsunits))
+}
+
+/// Reads several LEF files against one GDS library and saves a single database.
+///
+/// Directories among `lefs` are replaced by the LEF files in them (see
+/// [`expand_lefs`]), and the number of cells added from each file is
+/// reported. The GDS file, if any, is parsed once and reused for every LEF
+/// file, which avoids repeatedly loading large layouts; without one,
+/// `opts.pair_gds` loads a same-stem GDS file beside each LEF file instead. With `opts.append`, an existing
+/// output database is loaded first and the new cells are merged into it,
+/// asking before overwriting each cell that is already there (unless
+/// [`crate::set_overwrite`] is enabled).
 ///
 /// # Arguments
-/// * `lefin` - Path to the input LEF file
+/// * `lefs` - Paths to the input LEF files or directories of them
 /// * `gdsin` - Optional path to GDS file for enclosure computation
+/// * `defin` - Optional path to a DEF file whose COMPONENTS select and classify macros
 /// * `dbout` - Path where the output database should be saved
+/// * `opts` - GDS unit override, strictness, footprint layer and append mode
 /// * `verbose` - Whether to show detailed processing information
 ///
 /// # Returns
-/// * `Ok(())` - LEF file processed and database saved successfully
+/// * `Ok(())` - LEF files processed and database saved successfully
 /// * `Err(MemeaError)` - File I/O error, parsing error, or database save error
 ///
-/// # LEF File Format
-/// The function expects LEF files with MACRO definitions containing SIZE lines:
-/// ```text
-/// MACRO cell_name
-///   SIZE width BY height ;
-/// END cell_name
+/// # Examples
+/// ```no_run
+/// use memea::lef::{read_lefs, Options};
+/// use std::path::PathBuf;
+///
+/// let lefs = vec![PathBuf::from("core.lef"), PathBuf::from("periph.lef")];
+/// let gds = Some(PathBuf::from("cells.gds"));
+/// let opts = Options::default();
+/// read_lefs(&lefs, gds, None, PathBuf::from("db.yaml"), &opts, true)
+///     .expect("LEF processing failed");
 /// ```
-fn read_lef(
-    lefin: PathBuf,
+pub fn read_lefs(
+    lefs: &[PathBuf],
     gdsin: Option<PathBuf>,
+    defin: Option<PathBuf>,
     dbout: PathBuf,
+    opts: &Options,
     verbose: bool,
 ) -> Result<(), MemeaError> {
-    let lefin = File::open(lefin)?;
-    let rdr = BufReader::new(lefin);
-
-    // TODO: Currently assuming microns for LEF, need to scale this by LEF unit scale
-    let mut gdsunits = 1e-9;
-
-    let map = match gdsin {
+    let gds = match &gdsin {
+        Some(file) => Some(load_gds(file, opts.gds_units, verbose)?),
+        None => None,
+    };
+    let usage = match &defin {
         Some(file) => {
-            let lib = GdsLibrary::load(&file)?;
-            gdsunits = lib.units.db_unit();
-
+            let usage = parse_components(BufReader::new(File::open(file)?))?;
             vprintln!(
                 verbose,
-                "GDS library {} loaded, found {} cells",
+                "DEF {} instantiates {} distinct macro(s)",
                 file.to_string_lossy(),
-                lib.structs.len()
+                usage.len()
             );
-
-            Some(gds::hash_lib(lib))
+            Some(usage)
         }
         None => None,
     };
 
-    let mut name: String = String::new();
-    let mut dims: Option<Dims> = None;
-
+    // Load the existing database up front so a broken file fails early
+    let existing = match opts.append && metadata(&dbout).is_ok() {
+        true => {
+            let existing = build_db(&dbout, false)?;
+            vprintln!(
+                verbose,
+                "Adding to {} existing cell(s) in {}",
+                existing.iter_cells().count(),
+                dbout.to_string_lossy()
+            );
+            Some(existing)
+        }
+        false => None,
+    };
     let mut db = Database::new();
 
-    println!("Cell types: 1/core, 2/sw/switch, 3/log/logic, or 4/adc\n");
-    println!("{}", crate::bar(None, '-'));
+    if matches!(opts.classify, Classify::Interactive) {
+        println!("Cell types: 1/core, 2/sw/switch, 3/log/logic, 4/adc, or 5/sa/sense\n");
+        println!("{}", crate::bar(None, '-'));
+    }
+
+    for lef in expand_lefs(lefs)? {
+        vprintln!(verbose, "Reading LEF file {}", lef.to_string_lossy());
+        let paired = match (&gds, opts.pair_gds) {
+            (None, true) => match paired_gds(&lef) {
+                Some(file) => Some(load_gds(&file, opts.gds_units, verbose)?),
+                None => {
+                    warnln!(
+                        "No GDS file beside {}; its enclosures will not be computed",
+                        lef.to_string_lossy()
+                    );
+                    None
+                }
+            },
+            _ => None,
+        };
+        let map = gds.as_ref().or(paired.as_ref()).map(|(m, u)| (m, *u));
+        let added = read_lef(&lef, map, usage.as_ref(), &mut db, opts, verbose)?;
+        infoln!("Added {} cell(s) from {}", added, lef.to_string_lossy());
+    }
+
+    if let Some(mut existing) = existing {
+        existing.merge_with(db, |celltype, name| {
+            Ok(crate::overwrite_enabled()
+                || query(
+                    &format!("{celltype} '{name}' is already in the database. Overwrite?"),
+                    true,
+                    QueryDefault::No,
+                )?)
+        })?;
+        db = existing;
+    }
+
+    // Write database to file
+    db.save(&dbout, verbose)?;
+
+    Ok(())
+}
+
+/// Counts how many times each macro is instantiated in a DEF file.
+///
+/// Only the `COMPONENTS ... END COMPONENTS` section is read. Each component
+/// statement starts with `- <instance> <macro>` and may span several lines.
+///
+/// # Arguments
+/// * `reader` - Buffered DEF source
+///
+/// # Returns
+/// * `Ok(HashMap<String, usize>)` - Instance count per macro name
+/// * `Err(MemeaError)` - I/O error while reading
+///
+/// # Examples
+/// ```
+/// use memea::lef::parse_components;
+///
+/// let def = "DESIGN top ;\nCOMPONENTS 3 ;\n  - b0 bitcell + PLACED ( 0 0 ) N ;\n  - b1 bitcell\n    + PLACED ( 10 0 ) N ;\n  - d0 wldrv + PLACED ( -20 0 ) N ;\nEND COMPONENTS\nEND DESIGN\n";
+/// let usage = parse_components(def.as_bytes()).unwrap();
+/// assert_eq!(usage["bitcell"], 2);
+/// assert_eq!(usage["wldrv"], 1);
+/// assert_eq!(usage.len(), 2);
+/// ```
+pub fn parse_components<R: BufRead>(reader: R) -> Result<HashMap<String, usize>, MemeaError> {
+    let mut usage: HashMap<String, usize> = HashMap::new();
+    let mut in_section = false;
+    // Tokens still expected after a '-': instance name, then macro name
+    let mut pending = 0;
+    let mut last = String::new();
+
+    for line in reader.lines() {
+        let line = line?;
+        for token in line.split_whitespace() {
+            if !in_section {
+                in_section = token == "COMPONENTS" && last != "END";
+            } else if last == "END" && token == "COMPONENTS" {
+                return Ok(usage);
+            } else if pending == 2 {
+                pending = 1;
+            } else if pending == 1 {
+                *usage.entry(token.to_string()).or_default() += 1;
+                pending = 0;
+            } else if token == "-" {
+                pending = 2;
+            }
+            last = token.to_string();
+        }
+    }
+
+    Ok(usage)
+}
+
+/// Suggests a cell type for a macro from its DEF usage.
+///
+/// The most instantiated macro in an array layout is almost always the memory
+/// cell, so it is suggested as `core`; other macros get no suggestion.
+fn suggest_celltype(name: &str, usage: &HashMap<String, usize>) -> Option<CellType> {
+    let max = usage.values().max()?;
+    let count = usage.get(name)?;
+    let ties = usage.values().filter(|c| *c == max).count();
+
+    (count == max && ties == 1).then_some(CellType::Core)
+}
+
+/// LEF statements that can follow a MACRO line, used to detect a missing name.
+const MACRO_KEYWORDS: [&str; 12] = [
+    "MACRO", "CLASS", "FOREIGN", "ORIGIN", "EEQ", "LEQ", "SIZE", "SYMMETRY", "SITE", "PIN", "OBS",
+    "END",
+];
+
+/// Extracts each macro's name and size from LEF text.
+///
+/// The macro name is the token following `MACRO`, either on the same line
+/// (any trailing tokens are ignored) or, if the line holds only `MACRO`, the
+/// first token of the next non-empty line. Macros without a `SIZE` statement
+/// are omitted, as are `SIZE` statements outside any macro.
+///
+/// # Arguments
+/// * `rdr` - Reader over the LEF text
+///
+/// # Returns
+/// * `Ok(Vec<(name, width, height)>)` - Macros in file order, sizes in micrometers
+/// * `Err(MemeaError)` - I/O error, a MACRO without a name, or a malformed SIZE line
+///
+/// # Examples
+/// ```
+/// use memea::lef::parse_macros;
+///
+/// let same_line = "MACRO inv_x1 ;\n  SIZE 1.5 BY 2.0 ;\nEND inv_x1\n";
+/// let next_line = "MACRO\n\n  inv_x1\n  SIZE 1.5 BY 2.0 ;\nEND inv_x1\n";
+/// for lef in [same_line, next_line] {
+///     let macros = parse_macros(lef.as_bytes()).unwrap();
+///     assert_eq!(macros, vec![("inv_x1".to_string(), 1.5, 2.0)]);
+/// }
+///
+/// let site = "SITE core\n  SIZE 0.2 BY 1.8 ;\nEND core\n";
+/// assert!(parse_macros(site.as_bytes()).unwrap().is_empty());
+///
+/// let unnamed = "MACRO\n  SIZE 1.5 BY 2.0 ;\n";
+/// assert!(parse_macros(unnamed.as_bytes()).is_err());
+/// ```
+pub fn parse_macros<R: BufRead>(rdr: R) -> Result<Vec<(String, Float, Float)>, MemeaError> {
+    let mut macros = Vec::new();
+    let mut name: Option<String> = None;
+    let mut awaiting_name = false;
 
     for line in rdr.lines() {
         let line = line?;
-        let line = line.trim();
+        let mut tokens = line.split_whitespace();
+
+        let Some(first) = tokens.next() else {
+            continue;
+        };
+
+        if awaiting_name {
+            if MACRO_KEYWORDS.contains(&first) {
+                return Err(LefError::InvalidMacro(format!("no name before {first}")).into());
+            }
+            name = Some(first.to_string());
+            awaiting_name = false;
+            continue;
+        }
 
-        if line.contains("MACRO") {
-            // Push previous cell
-            if let Some(c) = dims.take() {
-                add_cell(&name, c, &mut db)?;
+        match first {
+            "MACRO" => match tokens.next() {
+                Some(n) if n != ";" => name = Some(n.to_string()),
+                _ => awaiting_name = true,
+            },
+            "SIZE" => {
+                // SIZE statements outside a macro (e.g. in SITE definitions) are skipped
+                if let Some(n) = &name {
+                    let (w, h) = parse_size(&line)?;
+                    macros.push((n.clone(), w, h));
+                }
             }
+            "END" if tokens.next() == name.as_deref() => name = None,
+            _ => {}
+        }
+    }
 
-            // Get new cell name
-            let n = line
-                .split_once(' ')
-                .ok_or(LefError::InvalidMacro(line.to_owned()))?
-                .1;
+    if awaiting_name {
+        return Err(LefError::InvalidMacro(String::from("no name before end of file")).into());
+    }
+
+    Ok(macros)
+}
 
-            name = n.to_string();
+/// Total RECT area of a macro's pins and obstructions, in LEF units squared.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PortAreas {
+    /// Sum of the RECT areas in all `PIN` blocks
+    pub pin: Float,
+    /// Sum of the RECT areas in the `OBS` block
+    pub obs: Float,
+}
+
+/// Returns the area of a `RECT [MASK n] x1 y1 x2 y2` statement.
+fn rect_area(tokens: &[&str]) -> Option<Float> {
+    let coords = match tokens {
+        ["MASK", _, rest @ ..] => rest,
+        rest => rest,
+    };
+    let [x1, y1, x2, y2] = coords else {
+        return None;
+    };
+    let [x1, y1, x2, y2] = [x1, y1, x2, y2].map(|n| n.parse::<Float>().ok());
+    Some(((x2? - x1?) * (y2? - y1?)).abs())
+}
+
+/// Sums the RECT geometry inside each macro's `PIN` and `OBS` blocks.
+///
+/// Every RECT in a pin counts, whatever its layer or port, and statements
+/// may share a line (e.g. `LAYER M1 ; RECT 0 0 1 1 ;`). Overlapping
+/// rectangles are counted once each, so areas are upper bounds for shapes
+/// drawn from overlapping pieces. `POLYGON` and `RECT ITERATE` statements are
+/// ignored. Areas are in the file's units squared; divide by the square of
+/// the [`parse_units`] factor for μm².
+///
+/// # Arguments
+/// * `rdr` - Reader over the LEF text
+///
+/// # Returns
+/// * `Ok(HashMap<name, PortAreas>)` - Areas of every macro with RECT geometry in a PIN or OBS block
+/// * `Err(MemeaError)` - I/O error or a RECT with malformed coordinates
+///
+/// # Examples
+/// ```
+/// use memea::lef::parse_port_areas;
+///
+/// let lef = "MACRO inv_x1\n  SIZE 4 BY 4 ;\n  PIN A\n    PORT\n      LAYER M1 ;\n\
+///            RECT 0 0 1 2 ;\n      LAYER M2 ; RECT 1 1 2 2 ;\n    END\n  END A\n\
+///            PIN Y\n    PORT\n      RECT MASK 1 3 0 4 1 ;\n    END\n  END Y\n\
+///            OBS\n    LAYER M1 ;\n    RECT 0 0 4 4 ;\n  END\nEND inv_x1\n";
+/// let areas = parse_port_areas(lef.as_bytes()).unwrap();
+/// assert_eq!(areas["inv_x1"].pin, 4.0);
+/// assert_eq!(areas["inv_x1"].obs, 16.0);
+///
+/// let bad = "MACRO inv_x1\n  OBS\n    RECT 0 0 one 1 ;\n  END\nEND inv_x1\n";
+/// assert!(parse_port_areas(bad.as_bytes()).is_err());
+/// ```
+pub fn parse_port_areas<R: BufRead>(rdr: R) -> Result<HashMap<String, PortAreas>, MemeaError> {
+    enum Block {
+        Pin(String),
+        Obs,
+    }
+
+    let mut areas: HashMap<String, PortAreas> = HashMap::new();
+    let mut name: Option<String> = None;
+    let mut block: Option<Block> = None;
+    let mut awaiting_name = false;
+
+    for line in rdr.lines() {
+        let line = line?;
+        for statement in line.split(';') {
+            let tokens: Vec<&str> = statement.split_whitespace().collect();
+            if awaiting_name {
+                if let Some(n) = tokens.first() {
+                    name = Some(n.to_string());
+                    awaiting_name = false;
+                }
+                continue;
+            }
+            match (tokens.as_slice(), &block) {
+                (["MACRO"], _) => {
+                    awaiting_name = true;
+                    block = None;
+                }
+                (["MACRO", n, ..], _) => {
+                    name = Some(n.to_string());
+                    block = None;
+                }
+                (["PIN", pin, ..], None) => block = Some(Block::Pin(pin.to_string())),
+                (["OBS", ..], None) => block = Some(Block::Obs),
+                (["END", pin], Some(Block::Pin(open))) if pin == open => block = None,
+                (["END"], Some(Block::Obs)) => block = None,
+                (["END", n], None) if Some(*n) == name.as_deref() => name = None,
+                (["RECT", "ITERATE", ..], _) => {}
+                (["RECT", coords @ ..], Some(open)) => {
+                    let Some(n) = &name else { continue };
+                    let area = rect_area(coords)
+                        .ok_or_else(|| LefError::InvalidRect(statement.trim().to_string()))?;
+                    let entry = areas.entry(n.clone()).or_default();
+                    match open {
+                        Block::Pin(_) => entry.pin += area,
+                        Block::Obs => entry.obs += area,
+                    }
+                }
+                _ => {}
+            }
         }
+    }
+
+    Ok(areas)
+}
+
+/// Reads the `DATABASE MICRONS` factor from the `UNITS` block of LEF text.
+///
+/// Only the `DATABASE MICRONS` statement inside `UNITS ... END UNITS` is
+/// read; other unit statements are ignored.
+///
+/// # Arguments
+/// * `rdr` - Reader over the LEF text
+///
+/// # Returns
+/// * `Ok(Some(factor))` - Database units per micron
+/// * `Ok(None)` - No `UNITS` block, or no `DATABASE MICRONS` statement in it
+/// * `Err(MemeaError)` - I/O error, or a factor that is missing, not a number, or not positive
+///
+/// # Examples
+/// ```
+/// use memea::lef::parse_units;
+///
+/// let lef = "VERSION 5.8 ;\nUNITS\n  TIME NANOSECONDS 1 ;\n  DATABASE MICRONS 2000 ;\nEND UNITS\n";
+/// assert_eq!(parse_units(lef.as_bytes()).unwrap(), Some(2000.0));
+///
+/// let no_units = "MACRO inv_x1 ;\n  SIZE 1.5 BY 2.0 ;\nEND inv_x1\n";
+/// assert_eq!(parse_units(no_units.as_bytes()).unwrap(), None);
+///
+/// let zero = "UNITS\n  DATABASE MICRONS 0 ;\nEND UNITS\n";
+/// assert!(parse_units(zero.as_bytes()).is_err());
+/// ```
+pub fn parse_units<R: BufRead>(rdr: R) -> Result<Option<Float>, MemeaError> {
+    let mut in_units = false;
 
-        if line.contains("SIZE") {
-            // Get size
-            let (w, h) = parse_size(line)?;
-            dims = match &map {
-                Some(m) => Some(gds::augment_dims(m, &name, w, h, gdsunits, verbose)?),
-                None => Some(Dims::from(w, h, 0.0, 0.0)),
+    for line in rdr.lines() {
+        let line = line?;
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+
+        match tokens.as_slice() {
+            ["UNITS", ..] => in_units = true,
+            ["END", "UNITS", ..] => in_units = false,
+            ["DATABASE", "MICRONS", rest @ ..] if in_units => {
+                let factor = rest
+                    .first()
+                    .and_then(|n| n.parse::<Float>().ok())
+                    .filter(|n| *n > 0.0)
+                    .ok_or_else(|| LefError::InvalidUnits(line.trim().to_string()))?;
+                return Ok(Some(factor));
             }
+            _ => {}
         }
     }
 
-    // Push last cell
-    if let Some(c) = dims {
-        add_cell(&name, c, &mut db)?;
-        println!();
+    Ok(None)
+}
+
+/// Reads the dimensions of every macro in a LEF file.
+///
+/// This function parses a LEF file with [`parse_macros`], extracting MACRO names
+/// and SIZE information to build component dimensions. SIZE values are divided
+/// by the `DATABASE MICRONS` factor of the file's `UNITS` block (see
+/// [`parse_units`]), or taken as microns if there is none. If a GDS map is
+/// provided, it augments the dimensions with enclosure data computed from the
+/// layout geometry. Pin and obstruction areas come from [`parse_port_areas`].
+///
+/// # Arguments
+/// * `lefin` - Path to the input LEF file
+/// * `gds` - Optional pre-loaded GDS structure map and its database unit in meters
+/// * `usage` - Optional DEF instance counts; macros absent from it are skipped
+/// * `opts` - GDS strictness, footprint layer and macro name filter
+/// * `verbose` - Whether to show detailed processing information
+///
+/// # Returns
+/// * `Ok(Vec<(name, dims)>)` - Macros matching the filter, in file order
+/// * `Err(MemeaError)` - File I/O error or parsing error
+///
+/// # LEF File Format
+/// The function expects LEF files with MACRO definitions containing SIZE lines:
+/// ```text
+/// MACRO cell_name
+///   SIZE width BY height ;
+/// END cell_name
+/// ```
+fn lef_cells(
+    lefin: &Path,
+    gds: Option<(&HashMap<String, Vec<GdsElement>>, f64)>,
+    usage: Option<&HashMap<String, usize>>,
+    opts: &Options,
+    verbose: bool,
+) -> Result<Vec<(String, Dims)>, MemeaError> {
+    let text = read_text(lefin)?;
+
+    let scale = match parse_units(text.as_bytes())? {
+        Some(factor) => {
+            vprintln!(
+                verbose,
+                "Scaling SIZE values in {} by 1/{} (UNITS DATABASE MICRONS)",
+                lefin.to_string_lossy(),
+                factor
+            );
+            factor
+        }
+        None => {
+            vprintln!(
+                verbose,
+                "No UNITS block in {}; assuming SIZE values are in microns",
+                lefin.to_string_lossy()
+            );
+            1.0
+        }
+    };
+    let parsed = parse_macros(text.as_bytes())?;
+    let ports = parse_port_areas(text.as_bytes())?;
+    let total = parsed.len();
+    let macros: Vec<(String, Float, Float)> = parsed
+        .into_iter()
+        .filter(|(name, _, _)| {
+            opts.macro_filter
+                .as_ref()
+                .is_none_or(|re| re.is_match(name))
+        })
+        .map(|(name, w, h)| (name, w / scale, h / scale))
+        .collect();
+    if let Some(re) = &opts.macro_filter {
+        vprintln!(
+            verbose,
+            "Skipped {} of {} macro(s) in {} not matching '{}'",
+            total - macros.len(),
+            total,
+            lefin.to_string_lossy(),
+            re
+        );
     }
+    let mut cells = Vec::new();
 
-    // Write database to file
-    db.save(&dbout, verbose)?;
+    for (name, w, h) in &macros {
+        if usage.is_some_and(|u| !u.contains_key(name)) {
+            vprintln!(verbose, "Skipping {} (not instantiated in DEF)", name);
+            continue;
+        }
 
-    Ok(())
+        let dims = match gds {
+            Some((m, units)) => {
+                let enc = gds::EncOptions {
+                    boundary_layer: opts.boundary_layer,
+                    strict: opts.strict_gds,
+                };
+                let mut dims = gds::augment_dims(m, name, *w, *h, units, enc, verbose)?;
+                // Only the footprint needs the shapes themselves
+                let footprint = opts
+                    .footprint_layer
+                    .and_then(|layer| Some((layer, gds::flatten(m, name)?)));
+                if let Some((layer, elems)) = footprint {
+                    dims.footprint = gds::footprint(&elems, layer, units);
+                    if dims.footprint.is_none() {
+                        warnln!("No polygons on layer {} for cell {}", layer, name);
+                    }
+                }
+                dims
+            }
+            None => Dims::from(*w, *h, 0.0, 0.0),
+        };
+        let area = |a: Float| (a > 0.0).then_some(a / (scale * scale));
+        let dims = match ports.get(name) {
+            Some(p) => Dims {
+                pin_area: area(p.pin),
+                obs_area: area(p.obs),
+                ..dims
+            },
+            None => dims,
+        };
+        cells.push((name.clone(), dims));
+    }
+
+    Ok(cells)
+}
+
+/// Reads a LEF file and adds its cells to a component database.
+///
+/// Cell types come from `opts.classify`: the user is asked for each macro
+/// (and its parameters) interactively, or every macro gets a fixed or mapped
+/// type with all electrical parameters set to zero (see
+/// [`Database::add_default`]).
+///
+/// # Arguments
+/// * `lefin` - Path to the input LEF file
+/// * `gds` - Optional pre-loaded GDS structure map and its database unit in meters
+/// * `usage` - Optional DEF instance counts; macros absent from it are skipped
+/// * `db` - Database to add the cells to
+/// * `opts` - GDS strictness, footprint layer and cell classification
+/// * `verbose` - Whether to show detailed processing information
+///
+/// # Returns
+/// * `Ok(usize)` - Number of cells added
+/// * `Err(MemeaError)` - File I/O error, parsing error, or user interaction error
+fn read_lef(
+    lefin: &Path,
+    gds: Option<(&HashMap<String, Vec<GdsElement>>, f64)>,
+    usage: Option<&HashMap<String, usize>>,
+    db: &mut Database,
+    opts: &Options,
+    verbose: bool,
+) -> Result<usize, MemeaError> {
+    let cells = lef_cells(lefin, gds, usage, opts, verbose)?;
+    let mut added = 0;
+
+    for (name, dims) in &cells {
+        let celltype = match &opts.classify {
+            Classify::Interactive => {
+                let suggested = usage.and_then(|u| suggest_celltype(name, u));
+                if add_cell(name, *dims, db, suggested)? {
+                    added += 1;
+                }
+                continue;
+            }
+            Classify::All(t) => *t,
+            Classify::Map(map) => match map.get(name) {
+                Some(t) => *t,
+                None => {
+                    vprintln!(verbose, "Skipping {} (not in type map)", name);
+                    continue;
+                }
+            },
+        };
+        db.add_default(celltype, name, *dims);
+        vprintln!(verbose, "Added {} as {}", name, celltype);
+        added += 1;
+    }
+
+    if !cells.is_empty() && matches!(opts.classify, Classify::Interactive) {
+        println!();
+    }
+
+    Ok(added)
 }
@@ -16,26 +16,27 @@
 //!
 //! # Quick Start
 //!
-//! ```rust
+//! ```no_run
 //! use memea::{config, db, export};
 //! use std::path::PathBuf;
 //! use std::collections::HashMap;
 //!
 //! // Load component database
 //! let db_path = PathBuf::from("components.yaml");
-//! let database = db::build_db(&db_path)?;
+//! let database = db::build_db(&db_path, false)?;
 //!
 //! // Load configurations
 //! let config_paths = vec![PathBuf::from("config.yaml")];
-//! let configs = config::read_all(&config_paths);
+//! let configs = config::read_all(&config_paths, None);
 //!
 //! // Process and export results
 //! let reports = HashMap::new(); // populated with analysis results
 //! let output_file = Some(PathBuf::from("results.csv"));
-//! export::export(&reports, &output_file)?;
+//! export::export(&reports, &output_file, &export::Options::default())?;
 //! # Ok::<(), memea::MemeaError>(())
 //! ```
 
+pub mod analysis;
 pub mod config;
 pub mod db;
 pub mod export;
@@ -46,24 +47,98 @@ pub mod tabulate;
 use crate::config::ConfigError;
 use crate::lef::LefError;
 
+use clap::ValueEnum;
 use dialoguer::Completion;
 use std::ffi::OsStr;
 use std::fmt::Write;
 use std::fs::{self, metadata};
 use std::io::{self, Write as IoWrite};
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
 use terminal_size::{terminal_size, Width};
 use thiserror::Error;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 /// Floating-point type used throughout MemEA for measurements and calculations.
 pub type Float = f32;
 
-/// Type representing memory array dimensions as (rows, columns).
-pub type Mosaic = (usize, usize);
+/// Arrangement of identical cells tiled in a grid.
+///
+/// Rows stack vertically and multiply cell height; columns run horizontally
+/// and multiply cell width. Peripherals use the named constructors:
+/// wordline drivers are [`Mosaic::row`] (one per row), bitline drivers and
+/// ADCs are [`Mosaic::col`] (one per column), and the memory core is a full
+/// [`Mosaic::array`].
+///
+/// # Examples
+/// ```
+/// use memea::Mosaic;
+///
+/// assert_eq!(Mosaic::row(64), Mosaic { rows: 64, cols: 1 });
+/// assert_eq!(Mosaic::col(32), Mosaic { rows: 1, cols: 32 });
+/// assert_eq!(Mosaic::array(64, 32), Mosaic { rows: 64, cols: 32 });
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Mosaic {
+    /// Number of cells stacked vertically.
+    pub rows: usize,
+    /// Number of cells placed side by side horizontally.
+    pub cols: usize,
+}
+
+impl Mosaic {
+    /// A single cell.
+    pub const SINGLE: Mosaic = Mosaic::array(1, 1);
+
+    /// One cell per row of an `n`-row array, stacked in a single column.
+    pub const fn row(n: usize) -> Mosaic {
+        Mosaic { rows: n, cols: 1 }
+    }
+
+    /// One cell per column of an `m`-column array, placed in a single row.
+    pub const fn col(m: usize) -> Mosaic {
+        Mosaic { rows: 1, cols: m }
+    }
+
+    /// A full grid of `n` rows by `m` columns.
+    pub const fn array(n: usize, m: usize) -> Mosaic {
+        Mosaic { rows: n, cols: m }
+    }
+}
 
 /// Current version of the MemEA library.
 pub const VER: &str = "v0.1.2";
 
+/// Returns a multi-line summary of how this binary was built.
+///
+/// Includes the crate version, git commit, rustc version, and the resolved
+/// versions of key dependencies. Values that could not be determined at
+/// build time are reported as `unknown`.
+///
+/// # Examples
+/// ```
+/// let info = memea::build_info();
+/// assert!(info.starts_with(&format!("MemEA {}", env!("CARGO_PKG_VERSION"))));
+/// assert!(info.contains("gds21"));
+/// ```
+pub fn build_info() -> String {
+    let dirty = match option_env!("VERGEN_GIT_DIRTY") {
+        Some("true") => " (dirty)",
+        _ => "",
+    };
+
+    format!(
+        "MemEA {}\ncommit: {}{}\nrustc: {}\ngds21: {}\nserde: {}",
+        env!("CARGO_PKG_VERSION"),
+        option_env!("VERGEN_GIT_SHA").unwrap_or("unknown"),
+        dirty,
+        option_env!("MEMEA_RUSTC_VERSION").unwrap_or("unknown"),
+        option_env!("MEMEA_DEP_GDS21").unwrap_or("unknown"),
+        option_env!("MEMEA_DEP_SERDE").unwrap_or("unknown"),
+    )
+}
+
 /// ASCII art logo for the MemEA application.
 pub const LOGO: &str = r#"
     __  ___               _________
@@ -81,14 +156,22 @@ macro_rules! eliteral {
     };
 }
 
-/// Internal macro for colored log message formatting.
+/// Internal macro for log message formatting.
+///
+/// Emits colored text, or one JSON object per message when the log format is
+/// [`LogFormat::Json`].
 #[macro_export]
 macro_rules! __log_internal {
     ($print:ident, $color:literal, $label:literal, $literal:literal $(, $args:expr)* $(,)?) => {
-        $print!(
-            concat!("\x1b[", $color, "m", $label, ": ", $literal, "\x1b[0m")
-            $(, $args)*
-        )
+        match $crate::log_format() {
+            $crate::LogFormat::Json => {
+                $crate::log_json($label, &format!($literal $(, $args)*))
+            }
+            $crate::LogFormat::Text => $print!(
+                concat!("\x1b[", $color, "m", $label, ": ", $literal, "\x1b[0m")
+                $(, $args)*
+            ),
+        }
     };
 }
 
@@ -105,15 +188,27 @@ macro_rules! infoln {
 }
 
 /// Macro for printing warning messages in yellow without newline.
+///
+/// Suppressed when warnings are disabled with [`set_warnings`].
 #[macro_export]
 macro_rules! warn {
-    ($($tt:tt)*) => { $crate::__log_internal!(eprint, "33", "WARNING", $($tt)*) }
+    ($($tt:tt)*) => {
+        if $crate::warnings_enabled() {
+            $crate::__log_internal!(eprint, "33", "WARNING", $($tt)*)
+        }
+    }
 }
 
 /// Macro for printing warning messages in yellow with newline.
+///
+/// Suppressed when warnings are disabled with [`set_warnings`].
 #[macro_export]
 macro_rules! warnln {
-    ($($tt:tt)*) => { $crate::__log_internal!(eprintln, "33", "WARNING", $($tt)*) }
+    ($($tt:tt)*) => {
+        if $crate::warnings_enabled() {
+            $crate::__log_internal!(eprintln, "33", "WARNING", $($tt)*)
+        }
+    }
 }
 
 /// Macro for printing error messages in red without newline.
@@ -138,6 +233,99 @@ macro_rules! vprintln {
     };
 }
 
+/// Runtime gate for warning-level output.
+static WARNINGS: AtomicBool = AtomicBool::new(true);
+
+/// Enables or disables warning-level output from [`warn!`] and [`warnln!`].
+///
+/// Informational and error output are unaffected.
+///
+/// # Arguments
+/// * `enabled` - Whether warnings should be printed
+pub fn set_warnings(enabled: bool) {
+    WARNINGS.store(enabled, Ordering::Relaxed);
+}
+
+/// Returns whether warning-level output is currently enabled.
+pub fn warnings_enabled() -> bool {
+    WARNINGS.load(Ordering::Relaxed)
+}
+
+/// Runtime switch for overwriting output files without confirmation.
+static OVERWRITE: AtomicBool = AtomicBool::new(false);
+
+/// Enables or disables overwriting existing output files without asking.
+///
+/// # Arguments
+/// * `enabled` - Whether existing output files may be replaced silently
+pub fn set_overwrite(enabled: bool) {
+    OVERWRITE.store(enabled, Ordering::Relaxed);
+}
+
+/// Returns whether existing output files are overwritten without asking.
+pub fn overwrite_enabled() -> bool {
+    OVERWRITE.load(Ordering::Relaxed)
+}
+
+/// Format of log messages written to stderr.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum LogFormat {
+    /// Colored, human-readable text.
+    #[default]
+    Text,
+    /// One JSON object per message: `{"level", "config", "message"}`.
+    Json,
+}
+
+/// Runtime switch for JSON log output.
+static LOG_JSON: AtomicBool = AtomicBool::new(false);
+
+/// Configuration currently being processed, attached to JSON log messages.
+static LOG_CONFIG: Mutex<Option<String>> = Mutex::new(None);
+
+/// Selects the format used by the logging macros.
+///
+/// # Arguments
+/// * `format` - Format of subsequent log messages
+pub fn set_log_format(format: LogFormat) {
+    LOG_JSON.store(format == LogFormat::Json, Ordering::Relaxed);
+}
+
+/// Returns the format currently used by the logging macros.
+pub fn log_format() -> LogFormat {
+    match LOG_JSON.load(Ordering::Relaxed) {
+        true => LogFormat::Json,
+        false => LogFormat::Text,
+    }
+}
+
+/// Sets the configuration name reported in the `config` field of JSON logs.
+///
+/// # Arguments
+/// * `name` - Configuration being processed, or `None` outside of one
+pub fn set_log_config(name: Option<&str>) {
+    if let Ok(mut current) = LOG_CONFIG.lock() {
+        *current = name.map(String::from);
+    }
+}
+
+/// Writes one log message to stderr as a single-line JSON object.
+///
+/// Used by the logging macros in [`LogFormat::Json`] mode.
+///
+/// # Arguments
+/// * `label` - Message level, as printed in text mode (e.g. `WARNING`)
+/// * `message` - Formatted message text
+pub fn log_json(label: &str, message: &str) {
+    let config = LOG_CONFIG.lock().ok().and_then(|c| c.clone());
+    let line = serde_json::json!({
+        "level": label.to_lowercase(),
+        "config": config,
+        "message": message.trim_end(),
+    });
+    eprintln!("{line}");
+}
+
 /// Comprehensive error type for all MemEA operations.
 ///
 /// This enum covers all possible errors that can occur during MemEA operations,
@@ -200,27 +388,102 @@ pub enum QueryDefault {
 /// File completion handler for interactive prompts.
 ///
 /// Provides tab completion functionality for file paths in interactive
-/// command-line interfaces.
-pub struct FileCompleter;
+/// command-line interfaces. The first Tab completes to the first entry, in
+/// name order, that starts with the typed name; each further Tab (with the
+/// input unchanged) moves on to the next one, wrapping around. Directories
+/// are completed with a trailing `/`, and hidden entries are only offered
+/// when the typed name starts with `.`.
+///
+/// # Examples
+/// ```
+/// use dialoguer::Completion;
+/// use memea::FileCompleter;
+/// use std::fs;
+///
+/// let dir = std::env::temp_dir().join("memea_completer_doc");
+/// fs::create_dir_all(dir.join("abdir")).unwrap();
+/// for name in ["b.lef", "ab.lef", "a.lef"] {
+///     fs::write(dir.join(name), "").unwrap();
+/// }
+///
+/// let prefix = format!("{}/a", dir.display());
+/// let names = ["a.lef", "ab.lef", "abdir/"].map(|n| format!("{}/{n}", dir.display()));
+/// assert_eq!(FileCompleter::candidates(&prefix), names);
+///
+/// // Repeated Tab presses cycle through the matches
+/// let completer = FileCompleter::default();
+/// let mut input = prefix.clone();
+/// for expected in names.iter().chain(&names[..1]) {
+///     input = completer.get(&input).unwrap();
+///     assert_eq!(&input, expected);
+/// }
+/// assert_eq!(completer.get(&format!("{}/z", dir.display())), None);
+/// ```
+#[derive(Debug, Default)]
+pub struct FileCompleter {
+    /// Matches offered for the last completed input, and the one shown
+    cycle: Mutex<Option<(Vec<String>, usize)>>,
+}
+
+impl FileCompleter {
+    /// Lists the completions of a partial path, in name order.
+    ///
+    /// The directory part of `input` (up to its last `/`) is kept as typed,
+    /// with `~` expanded only to read the directory.
+    ///
+    /// # Arguments
+    /// * `input` - Partial path typed so far
+    ///
+    /// # Returns
+    /// Each matching path, directories with a trailing `/`
+    pub fn candidates(input: &str) -> Vec<String> {
+        let (dir, prefix) = match input.rfind('/') {
+            Some(i) => input.split_at(i + 1),
+            None => ("", input),
+        };
+        let search = match dir {
+            "" => String::from("."),
+            dir => shellexpand::tilde(dir).to_string(),
+        };
+        let Ok(entries) = fs::read_dir(search) else {
+            return Vec::new();
+        };
+
+        let mut names: Vec<String> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.file_name().into_string().ok()?;
+                let visible = !name.starts_with('.') || prefix.starts_with('.');
+                (visible && name.starts_with(prefix)).then(|| match entry.path().is_dir() {
+                    true => format!("{name}/"),
+                    false => name,
+                })
+            })
+            .collect();
+        names.sort();
+        names
+            .into_iter()
+            .map(|name| format!("{dir}{name}"))
+            .collect()
+    }
+}
 
-// TODO: Remove spaghetti
 impl Completion for FileCompleter {
     fn get(&self, input: &str) -> Option<String> {
-        let expanded = shellexpand::tilde(input).to_string();
-        let path = Path::new(&expanded);
-        if let Some(parent) = path.parent() {
-            if let Ok(entries) = fs::read_dir(parent) {
-                for entry in entries.flatten() {
-                    if let Some(name) = entry.path().file_name().and_then(|n| n.to_str()) {
-                        if name.starts_with(path.file_name().and_then(|n| n.to_str()).unwrap_or(""))
-                        {
-                            return Some(name.to_string());
-                        }
-                    }
-                }
+        let mut cycle = self.cycle.lock().ok()?;
+
+        // Tab pressed again on the last completion: offer the next match
+        if let Some((names, shown)) = cycle.as_mut() {
+            if names.get(*shown).is_some_and(|name| name == input) {
+                *shown = (*shown + 1) % names.len();
+                return Some(names[*shown].clone());
             }
         }
-        None
+
+        let names = Self::candidates(input);
+        let first = names.first().cloned();
+        *cycle = first.is_some().then_some((names, 0));
+        first
     }
 }
 
@@ -257,8 +520,9 @@ pub fn query(prompt: &str, warn: bool, default: QueryDefault) -> Result<bool, Me
         QueryDefault::Neither => " (y/n) ",
     };
 
+    // Prompts are always shown, even when warnings are disabled
     match warn {
-        true => warn!("{} {}", prompt, query),
+        true => __log_internal!(eprint, "33", "WARNING", "{} {}", prompt, query),
         false => {
             print!("{prompt} {query}");
             std::io::stdout().flush()?;
@@ -287,11 +551,44 @@ pub fn query(prompt: &str, warn: bool, default: QueryDefault) -> Result<bool, Me
     }
 }
 
+/// Returns the width of the attached terminal in columns, or 80 if unknown.
+pub fn terminal_width() -> usize {
+    if let Some((Width(w), _)) = terminal_size() {
+        w as usize
+    } else {
+        80
+    }
+}
+
+/// Shortens text to at most `max` terminal columns, ending it with `…` if cut.
+fn fit_width(text: &str, max: usize) -> String {
+    if text.width() <= max {
+        return text.to_string();
+    }
+
+    let mut fitted = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let w = c.width().unwrap_or(0);
+        if used + w + 1 > max {
+            break;
+        }
+        fitted.push(c);
+        used += w;
+    }
+    if max > 0 {
+        fitted.push('…');
+    }
+    fitted
+}
+
 /// Creates a formatted horizontal bar for terminal output.
 ///
 /// This function generates a horizontal separator bar using the specified character,
 /// optionally with a centered header text. The bar width adapts to the terminal
-/// size or defaults to 80 characters.
+/// size or defaults to 80 characters. Headers are centered by their display
+/// width, so double-width (e.g. CJK or emoji) characters line up, and headers
+/// too wide for the terminal are truncated with an ellipsis.
 ///
 /// # Arguments
 /// * `header` - Optional text to center in the bar
@@ -302,26 +599,31 @@ pub fn query(prompt: &str, warn: bool, default: QueryDefault) -> Result<bool, Me
 ///
 /// # Examples
 /// ```
-/// use memea::bar;
+/// use memea::{bar, terminal_width};
+/// use unicode_width::UnicodeWidthStr;
 ///
 /// let simple_bar = bar(None, '-');
 /// let header_bar = bar(Some("Results"), '=');
 /// println!("{}", header_bar);
+///
+/// // Every line spans the terminal, whatever the header's characters
+/// let width = terminal_width();
+/// for header in ["Results", "結果の概要", "Results 📊", &"長".repeat(width)] {
+///     let bar = bar(Some(header), '#');
+///     assert!(bar.lines().all(|line| line.width() == width));
+/// }
+/// assert!(bar(Some(&"長".repeat(width)), '#').contains('…'));
 /// ```
 pub fn bar(header: Option<&str>, ch: char) -> String {
-    let width = if let Some((Width(w), _)) = terminal_size() {
-        w as usize
-    } else {
-        80
-    };
+    let width = terminal_width();
 
     let mut output = String::new();
 
     if let Some(text) = header {
         writeln!(output, "{}", ch.to_string().repeat(width)).ok();
 
-        let text_len = text.chars().count();
-        let padding = width.saturating_sub(text_len + 2);
+        let text = fit_width(text, width.saturating_sub(2));
+        let padding = width.saturating_sub(text.width() + 2);
         let left_pad = padding / 2;
 
         writeln!(
@@ -344,13 +646,15 @@ pub fn bar(header: Option<&str>, ch: char) -> String {
 /// Returns the scaling factor for a given technology node.
 ///
 /// This function provides predefined scaling factors based on industry-
-/// reported SRAM cell size trends. Returns `None` for unrecognized nodes.
+/// reported SRAM cell size trends. The values are 6T SRAM bitcell areas in
+/// μm², so ratios between them are **area** factors, not linear ones.
+/// Returns `None` for unrecognized nodes.
 ///
 /// # Arguments
 /// * `n` - Technology node size in nanometers
 ///
 /// # Returns
-/// Scaling factor for the technology node, or `None` if not recognized
+/// SRAM bitcell area for the technology node, or `None` if not recognized
 fn get_scale(n: &usize) -> Option<Float> {
     match n {
         65 => Some(0.52),
@@ -360,37 +664,141 @@ fn get_scale(n: &usize) -> Option<Float> {
         10 => Some(0.042),
         7 => Some(0.027),
         5 => Some(0.021),
-        3 => Some(0.1999),
+        3 => Some(0.0199),
         _ => None,
     }
 }
 
+/// Technology nodes, in nanometers, with built-in scaling data.
+pub const SCALE_NODES: [usize; 8] = [65, 28, 22, 16, 10, 7, 5, 3];
+
+/// SRAM bitcell area of a technology node, possibly interpolated.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NodeArea {
+    /// Bitcell area in μm²
+    pub area: Float,
+    /// Known nodes the area was interpolated between, if the node has no data
+    pub interpolated: Option<(usize, usize)>,
+}
+
+/// Looks up the SRAM bitcell area of a technology node.
+///
+/// Nodes with built-in data return it unchanged. Nodes between two known
+/// nodes are interpolated log-linearly: the logarithm of the area varies
+/// linearly with the node size between the nearest smaller and larger known
+/// nodes.
+///
+/// # Arguments
+/// * `n` - Technology node size in nanometers
+///
+/// # Returns
+/// The bitcell area, or `None` if the node lies outside the known nodes
+///
+/// # Examples
+/// ```
+/// use memea::node_area;
+///
+/// let exact = node_area(22).unwrap();
+/// assert_eq!((exact.area, exact.interpolated), (0.095, None));
+///
+/// // 14 nm lies between 10 nm (0.042) and 16 nm (0.074)
+/// let interp = node_area(14).unwrap();
+/// assert_eq!(interp.interpolated, Some((10, 16)));
+/// let expected = (0.042f32.ln() + (0.074f32 / 0.042).ln() * 4.0 / 6.0).exp();
+/// assert!((interp.area - expected).abs() < 1e-6);
+///
+/// assert!(node_area(90).is_none());
+///
+/// // Smaller nodes never have larger bitcells, interpolated or not
+/// let areas: Vec<f32> = (3..=65).map(|n| node_area(n).unwrap().area).collect();
+/// assert!(areas.windows(2).all(|w| w[0] <= w[1]));
+/// ```
+pub fn node_area(n: usize) -> Option<NodeArea> {
+    if let Some(area) = get_scale(&n) {
+        return Some(NodeArea {
+            area,
+            interpolated: None,
+        });
+    }
+
+    let lo = SCALE_NODES.iter().filter(|&&k| k < n).max()?;
+    let hi = SCALE_NODES.iter().filter(|&&k| k > n).min()?;
+    let (a_lo, a_hi) = (get_scale(lo)?, get_scale(hi)?);
+
+    let t = (n - lo) as Float / (hi - lo) as Float;
+    Some(NodeArea {
+        area: (a_lo.ln() + t * (a_hi.ln() - a_lo.ln())).exp(),
+        interpolated: Some((*lo, *hi)),
+    })
+}
+
+/// Checks that a technology node is covered by the built-in scaling data.
+///
+/// Nodes between two known nodes are accepted (see [`node_area`]).
+///
+/// # Arguments
+/// * `n` - Technology node size in nanometers
+///
+/// # Returns
+/// * `Ok(n)` - The node is supported
+/// * `Err(message)` - The node is out of range; the message names the nearest supported node
+///
+/// # Examples
+/// ```
+/// use memea::supported_node;
+///
+/// assert_eq!(supported_node(22), Ok(22));
+/// assert_eq!(supported_node(20), Ok(20));
+/// let err = supported_node(90).unwrap_err();
+/// assert!(err.contains("nearest supported node is 65"));
+/// ```
+pub fn supported_node(n: usize) -> Result<usize, String> {
+    if node_area(n).is_some() {
+        return Ok(n);
+    }
+
+    let nearest = SCALE_NODES
+        .iter()
+        .min_by_key(|node| node.abs_diff(n))
+        .copied()
+        .unwrap_or_default();
+    let nodes: Vec<String> = SCALE_NODES.iter().map(|n| n.to_string()).collect();
+
+    Err(format!(
+        "no scaling data for {n} nm; nearest supported node is {nearest} nm (supported: {})",
+        nodes.join(", ")
+    ))
+}
+
 /// Calculates scaling factor between two technology nodes.
 ///
 /// This function computes the scaling factor needed to convert measurements
-/// from one technology node to another. If either node is not recognized,
-/// it returns 1.0 and prints a warning.
+/// from one technology node to another. Nodes without built-in data are
+/// interpolated (see [`node_area`]). If either node is out of range, it
+/// returns 1.0 and prints a warning.
 ///
 /// # Arguments
 /// * `from` - Source technology node in nanometers
 /// * `to` - Target technology node in nanometers
 ///
 /// # Returns
-/// Scaling factor to convert from source to target technology
+/// Area scaling factor to convert from source to target technology; take its
+/// square root for the equivalent linear shrink
 ///
 /// # Examples
 /// ```
 /// use memea::scale;
 ///
+/// let original_area = 100.0;
 /// let scaling_factor = scale(65, 28); // Scale from 65nm to 28nm
 /// let scaled_area = original_area * scaling_factor;
 /// ```
 pub fn scale(from: usize, to: usize) -> Float {
-    let scale_from = get_scale(&from);
-    let scale_to = get_scale(&to);
+    let scale_from = node_area(from);
+    let scale_to = node_area(to);
 
     match (scale_from, scale_to) {
-        (Some(val_a), Some(val_b)) => val_b / val_a,
+        (Some(a), Some(b)) => b.area / a.area,
         _ => {
             if scale_from.is_none() {
                 warnln!(
@@ -409,6 +817,71 @@ pub fn scale(from: usize, to: usize) -> Float {
     }
 }
 
+/// Describes how a scaling factor is derived and applied.
+///
+/// With technology nodes, lists the bitcell area of each node and their
+/// ratio; with a manual factor, echoes it. Either way, states that the factor
+/// applies to area and gives the equivalent linear shrink.
+///
+/// # Arguments
+/// * `nodes` - Source and target technology nodes, if automatic scaling is used
+/// * `factor` - Area scaling factor that will be applied
+///
+/// # Returns
+/// Multi-line explanation of the scaling factor
+///
+/// # Examples
+/// ```
+/// use memea::{explain_scale, scale};
+///
+/// let text = explain_scale(Some((65, 22)), scale(65, 22));
+/// assert!(text.contains("65 nm: 0.52 μm²"));
+/// assert!(text.contains("area factor"));
+///
+/// let text = explain_scale(None, 0.25);
+/// assert!(text.contains("Manual scale factor: 0.25"));
+/// assert!(text.contains("0.5"));
+/// ```
+pub fn explain_scale(nodes: Option<(usize, usize)>, factor: Float) -> String {
+    let mut out = String::new();
+
+    match nodes {
+        Some((from, to)) => {
+            let node = |n: usize| match node_area(n) {
+                Some(NodeArea {
+                    area,
+                    interpolated: None,
+                }) => format!("{n} nm: {area} μm² bitcell"),
+                Some(NodeArea {
+                    area,
+                    interpolated: Some((lo, hi)),
+                }) => {
+                    format!("{n} nm: {area:.4} μm² bitcell (interpolated between {lo} and {hi} nm)")
+                }
+                None => format!("{n} nm: not recognized"),
+            };
+            writeln!(out, "Source node {}", node(from)).ok();
+            writeln!(out, "Target node {}", node(to)).ok();
+            match (node_area(from), node_area(to)) {
+                (Some(a), Some(b)) => writeln!(out, "Ratio: {} / {} = {factor:.4}", b.area, a.area),
+                _ => writeln!(out, "Ratio: unavailable, using {factor}"),
+            }
+            .ok();
+        }
+        None => {
+            writeln!(out, "Manual scale factor: {factor}").ok();
+        }
+    }
+    write!(
+        out,
+        "Applied as an area factor (lengths scaled by {:.4})",
+        factor.sqrt()
+    )
+    .ok();
+
+    out
+}
+
 /// Represents a numeric range with minimum and maximum values.
 ///
 /// This struct is commonly used for voltage ranges, parameter bounds,
@@ -423,6 +896,8 @@ pub struct Range {
 
 /// Parses a range from a string containing two comma or semicolon-separated values.
 ///
+/// The values are read with [`parse_tuple`], so exactly two are required.
+///
 /// # Arguments
 /// * `line` - String containing two numeric values separated by comma, semicolon, or whitespace
 ///
@@ -437,6 +912,8 @@ pub struct Range {
 /// let range = parse_range("1.2, 3.4").expect("Failed to parse range");
 /// assert_eq!(range.min, 1.2);
 /// assert_eq!(range.max, 3.4);
+///
+/// assert!(parse_range("1.2, 3.4, 5.6").is_err());
 /// ```
 pub fn parse_range(line: &str) -> Result<Range, MemeaError> {
     let (min, max) = parse_tuple(line)?;
@@ -446,6 +923,10 @@ pub fn parse_range(line: &str) -> Result<Range, MemeaError> {
 /// Checks whether a given path points to an existing file **and** that the file’s
 /// extension matches one of the supplied allowed extensions.
 ///
+/// An allowed extension may span several dots (e.g. `"gds.gz"`), in which
+/// case the whole suffix must match: `cells.gds.gz` passes but `cells.lef.gz`
+/// does not.
+///
 /// The function prints a helpful error message when the check fails, then
 /// returns `false`.  On success it simply returns `true`.
 ///
@@ -461,16 +942,26 @@ pub fn parse_range(line: &str) -> Result<Range, MemeaError> {
 ///
 /// # Example
 ///
-/// ```rust
-/// use std::path::Path;
+/// ```
+/// use memea::check_filetype;
 ///
-/// // Accept either .txt or .md files
-/// let ok = check_filetype(Path::new("notes.txt"), &["txt", "md"]);
-/// assert!(ok);
+/// let dir = std::env::temp_dir();
+/// let files = ["x.gds", "x.GDS.gz", "x.lef.gz"].map(|name| dir.join(name));
+/// for file in &files {
+///     std::fs::write(file, b"").unwrap();
+/// }
+///
+/// // Plain and gzip-compressed GDS files pass, other gzip files do not
+/// let gds = ["gds", "gds.gz"];
+/// assert!(check_filetype(&files[0], &gds));
+/// assert!(check_filetype(&files[1], &gds));
+/// assert!(!check_filetype(&files[2], &gds));
 ///
-/// // Wrong extension: prints an error and returns false
-/// let not_ok = check_filetype(Path::new("image.png"), &["txt", "md"]);
-/// assert!(!not_ok);
+/// // Missing files are rejected too
+/// assert!(!check_filetype(&dir.join("missing.gds"), &gds));
+/// # for file in &files {
+/// #     std::fs::remove_file(file).unwrap();
+/// # }
 /// ```
 pub fn check_filetype(path: &Path, allowed: &[&str]) -> bool {
     if metadata(path).is_err() {
@@ -478,39 +969,119 @@ pub fn check_filetype(path: &Path, allowed: &[&str]) -> bool {
         return false;
     }
 
-    match path.extension().and_then(OsStr::to_str) {
-        Some(actual) => {
-            // Normalise to lower‑case once for the comparison
-            let actual_lc = actual.to_ascii_lowercase();
-            if allowed.iter().any(|&e| e.eq_ignore_ascii_case(&actual_lc)) {
-                true
-            } else {
-                errorln!(
-                    "{:?} must be one of the following file types: {:?}",
-                    path,
-                    allowed
-                );
-                false
-            }
-        }
-        None => {
-            errorln!("{:?} must have a file extension", path);
-            false
-        }
+    let Some(name) = path.file_name().and_then(OsStr::to_str) else {
+        errorln!("{:?} must have a file extension", path);
+        return false;
+    };
+    // Normalise to lower‑case once for the comparison
+    let name_lc = name.to_ascii_lowercase();
+    let matches = |ext: &str| {
+        let suffix = format!(".{}", ext.to_ascii_lowercase());
+        name_lc.len() > suffix.len() && name_lc.ends_with(&suffix)
+    };
+
+    if allowed.iter().any(|e| matches(e)) {
+        true
+    } else if !name.contains('.') {
+        errorln!("{:?} must have a file extension", path);
+        false
+    } else {
+        errorln!(
+            "{:?} must be one of the following file types: {:?}",
+            path,
+            allowed
+        );
+        false
+    }
+}
+
+/// Reads a text file, dropping a leading UTF-8 byte order mark.
+///
+/// Editors on Windows often prefix UTF-8 files with a BOM (`U+FEFF`), which
+/// YAML and JSON parsers reject with a confusing error.
+///
+/// # Arguments
+/// * `path` - File to read
+///
+/// # Returns
+/// * `Ok(String)` - File contents without a leading BOM
+/// * `Err(io::Error)` - File cannot be read or is not valid UTF-8
+///
+/// # Examples
+/// ```
+/// use std::fs;
+///
+/// let path = std::env::temp_dir().join("memea_bom_doc.yaml");
+/// fs::write(&path, "\u{FEFF}n: 64\n").unwrap();
+/// assert_eq!(memea::read_text(&path).unwrap(), "n: 64\n");
+/// ```
+pub fn read_text(path: &Path) -> io::Result<String> {
+    let text = fs::read_to_string(path)?;
+    Ok(match text.strip_prefix('\u{FEFF}') {
+        Some(rest) => rest.to_string(),
+        None => text,
+    })
+}
+
+/// Parses every floating-point value in a delimited string.
+///
+/// Fields are separated by commas, semicolons or whitespace, and brackets
+/// or parentheses around a field are ignored. Fields that are not numbers
+/// (including empty ones, e.g. from `", "`) are skipped, so only a string
+/// without any number is an error.
+///
+/// # Arguments
+/// * `line` - String containing numeric values with separators
+///
+/// # Returns
+/// * `Ok(Vec<Float>)` - Values in order, at least one
+/// * `Err(MemeaError)` - No field is a number
+///
+/// # Examples
+/// ```
+/// use memea::parse_floats;
+///
+/// assert_eq!(parse_floats("1, 2, 3").unwrap(), vec![1.0, 2.0, 3.0]);
+/// assert_eq!(parse_floats("1.5;2.5").unwrap(), vec![1.5, 2.5]);
+/// assert_eq!(parse_floats("[-0.5 1e-3]").unwrap(), vec![-0.5, 1e-3]);
+///
+/// // Malformed fields are skipped; a string without numbers is an error
+/// assert_eq!(parse_floats("1, two, 3").unwrap(), vec![1.0, 3.0]);
+/// assert_eq!(parse_floats("abc 1 2 xyz").unwrap(), vec![1.0, 2.0]);
+/// assert!(parse_floats("one, two").is_err());
+/// assert!(parse_floats(" ; ").is_err());
+/// assert!(parse_floats("").is_err());
+/// ```
+pub fn parse_floats(line: &str) -> Result<Vec<Float>, MemeaError> {
+    let values: Vec<Float> = line
+        .split(|c: char| c == ',' || c == ';' || c.is_whitespace())
+        .filter_map(|field| {
+            field
+                .trim_matches(|c| matches!(c, '[' | ']' | '(' | ')'))
+                .parse()
+                .ok()
+        })
+        .collect();
+
+    match values.is_empty() {
+        true => Err(MemeaError::ParseError(line.to_string())),
+        false => Ok(values),
     }
 }
 
 /// Parses a tuple of two floating-point values from a string.
 ///
-/// This function extracts two numeric values from a string, handling various
-/// separators including commas, semicolons, and whitespace.
+/// This function extracts two numeric values from a string with
+/// [`parse_floats`], handling various separators including commas,
+/// semicolons, and whitespace.
 ///
 /// # Arguments
 /// * `line` - String containing two numeric values with separators
 ///
 /// # Returns
 /// * `Ok((a, b))` - Successfully parsed tuple of values
-/// * `Err(MemeaError)` - Parsing error if format is invalid or values cannot be parsed
+/// * `Err(MemeaError)` - Parsing error if there are not exactly two numeric
+///   fields
 ///
 /// # Examples
 /// ```
@@ -518,16 +1089,13 @@ pub fn check_filetype(path: &Path, allowed: &[&str]) -> bool {
 ///
 /// let (a, b) = parse_tuple("1.5; 2.7").expect("Failed to parse tuple");
 /// assert_eq!((a, b), (1.5, 2.7));
+///
+/// assert!(parse_tuple("1, 2, 3").is_err());
+/// assert!(parse_tuple("1.5").is_err());
 /// ```
 pub fn parse_tuple(line: &str) -> Result<(Float, Float), MemeaError> {
-    let (a, b) = line
-        .trim()
-        .trim_matches(|c: char| !c.is_ascii_digit() && c != '.' && c != ',' && c != ';' && c != '-')
-        .split_once(|c: char| c == ',' || c == ';' || c.is_whitespace())
-        .ok_or(MemeaError::ParseError(line.to_string()))?;
-
-    let a: Float = a.trim().parse::<Float>()?;
-    let b: Float = b.trim().parse::<Float>()?;
-
-    Ok((a, b))
+    match parse_floats(line)?.as_slice() {
+        [a, b] => Ok((*a, *b)),
+        _ => Err(MemeaError::ParseError(line.to_string())),
+    }
 }
@@ -4,46 +4,210 @@
 //! memory macro area estimation. It processes configuration files, component databases,
 //! and generates detailed area reports for memory peripherals.
 
-use clap::Parser;
-use std::{collections::HashMap, path::PathBuf, time::Instant};
+use clap::{Args, Parser, Subcommand};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fs,
+    path::PathBuf,
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
 
 use memea::*;
 
-/// Default path to the component database file.
+/// Default path to the component database file, used when neither `--db` nor
+/// `MEMEA_DB` is given.
 const DEFAULT_DB: &str = "./data/db.yaml";
 
 /// Command-line arguments for the MemEA application.
 ///
 /// This struct defines all command-line options and arguments using the clap derive API.
-/// It supports various modes of operation including area estimation, database building,
-/// and result export in multiple formats.
+/// Each mode of operation is a subcommand with only its relevant flags; without a
+/// subcommand, the arguments are those of `estimate`.
 #[derive(Parser, Debug)]
 #[command(
-    version,
     about,
     long_about = None,
     name = "MemEA",
-    about = "Layout-informed memory macro area estimator"
+    about = "Layout-informed memory macro area estimator",
+    disable_version_flag = true,
+    args_conflicts_with_subcommands = true
 )]
-pub struct Args {
-    /// Path(s) to configuration file(s) containing memory specifications.
-    #[arg(help = "Path(s) to configuration file(s)")]
-    input: Vec<PathBuf>,
+pub struct Cli {
+    /// Operating mode; estimation when omitted.
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Estimation arguments used when no subcommand is given.
+    #[command(flatten)]
+    estimate: EstimateArgs,
+
+    /// Suppress nonessential informational messages.
+    #[arg(short, long, global = true, help = "Suppress nonessential messages")]
+    quiet: bool,
+
+    /// Suppress warning messages, independent of `--quiet`.
+    #[arg(long, global = true, help = "Suppress warning messages")]
+    no_warn: bool,
 
-    /// Path to the component database file (YAML or JSON format).
+    /// Format of log messages on stderr.
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        default_value_t = LogFormat::Text,
+        help = "Log message format on stderr: colored text or one JSON object per message"
+    )]
+    log_format: LogFormat,
+
+    /// Print version information and exit.
+    #[arg(short = 'V', long, help = "Print version (add -v for build details)")]
+    version: bool,
+
+    /// Include build and dependency details with `--version`.
     #[arg(
         short,
         long,
+        requires = "version",
+        help = "With --version, also print git commit, rustc, and dependency versions"
+    )]
+    verbose: bool,
+}
+
+/// MemEA operating modes.
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Estimate the area of one or more memory configurations (default).
+    Estimate(Box<EstimateArgs>),
+    /// Interactively build a database file from GDS and LEF data.
+    BuildDb(BuildDbArgs),
+    /// Edit an existing database file.
+    EditDb(EditDbArgs),
+    /// Check configurations against the database without tabulating.
+    Check(CheckArgs),
+    /// List every cell in the database.
+    ListCells(ListCellsArgs),
+    /// Write a commented starter configuration.
+    InitConfig(InitConfigArgs),
+}
+
+/// Database selection shared by every subcommand that reads a database.
+#[derive(Args, Debug)]
+struct DbArgs {
+    /// Component database file(s) (YAML or JSON format), optionally scoped
+    /// to one cell type with `TYPE=FILE`.
+    #[arg(
+        short,
+        long,
+        env = "MEMEA_DB",
         default_value = DEFAULT_DB,
-        help = "Path to the database file"
+        value_name = "[TYPE=]FILE",
+        help = "Database file (overrides MEMEA_DB); repeat to merge, TYPE=FILE loads only that cell type"
+    )]
+    db: Vec<db::DbSource>,
+
+    /// Fold cell names to lowercase when matching configs against the database.
+    ///
+    /// Surrounding whitespace is always trimmed; this additionally ignores case.
+    #[arg(
+        long,
+        help = "Match cell names case-insensitively (names are always trimmed)"
+    )]
+    case_insensitive_cells: bool,
+}
+
+/// Arguments of the `list-cells` subcommand.
+#[derive(Args, Debug)]
+struct ListCellsArgs {
+    #[command(flatten)]
+    db: DbArgs,
+
+    /// Write the listing to a CSV, JSON or YAML file instead of printing it.
+    #[arg(
+        short,
+        long,
+        help = "Write the listing to a CSV/JSON/YAML file (chosen from extension)"
+    )]
+    export: Option<PathBuf>,
+
+    /// Print only the type, name and area of each cell.
+    #[arg(short, long, help = "Only print the type, name and area of each cell")]
+    area_only: bool,
+}
+
+/// Arguments of the `init-config` subcommand.
+#[derive(Args, Debug)]
+struct InitConfigArgs {
+    /// File to write the starter configuration to (stdout if omitted).
+    #[arg(help = "File to write the starter configuration to (default: print to stdout)")]
+    path: Option<PathBuf>,
+}
+
+/// Arguments of the `edit-db` subcommand.
+#[derive(Args, Debug)]
+struct EditDbArgs {
+    #[command(flatten)]
+    db: DbArgs,
+
+    /// Remove cells another cell makes redundant and write the database back.
+    #[arg(
+        long,
+        help = "Remove cells dominated by a smaller, equally capable cell and save the database"
+    )]
+    trim: bool,
+}
+
+/// Format of per-configuration failures reported by `estimate`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+enum ErrorFormat {
+    /// Colored messages, printed as each failure happens.
+    #[default]
+    Text,
+    /// A JSON array of `{config, stage, message}` objects, printed at the end.
+    Json,
+}
+
+/// A failure to process a configuration, reported with `--error-format json`.
+#[derive(Debug, serde::Serialize)]
+struct Failure {
+    /// Configuration name (or file, if it could not be read), if any
+    config: Option<String>,
+    /// Step that failed: `read`, `lookup`, `tabulate`, or `run` for errors
+    /// that stopped the whole estimation
+    stage: &'static str,
+    /// Error message
+    message: String,
+}
+
+/// Arguments of the `estimate` subcommand.
+#[derive(Args, Debug)]
+struct EstimateArgs {
+    /// Path(s) to configuration file(s) containing memory specifications.
+    #[arg(help = "Path(s) to configuration file(s), directories of them, or globs")]
+    input: Vec<PathBuf>,
+
+    /// Search directories given as input recursively.
+    #[arg(
+        long,
+        help = "Also read configuration files in subdirectories of input directories"
     )]
-    db: PathBuf,
+    recursive: bool,
 
-    /// Export results to file in CSV/JSON/YAML format (format chosen from extension).
+    /// Path to a YAML file of default values for unset configuration fields.
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "YAML file of default values used for any field a configuration leaves unset"
+    )]
+    defaults: Option<PathBuf>,
+
+    #[command(flatten)]
+    db: DbArgs,
+
+    /// Export results to file in CSV/JSON/YAML/DEF format (format chosen from extension).
     #[arg(
         short,
         long,
-        help = "Export results to file in CSV/JSON/YAML format (chosen from extension)"
+        help = "Export results to file in CSV/JSON/YAML/DEF format (chosen from extension)"
     )]
     export: Option<PathBuf>,
 
@@ -57,9 +221,82 @@ pub struct Args {
     )]
     area_only: bool,
 
-    /// Suppress nonessential informational messages.
-    #[arg(short, long, help = "Suppress nonessential messages")]
-    quiet: bool,
+    /// Write one file per configuration into this directory.
+    #[arg(
+        long,
+        value_name = "DIR",
+        conflicts_with_all = ["export", "area_only"],
+        requires = "format",
+        help = "Write each configuration to DIR/<name>.<FORMAT> instead of a single file"
+    )]
+    output_dir: Option<PathBuf>,
+
+    /// Output format, overriding the extension of `--export`.
+    #[arg(
+        long,
+        value_parser = ["tsv", "csv", "json", "yaml", "def", "txt", "md", "direct"],
+        help = "Output format, overriding the --export file extension (csv, json, yaml, def, txt, md, direct; also selects what is printed without --export), for --output-dir, or for --area-only (tsv, json, yaml; default tsv)"
+    )]
+    format: Option<String>,
+
+    /// Overwrite existing output files without asking.
+    #[arg(short, long, help = "Overwrite existing output files without asking")]
+    yes: bool,
+
+    /// Fit the breakdown table to the terminal width.
+    #[arg(
+        long,
+        help = "Fit the breakdown table to the terminal width, truncating long names"
+    )]
+    compact: bool,
+
+    /// Head each location's rows in the breakdown table.
+    #[arg(
+        long,
+        help = "Head each location's rows (Array, WL, BL, Well) in the breakdown table with the location name"
+    )]
+    group_by_location: bool,
+
+    /// Format of failures to read, look up or tabulate configurations.
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = ErrorFormat::Text,
+        help = "Report failed configurations as colored messages (text) or, at the end, as a JSON array on stderr with a nonzero exit status (json)"
+    )]
+    error_format: ErrorFormat,
+
+    /// Include each report's share of its configuration's area in exports.
+    #[arg(
+        long,
+        help = "Add each cell's share of its configuration's total area (%) to CSV, JSON and YAML exports"
+    )]
+    share: bool,
+
+    /// Process only the first N configurations in sorted name order.
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Process only the first N configurations (sorted by name)"
+    )]
+    limit: Option<usize>,
+
+    /// Process a pseudo-random subset of K configurations.
+    #[arg(
+        long,
+        value_name = "K",
+        conflicts_with = "limit",
+        help = "Process a pseudo-random subset of K configurations"
+    )]
+    sample: Option<usize>,
+
+    /// Seed for `--sample`; a time-based seed is used if omitted.
+    #[arg(
+        long,
+        requires = "sample",
+        help = "Seed for --sample, for reproducible subsets"
+    )]
+    seed: Option<u64>,
 
     /// Scale area using built-in technology node data.
     ///
@@ -67,12 +304,34 @@ pub struct Args {
     /// Uses predefined scaling factors for common semiconductor processes.
     #[arg(
         long,
+        value_parser = parse_node,
         value_names = ["FROM", "TO"],
         num_args = 2,
         help = "Use built-in transistor scaling data to scale area from source technology node (e.g. 65) to target technology node (e.g. 22)"
     )]
     autoscale: Option<Vec<usize>>,
 
+    /// Report total area scaled to several technology nodes at once.
+    ///
+    /// The first value is the source node; the rest are target nodes.
+    #[arg(
+        long,
+        value_parser = parse_node,
+        value_names = ["FROM", "TO,..."],
+        num_args = 2..,
+        value_delimiter = ',',
+        conflicts_with_all = ["autoscale", "scale"],
+        help = "Print total area scaled from node FROM to each of the comma-separated TO nodes (e.g. 65 28,22,16)"
+    )]
+    autoscale_sweep: Option<Vec<usize>>,
+
+    /// Print how the scaling factor was derived before tabulating.
+    #[arg(
+        long,
+        help = "Print the node factors, ratio and how the scaling factor is applied before tabulating"
+    )]
+    explain_scale: bool,
+
     /// Manually specify a scaling factor to apply to all area calculations.
     #[arg(
         long,
@@ -80,46 +339,664 @@ pub struct Args {
     )]
     scale: Option<Float>,
 
-    /// Launch interactive database builder from GDS and LEF files.
+    /// Fail configurations that omit any peripheral section instead of skipping it.
     #[arg(
-        short,
         long,
-        help = "Interactively build a database file from GDS and LEF data"
+        help = "Treat missing wl/bl/well/ADC sections as errors instead of skipping them"
     )]
-    build_db: bool,
+    require_complete: bool,
+
+    /// Abort before tabulating if any configuration's core cell is missing.
+    #[arg(
+        long,
+        help = "Abort if any configuration references a core cell missing from the database (default: skip those configurations)"
+    )]
+    strict: bool,
+
+    /// Use cells pinned in the configuration even if they violate the constraints.
+    #[arg(
+        long,
+        help = "Use pinned cells (e.g. `wl_switch`) even if they do not meet the requirements"
+    )]
+    force: bool,
+
+    /// Pick a core cell automatically when the configured one is missing.
+    #[arg(
+        long,
+        help = "If a configuration's core cell is not in the database, use the smallest core cell whose wordline and bitline drive can be met"
+    )]
+    auto_core: bool,
+
+    /// Print a histogram of total area across all configurations to stderr.
+    ///
+    /// Takes an optional number of bins (default 10).
+    #[arg(
+        long,
+        value_name = "BINS",
+        num_args = 0..=1,
+        default_missing_value = "10",
+        help = "Print a histogram of total area across configurations to stderr (default 10 bins)"
+    )]
+    histogram: Option<usize>,
+
+    /// Write the histogram to a file instead of stderr.
+    #[arg(
+        long,
+        value_name = "FILE",
+        requires = "histogram",
+        help = "Write the --histogram to FILE instead of stderr"
+    )]
+    histogram_output: Option<PathBuf>,
+
+    /// How enclosure is accounted for when computing array area.
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = db::AreaModel::PerimeterOnly,
+        help = "Enclosure model: once around each array (perimeter-only) or around every instance (per-cell)"
+    )]
+    area_model: db::AreaModel,
+
+    /// Write a database containing only the cells that were selected.
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Write a pruned database (YAML or JSON) holding only the cells selected by any configuration"
+    )]
+    export_used: Option<PathBuf>,
+
+    /// Print peripheral area grouped by drive strength headroom.
+    #[arg(
+        long,
+        help = "After tabulating, print switch and logic area grouped by selected/required drive ratio"
+    )]
+    headroom_report: bool,
+
+    /// Print total area per physical cell across all configurations.
+    #[arg(
+        long,
+        help = "After tabulating, print total area per cell name across all configurations, largest first"
+    )]
+    by_cell: bool,
+
+    /// Print database cells that no configuration selected.
+    #[arg(
+        long,
+        help = "After tabulating, print the database cells no configuration used"
+    )]
+    report_unused: bool,
+
+    /// Multiply every enclosure by this factor before computing area.
+    #[arg(
+        long,
+        default_value_t = 1.0,
+        help = "Multiply every enclosure by this factor (applied before --enc-add)"
+    )]
+    enc_scale: Float,
+
+    /// Add this distance (μm) to every enclosure side before computing area.
+    #[arg(
+        long,
+        default_value_t = 0.0,
+        allow_negative_numbers = true,
+        help = "Add this distance in μm to every enclosure side (applied after --enc-scale)"
+    )]
+    enc_add: Float,
+
+    /// Require peripheral cells to match the core `wl_pitch`/`bl_pitch`.
+    #[arg(
+        long,
+        help = "Reject peripheral cells that do not match the core cell's wl_pitch/bl_pitch"
+    )]
+    pitch_match: bool,
+
+    /// Slack in volts allowed beyond a switch's voltage range.
+    #[arg(
+        long,
+        value_name = "VOLTS",
+        default_value_t = tabulate::V_TOL,
+        help = "Accept switches whose voltage range misses the requested voltage by at most this many volts"
+    )]
+    v_tol: Float,
+
+    /// Drive strength multiplier for well switches.
+    #[arg(
+        long,
+        value_name = "FACTOR",
+        value_parser = parse_scale,
+        help = "Fraction of the array's drive strength well switches must provide (overrides the database; default 0.25)"
+    )]
+    well_scale: Option<Float>,
+
+    /// Drive strength multiplier for decoder logic.
+    #[arg(
+        long,
+        value_name = "FACTOR",
+        value_parser = parse_scale,
+        help = "Fraction of a peripheral's drive strength its logic must provide (overrides the database; default 0.5)"
+    )]
+    logic_scale: Option<Float>,
 
     /// Launch graphical user interface (not yet implemented).
     #[arg(long, help = "Launch GUI")]
     gui: bool,
 }
 
+/// Arguments of the `build-db` subcommand.
+#[derive(Args, Debug)]
+struct BuildDbArgs {
+    /// Override the database unit reported by the GDS file (in meters).
+    #[arg(
+        long,
+        value_name = "METERS",
+        help = "Override the GDS database unit in meters (e.g. 1e-9) when building a database"
+    )]
+    gds_units: Option<f64>,
+
+    /// Fail the database build when a LEF macro is missing from the GDS file.
+    #[arg(
+        long,
+        help = "When building a database, fail if a LEF macro is missing from the GDS file instead of using zero enclosure"
+    )]
+    strict_gds: bool,
+
+    /// GDS layer whose polygons give each cell's true (non-rectangular) area.
+    #[arg(
+        long,
+        value_name = "LAYER[/DATATYPE]",
+        help = "When building a database, record each cell's true area as the union of its polygons on this GDS layer (e.g. 108 or 108/0)"
+    )]
+    footprint_layer: Option<gds::LayerSpec>,
+
+    /// GDS layer (and optionally datatype) holding the place-and-route boundary.
+    #[arg(
+        long,
+        value_name = "LAYER[/DATATYPE]",
+        help = "When building a database, compute enclosures only from boundaries on this GDS layer (e.g. 108 or 108/0), falling back to all layers for cells without one"
+    )]
+    boundary_layer: Option<gds::LayerSpec>,
+
+    /// LEF file(s) or directories of them to convert without the interactive
+    /// file prompts.
+    #[arg(
+        long,
+        value_name = "FILE|DIR",
+        requires = "db_out",
+        help = "Convert these LEF files, or every *.lef file in these directories (repeatable), without prompting for file names; requires --db-out"
+    )]
+    lef: Vec<PathBuf>,
+
+    /// GDS file used for enclosures when converting with `--lef`.
+    #[arg(
+        long,
+        value_name = "FILE",
+        requires = "lef",
+        help = "GDS file for enclosure computation (with --lef)"
+    )]
+    gds: Option<PathBuf>,
+
+    /// Only import LEF macros whose name matches this regular expression.
+    #[arg(
+        long,
+        value_name = "REGEX",
+        help = "Only import LEF macros whose name matches this regular expression (e.g. '^SRAM_'); other macros are skipped without prompting"
+    )]
+    macro_filter: Option<regex::Regex>,
+
+    /// Read each LEF file's enclosures from a same-stem GDS file beside it.
+    #[arg(
+        long,
+        requires = "lef",
+        conflicts_with = "gds",
+        help = "Compute each LEF file's enclosures from the GDS file of the same name beside it, e.g. cells.gds for cells.lef (with --lef)"
+    )]
+    pair_gds: bool,
+
+    /// DEF file selecting and classifying macros when converting with `--lef`.
+    #[arg(
+        long,
+        value_name = "FILE",
+        requires = "lef",
+        help = "DEF file whose COMPONENTS select the macros to convert (with --lef)"
+    )]
+    def: Option<PathBuf>,
+
+    /// Output database file when converting with `--lef`.
+    #[arg(
+        long,
+        value_name = "FILE",
+        requires = "lef",
+        help = "Output database file, YAML or JSON (with --lef)"
+    )]
+    db_out: Option<PathBuf>,
+
+    /// Cell type given to every macro, skipping the per-cell prompts.
+    #[arg(
+        long,
+        value_name = "TYPE",
+        requires = "lef",
+        conflicts_with = "type_map",
+        help = "Add every macro as this cell type (core, logic, switch, adc or sense) with zero-valued parameters instead of prompting (with --lef)"
+    )]
+    cell_type: Option<db::CellType>,
+
+    /// YAML map from macro name to cell type, skipping the per-cell prompts.
+    #[arg(
+        long,
+        value_name = "FILE",
+        requires = "lef",
+        help = "YAML file mapping macro names to cell types; listed macros are added with zero-valued parameters, others are skipped (with --lef)"
+    )]
+    type_map: Option<PathBuf>,
+
+    /// Add the new cells to an existing output database instead of replacing it.
+    #[arg(
+        long,
+        requires = "db_out",
+        help = "Add the new cells to an existing --db-out file, asking before overwriting each cell already in it"
+    )]
+    append: bool,
+
+    /// Overwrite an existing output database without asking.
+    #[arg(
+        short,
+        long,
+        help = "Overwrite an existing --db-out file (or, with --append, its colliding cells) without asking"
+    )]
+    yes: bool,
+}
+
+/// Arguments of the `check` subcommand.
+#[derive(Args, Debug)]
+struct CheckArgs {
+    /// Path(s) to configuration file(s) containing memory specifications.
+    #[arg(help = "Path(s) to configuration file(s), directories of them, or globs")]
+    input: Vec<PathBuf>,
+
+    /// Search directories given as input recursively.
+    #[arg(
+        long,
+        help = "Also read configuration files in subdirectories of input directories"
+    )]
+    recursive: bool,
+
+    /// Path to a YAML file of default values for unset configuration fields.
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "YAML file of default values used for any field a configuration leaves unset"
+    )]
+    defaults: Option<PathBuf>,
+
+    #[command(flatten)]
+    db: DbArgs,
+
+    /// Report cells with identical parameters under different names.
+    #[arg(
+        long,
+        help = "Also print groups of database cells with identical parameters"
+    )]
+    duplicates: bool,
+
+    /// Check database cells for invalid or suspicious parameters.
+    #[arg(
+        long,
+        help = "Also check database cells for invalid dimensions, voltage ranges, drive strengths and sampling rates"
+    )]
+    validate: bool,
+}
+
+/// Builds a database from LEF (and optionally GDS and DEF) files.
+///
+/// Without `--lef`, the interactive builder prompts for every file and cell.
+/// With `--lef`, the files come from the command line, and `--cell-type` or
+/// `--type-map` additionally removes the per-cell prompts.
+///
+/// # Arguments
+/// * `args` - Parsed `build-db` arguments
+/// * `verbose` - Whether to show detailed processing information
+///
+/// # Returns
+/// * `Ok(())` - Database written (or the user declined to overwrite)
+/// * `Err(MemeaError)` - Invalid arguments, file I/O or parsing error
+fn build_db(args: BuildDbArgs, verbose: bool) -> Result<(), MemeaError> {
+    let classify = match (args.cell_type, &args.type_map) {
+        (Some(t), _) => lef::Classify::All(t),
+        (None, Some(path)) => lef::Classify::Map(lef::read_type_map(path)?),
+        (None, None) => lef::Classify::Interactive,
+    };
+    let opts = lef::Options {
+        gds_units: args.gds_units,
+        strict_gds: args.strict_gds,
+        footprint_layer: args.footprint_layer,
+        boundary_layer: args.boundary_layer,
+        classify,
+        append: args.append,
+        pair_gds: args.pair_gds,
+        macro_filter: args.macro_filter,
+    };
+
+    let Some(dbout) = args.db_out else {
+        println!("{LOGO}");
+        println!("{}\n", bar(Some("Interactive Database Builder"), '#'));
+        return lef::lefin(&opts, verbose);
+    };
+
+    if !db::valid_ext(&dbout.to_string_lossy()) {
+        let ext = dbout.extension().unwrap_or_default().to_string_lossy();
+        return Err(db::DBError::FileType(ext.to_string()).into());
+    }
+    set_overwrite(args.yes);
+    if !args.append && !export::confirm_overwrite(&dbout)? {
+        return Ok(());
+    }
+
+    lef::read_lefs(&args.lef, args.gds, args.def, dbout, &opts, verbose)
+}
+
+/// Parses a technology node argument, rejecting nodes without scaling data.
+fn parse_node(s: &str) -> Result<usize, String> {
+    let n: usize = s.parse().map_err(|e| format!("{e}"))?;
+    supported_node(n)
+}
+
+/// Parses a drive strength multiplier, rejecting values that are not
+/// positive and finite.
+fn parse_scale(s: &str) -> Result<Float, String> {
+    let v: Float = s.parse().map_err(|e| format!("{e}"))?;
+    match v > 0.0 && v.is_finite() {
+        true => Ok(v),
+        false => Err(format!("{v} is not a positive, finite number")),
+    }
+}
+
 /// Main entry point for the MemEA application.
 ///
-/// This function orchestrates the complete workflow:
-/// 1. Parse command-line arguments
-/// 2. Handle special modes (database building, GUI)
-/// 3. Load component database and configurations
-/// 4. Process area estimations with optional scaling
-/// 5. Export results in the requested format
+/// This function parses command-line arguments, applies global settings, and
+/// dispatches to the selected subcommand (estimation by default).
 ///
 /// # Returns
 /// * `Ok(())` - Application completed successfully
 /// * `Err(MemeaError)` - Error during processing (file I/O, parsing, etc.)
 fn main() -> Result<(), MemeaError> {
-    let args = Args::parse();
-    let verbose = !args.quiet && !args.area_only;
+    let cli = Cli::parse();
+    set_warnings(!cli.no_warn);
+    set_log_format(cli.log_format);
 
-    // Handle special operating modes first
-    if args.build_db {
-        println!("{LOGO}");
-        println!("{}\n", bar(Some("Interactive Database Builder"), '#'));
-        lef::lefin(verbose)?;
+    if cli.version {
+        match cli.verbose {
+            true => println!("{}", build_info()),
+            false => println!("MemEA {}", env!("CARGO_PKG_VERSION")),
+        }
         return Ok(());
-    } else if args.input.is_empty() {
+    }
+
+    match cli.command {
+        None => estimate(cli.estimate, cli.quiet),
+        Some(Command::Estimate(args)) => estimate(*args, cli.quiet),
+        Some(Command::BuildDb(args)) => build_db(args, !cli.quiet),
+        Some(Command::EditDb(args)) => edit_db(args, !cli.quiet),
+        Some(Command::Check(args)) => check(args, !cli.quiet),
+        Some(Command::ListCells(args)) => list_cells(args),
+        Some(Command::InitConfig(args)) => init_config(args),
+    }
+}
+
+/// Checks configurations against the database without tabulating them.
+///
+/// Every configuration is validated and its core cell looked up, and with
+/// `--validate` every database cell is checked too (see [`db::lint`]); all
+/// problems are reported before returning.
+///
+/// # Arguments
+/// * `args` - Parsed `check` arguments
+/// * `verbose` - Whether to print a summary when everything passes
+///
+/// # Returns
+/// * `Ok(())` - Every configuration is usable with the database
+/// * `Err(MemeaError)` - A configuration is invalid, references a missing core
+///   cell, or the database has errors
+fn check(args: CheckArgs, verbose: bool) -> Result<(), MemeaError> {
+    let db = db::build_dbs(&args.db.db, args.db.case_insensitive_cells)?;
+
+    if args.duplicates {
+        for (celltype, names) in &db::find_duplicates(&db) {
+            println!("Duplicate {}: {}", celltype, names.join(", "));
+        }
+    }
+
+    let mut lint_errors = 0;
+    if args.validate {
+        for issue in db::lint(&db) {
+            match issue.severity {
+                db::Severity::Error => {
+                    errorln!("{}", issue);
+                    lint_errors += 1;
+                }
+                db::Severity::Warning => warnln!("{}", issue),
+            }
+        }
+    }
+
+    let defaults = match &args.defaults {
+        Some(path) => Some(config::read_defaults(path)?),
+        None => None,
+    };
+    let inputs = config::expand_inputs(&args.input, args.recursive);
+    let mut configs = config::read_all(&inputs, defaults.as_ref());
+    for c in configs.values_mut() {
+        c.normalize_names(args.db.case_insensitive_cells);
+    }
+
+    let mut invalid = 0;
+    for (name, c) in &configs {
+        if let Err(e) = c.validate() {
+            errorln!("Config '{}': {}", name, e);
+            invalid += 1;
+        }
+    }
+
+    let missing = config::missing_cells(&configs, &db);
+    for (cell, names) in &missing {
+        errorln!(
+            "Core cell '{}' not found in database (used by {})",
+            cell,
+            names.join(", ")
+        );
+    }
+
+    if !missing.is_empty() {
+        let cells: Vec<&str> = missing.iter().map(|(c, _)| c.as_str()).collect();
+        return Err(db::DBError::MissingCell(cells.join(", ")).into());
+    } else if invalid > 0 {
+        return Err(config::ConfigError::InvalidOption(format!(
+            "{invalid} invalid configuration(s)"
+        ))
+        .into());
+    } else if lint_errors > 0 {
+        return Err(
+            db::DBError::Invalid(format!("{lint_errors} error(s) in database cells")).into(),
+        );
+    }
+
+    vprintln!(
+        verbose,
+        "{} configuration(s) OK against {} database cell(s)",
+        configs.len(),
+        db.iter_cells().count()
+    );
+
+    Ok(())
+}
+
+/// Edits a database file in place.
+///
+/// With `--trim`, dominated cells are removed. Otherwise the database is
+/// edited interactively (see [`db::Database::edit_interactive`]). Either way
+/// the database is written back to its original path, unless the user quits
+/// the interactive editor without saving.
+///
+/// # Arguments
+/// * `args` - Parsed `edit-db` arguments
+/// * `verbose` - Whether to report each removed cell
+///
+/// # Returns
+/// * `Ok(())` - Database edited (or nothing to do)
+/// * `Err(MemeaError)` - Database could not be loaded or written
+fn edit_db(args: EditDbArgs, verbose: bool) -> Result<(), MemeaError> {
+    // Writing back needs a single file holding every cell type
+    let path = match args.db.db.as_slice() {
+        [db::DbSource { scope: None, path }] => path,
+        _ => {
+            return Err(db::DBError::Source(
+                "edit-db needs exactly one database file without a type scope".to_string(),
+            )
+            .into())
+        }
+    };
+
+    // The database is written back, so names must keep their stored case
+    if args.db.case_insensitive_cells {
+        return Err(db::DBError::Source(
+            "edit-db writes the database back and cannot fold cell names; drop --case-insensitive-cells"
+                .to_string(),
+        )
+        .into());
+    }
+
+    let mut db = db::build_db(path, false)?;
+    if !args.trim {
+        return match db.edit_interactive()? {
+            true => db::write_db(&db, path, verbose),
+            false => {
+                infoln!("Discarded changes to {:?}", path);
+                Ok(())
+            }
+        };
+    }
+
+    let dominated = db::dominated_cells(&db);
+    if dominated.is_empty() {
+        vprintln!(verbose, "No dominated cells in {:?}", path);
+        return Ok(());
+    }
+
+    for (celltype, name) in &dominated {
+        vprintln!(verbose, "Trimming dominated {} '{}'", celltype, name);
+        db.remove(*celltype, name);
+    }
+
+    db::write_db(&db, path, verbose)
+}
+
+/// Prints every database cell with its dimensions and key parameters.
+///
+/// # Arguments
+/// * `args` - Parsed `list-cells` arguments
+///
+/// # Returns
+/// * `Ok(())` - Listing printed or written
+/// * `Err(MemeaError)` - Database could not be loaded or the listing written
+fn list_cells(args: ListCellsArgs) -> Result<(), MemeaError> {
+    let db = db::build_dbs(&args.db.db, args.db.case_insensitive_cells)?;
+    let cells = db.summaries();
+
+    match &args.export {
+        Some(path) => export::export_cells(&cells, path)?,
+        None => print!("{}", export::fmt_cells(&cells, args.area_only)),
+    }
+
+    Ok(())
+}
+
+/// Writes the commented starter configuration.
+///
+/// # Arguments
+/// * `args` - Parsed `init-config` arguments
+///
+/// # Returns
+/// * `Ok(())` - Template written (or the user declined to overwrite)
+/// * `Err(MemeaError)` - File could not be written
+fn init_config(args: InitConfigArgs) -> Result<(), MemeaError> {
+    match &args.path {
+        Some(path) => {
+            if export::confirm_overwrite(path)? {
+                fs::write(path, config::TEMPLATE)?;
+                infoln!("Wrote starter configuration to {:?}", path);
+            }
+        }
+        None => print!("{}", config::TEMPLATE),
+    }
+
+    Ok(())
+}
+
+/// Estimates area for every configuration and writes the requested output.
+///
+/// This function orchestrates the estimation workflow:
+/// 1. Load component database and configurations
+/// 2. Process area estimations with optional scaling
+/// 3. Export results in the requested format
+///
+/// # Arguments
+/// * `args` - Parsed estimation arguments
+/// * `quiet` - Whether nonessential messages are suppressed
+///
+/// # Returns
+/// * `Ok(())` - Estimation completed successfully
+/// * `Err(MemeaError)` - Error during processing (file I/O, parsing, etc.)
+///
+/// With `--error-format json`, failures are printed as a JSON array on stderr
+/// instead, and the process exits with status 1 if there were any.
+fn estimate(args: EstimateArgs, quiet: bool) -> Result<(), MemeaError> {
+    let format = args.error_format;
+    let mut failures = Vec::new();
+    let result = run_estimate(args, quiet, &mut failures);
+    if format == ErrorFormat::Text {
+        return result;
+    }
+
+    if let Err(e) = result {
+        failures.push(Failure {
+            config: None,
+            stage: "run",
+            message: e.to_string(),
+        });
+    }
+    eprintln!("{}", serde_json::to_string_pretty(&failures)?);
+    if !failures.is_empty() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Runs the estimation workflow of [`estimate`].
+///
+/// # Arguments
+/// * `args` - Parsed estimation arguments
+/// * `quiet` - Whether nonessential messages are suppressed
+/// * `failures` - Collects per-configuration failures with `--error-format json`
+///
+/// # Returns
+/// * `Ok(())` - Estimation completed, possibly without some configurations
+/// * `Err(MemeaError)` - Error that stopped the estimation
+fn run_estimate(
+    args: EstimateArgs,
+    quiet: bool,
+    failures: &mut Vec<Failure>,
+) -> Result<(), MemeaError> {
+    let json = args.error_format == ErrorFormat::Json;
+    let verbose = !quiet && !args.area_only;
+    set_overwrite(args.yes);
+
+    if args.input.is_empty() {
         errorln!("No configuration files provided, aborting...");
         return Ok(());
     }
 
+    // Reject format combinations before any work is done
+    export::check_format(args.format.as_deref(), &args.export, args.area_only)?;
+
     if args.gui {
         // TODO: GUI implementation
         errorln!("GUI not yet implemented, falling back to CLI");
@@ -127,17 +1004,105 @@ fn main() -> Result<(), MemeaError> {
 
     // Load component database
     let start = Instant::now();
-    let db = db::build_db(&args.db)?;
+    let db = db::build_dbs(&args.db.db, args.db.case_insensitive_cells)?;
     vprintln!(verbose, "Built database in {:?}", start.elapsed());
 
     // Load configuration files
     let start = Instant::now();
-    let configs = config::read_all(&args.input);
+    let defaults = match &args.defaults {
+        Some(path) => Some(config::read_defaults(path)?),
+        None => None,
+    };
+    let inputs = config::expand_inputs(&args.input, args.recursive);
+    let (mut configs, failed) = config::try_read_all(&inputs, defaults.as_ref());
+    for (path, e) in failed {
+        match json {
+            true => failures.push(Failure {
+                config: Some(path.to_string_lossy().into_owned()),
+                stage: "read",
+                message: e.to_string(),
+            }),
+            false => errorln!("Failed to read config {:?} ({})", path, e),
+        }
+    }
+
+    // Restrict to a subset of configurations for quick checks
+    if let Some(n) = args.limit {
+        configs = config::limit(configs, n);
+    } else if let Some(k) = args.sample {
+        let seed = args.seed.unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_nanos() as u64)
+                .unwrap_or_default()
+        });
+        vprintln!(
+            verbose,
+            "Sampling {} configuration(s) with seed {}",
+            k,
+            seed
+        );
+        configs = config::sample(configs, k, seed);
+    }
+
+    // Normalize cell references and pins the same way as database names
+    for c in configs.values_mut() {
+        c.normalize_names(args.db.case_insensitive_cells);
+    }
+
+    // Check every referenced core cell up front instead of failing mid-run
+    // (with --auto-core, missing cells are replaced during tabulation instead)
+    let missing = match args.auto_core {
+        true => Vec::new(),
+        false => config::missing_cells(&configs, &db),
+    };
+    for (cell, names) in &missing {
+        match json {
+            true => failures.extend(names.iter().map(|name| Failure {
+                config: Some(name.clone()),
+                stage: "lookup",
+                message: format!("Core cell '{cell}' not found in database"),
+            })),
+            false => errorln!(
+                "Core cell '{}' not found in database (used by {})",
+                cell,
+                names.join(", ")
+            ),
+        }
+    }
+    if !missing.is_empty() {
+        if args.strict {
+            let cells: Vec<&str> = missing.iter().map(|(c, _)| c.as_str()).collect();
+            return Err(db::DBError::MissingCell(cells.join(", ")).into());
+        }
+        let skipped: usize = missing.iter().map(|(_, n)| n.len()).sum();
+        warnln!(
+            "Skipping {} configuration(s) with missing core cells",
+            skipped
+        );
+        configs.retain(|_, c| db.core.contains_key(&c.cell));
+    }
+
+    // Interpolated node data is an approximation; say so
+    let nodes = match (&args.autoscale, &args.autoscale_sweep) {
+        (Some(n), _) | (None, Some(n)) if args.scale.is_none() => n.as_slice(),
+        _ => &[],
+    };
+    for n in nodes.iter().collect::<std::collections::BTreeSet<_>>() {
+        if let Some((lo, hi)) = node_area(*n).and_then(|a| a.interpolated) {
+            infoln!(
+                "No scaling data for {} nm; interpolating between {} and {} nm",
+                n,
+                lo,
+                hi
+            );
+        }
+    }
 
     // Determine scaling factor from command-line arguments
     let scale: Float = match args.scale {
         Some(val) => val,
-        None => match args.autoscale {
+        None => match &args.autoscale {
             Some(vals) => {
                 let (from, to) = (vals[0], vals[1]);
                 scale(from, to)
@@ -146,30 +1111,136 @@ fn main() -> Result<(), MemeaError> {
         },
     };
 
+    if args.explain_scale {
+        match (&args.autoscale_sweep, &args.autoscale) {
+            (Some(nodes), _) => {
+                for to in &nodes[1..] {
+                    eprintln!(
+                        "{}",
+                        explain_scale(Some((nodes[0], *to)), memea::scale(nodes[0], *to))
+                    );
+                }
+            }
+            (None, Some(vals)) if args.scale.is_none() => {
+                eprintln!("{}", explain_scale(Some((vals[0], vals[1])), scale));
+            }
+            _ => eprintln!("{}", explain_scale(None, scale)),
+        }
+    }
+
     vprintln!(
         verbose,
         "Read {} configuration file(s) in {:?}",
         configs.len(),
         start.elapsed()
     );
+    // Stream CSV exports row by row instead of buffering every report
+    let mut sink = match (
+        &args.export,
+        args.area_only || args.autoscale_sweep.is_some(),
+    ) {
+        (Some(path), false)
+            if args
+                .format
+                .clone()
+                .unwrap_or_else(|| export::format_of(&args.export))
+                == "csv" =>
+        {
+            match export::CsvSink::create(path)? {
+                Some(s) => Some(s.with_share(args.share)),
+                None => return Ok(()),
+            }
+        }
+        _ => None,
+    };
+
     // Generate area estimation reports for each configuration
     let start = Instant::now();
     let mut reports: HashMap<String, tabulate::Reports> = HashMap::new();
+    let mut totals: Vec<(String, Float)> = Vec::new();
+    let mut used: HashSet<(db::CellType, String)> = HashSet::new();
+    let mut headroom = analysis::Headroom::default();
+    let mut by_cell: Vec<tabulate::Report> = Vec::new();
+    let mut warnings: HashMap<String, Vec<String>> = HashMap::new();
+    let cache = tabulate::SelectionCache::new();
+    let opts = tabulate::Options {
+        scale,
+        require_complete: args.require_complete,
+        force: args.force,
+        area_model: args.area_model,
+        enc_scale: args.enc_scale,
+        enc_add: args.enc_add,
+        pitch_match: args.pitch_match,
+        v_tol: args.v_tol,
+        auto_core: args.auto_core,
+        well_scale: args
+            .well_scale
+            .or(db.scales.well)
+            .unwrap_or(tabulate::WELL_SCALE),
+        logic_scale: args
+            .logic_scale
+            .or(db.scales.logic)
+            .unwrap_or(tabulate::LOGIC_SCALE),
+    };
+    vprintln!(
+        verbose,
+        "Drive strength scales: well {}, logic {}",
+        opts.well_scale,
+        opts.logic_scale
+    );
 
-    for (name, c) in &configs {
-        match tabulate::tabulate(name, c, &db, scale) {
-            Ok(r) => {
-                reports.insert(name.clone(), r);
+    // Tabulate in name order so streamed output and totals are reproducible
+    let ordered: BTreeMap<&String, &config::Config> = configs.iter().collect();
+    for (name, c) in ordered {
+        set_log_config(Some(name));
+        match tabulate::tabulate_warned(name, c, &db, &opts, Some(&cache)) {
+            Ok(tabulate::Tabulation {
+                reports: r,
+                warnings: w,
+            }) => {
+                if !w.is_empty() {
+                    warnings.insert(name.clone(), w);
+                }
+                totals.push((name.clone(), export::area(&r)));
+                if args.headroom_report {
+                    headroom.add(&r);
+                }
+                if args.by_cell {
+                    by_cell.extend(r.iter().cloned());
+                }
+                if args.report_unused || args.export_used.is_some() {
+                    used.extend(r.iter().map(|rep| (rep.celltype, rep.name.clone())));
+                }
+                match sink.as_mut() {
+                    Some(s) => s.write(name, &r)?,
+                    None => {
+                        reports.insert(name.clone(), r);
+                    }
+                }
             }
-            Err(e) => errorln!("Failed to tabulate config '{}': {}", name, e),
+            Err(e) => match json {
+                true => failures.push(Failure {
+                    config: Some(name.clone()),
+                    stage: "tabulate",
+                    message: e.to_string(),
+                }),
+                false => errorln!("Failed to tabulate config '{}': {}", name, e),
+            },
         }
     }
+    set_log_config(None);
+    vprintln!(
+        verbose,
+        "Cell selection: {} cached, {} scanned",
+        cache.hits(),
+        cache.misses()
+    );
 
     // Warn if some configurations failed to process
-    if configs.len() != reports.len() {
+    if configs.len() != totals.len() {
         warnln!(
             "Number of reports ({}) does not match number of configs ({})",
-            reports.len(),
+            totals.len(),
             configs.len()
         );
     }
@@ -177,22 +1248,95 @@ fn main() -> Result<(), MemeaError> {
     vprintln!(
         verbose,
         "Built {}/{} solution(s) in {:?}",
-        reports.len(),
+        totals.len(),
         configs.len(),
         start.elapsed()
     );
 
-    // Output results in the requested format
-    match args.area_only {
-        true => {
-            // Simple tab-separated output: configuration name and total area
-            for (name, r) in &reports {
-                println!("{}\t{}", name, export::area(r));
+    // Summarize the spread of total area across configurations
+    if let Some(bins) = args.histogram {
+        let areas: Vec<Float> = totals.iter().map(|(_, a)| *a).collect();
+        let histogram = analysis::histogram(&areas, bins);
+        match &args.histogram_output {
+            Some(path) => {
+                if export::confirm_overwrite(path)? {
+                    fs::write(path, format!("{histogram}\n"))?;
+                    infoln!("Wrote histogram to {:#?}", path);
+                }
             }
+            None => eprintln!("{histogram}"),
+        }
+    }
+
+    // Print a config-by-node matrix of scaled totals instead of the usual output
+    if let Some(nodes) = &args.autoscale_sweep {
+        print!("{}", analysis::sweep_table(&totals, nodes[0], &nodes[1..]));
+        return Ok(());
+    }
+
+    // Output results in the requested format
+    match (args.area_only, sink) {
+        (true, _) => {
+            // Configuration name and total area only
+            let format = args.format.as_deref().unwrap_or("tsv");
+            print!("{}", export::fmt_totals(&totals, format)?);
         }
-        false => {
+        (false, Some(s)) => {
+            // Streamed CSV rows were already written
+            s.finish()?;
+        }
+        (false, None) => {
             // Full export with detailed breakdown
-            export::export(&reports, &args.export)?;
+            let densities: HashMap<String, Float> = configs
+                .iter()
+                .filter_map(|(name, c)| c.defect_density.map(|d| (name.clone(), d)))
+                .collect();
+            let capacities: HashMap<String, usize> = configs
+                .iter()
+                .map(|(name, c)| (name.clone(), c.capacity()))
+                .collect();
+            let export_opts = export::Options {
+                compact: args.compact,
+                defect_density: Some(&densities),
+                share: args.share,
+                format: args.format.as_deref(),
+                group_by_location: args.group_by_location,
+                warnings: Some(&warnings),
+                capacity: Some(&capacities),
+            };
+            match (&args.output_dir, &args.format) {
+                (Some(dir), Some(format)) => {
+                    export::export_dir(&reports, dir, format, &export_opts)?
+                }
+                _ => export::export(&reports, &args.export, &export_opts)?,
+            }
+        }
+    }
+
+    // Ship the selected cells as their own database
+    if let Some(path) = &args.export_used {
+        if export::confirm_overwrite(path)? {
+            db.subset(&used).save(path, verbose)?;
+        }
+    }
+
+    // Show how much peripheral area is spent on surplus drive
+    if args.headroom_report {
+        print!("{}", headroom.fmt());
+    }
+
+    // Rank physical cells by the area they contribute
+    if args.by_cell {
+        print!(
+            "{}",
+            analysis::fmt_by_cell(&analysis::area_by_cell_name(&by_cell))
+        );
+    }
+
+    // List database cells that never appeared in any report
+    if args.report_unused {
+        for (celltype, names) in analysis::unused_cells(&db, &used) {
+            println!("Unused {} cells: {}", celltype, names.join(", "));
         }
     }
 
@@ -1,118 +1,892 @@
 use serde::Serialize;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
 
-use crate::config::Config;
+use crate::config::{BankSplit, Config, ConfigError};
 use crate::db::*;
-use crate::{warnln, Float, MemeaError, Mosaic};
+use crate::{infoln, warnln, Float, MemeaError, Mosaic};
 
-// Drive strength multipliers
-const WELL_SCALE: Float = 0.25;
-const LOGIC_SCALE: Float = 0.5;
+/// Default fraction of the array's drive strength well switches must provide.
+pub const WELL_SCALE: Float = 0.25;
+/// Default fraction of a peripheral's drive strength its logic must provide.
+pub const LOGIC_SCALE: Float = 0.5;
 
-const SINGLE: Mosaic = (1, 1);
-
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Report {
     pub name: String,
     pub count: usize,
     pub celltype: CellType,
     pub loc: String,
     pub area: Float,
+    pub copies: usize,
+    /// Drive strength the peripheral had to provide (switches and logic only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dx_required: Option<Float>,
+    /// Drive strength of the selected cell (switches and logic only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dx_selected: Option<Float>,
+    /// Leakage power of all instances in watts, if the cell has leakage data
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub leakage: Option<Float>,
+    /// Width and height in μm of the block of instances in one array
+    /// (copies are not included)
+    pub extent: [Float; 2],
 }
 
 pub type Reports = Vec<Report>;
 
+/// Reports of one configuration together with the warnings raised while
+/// tabulating it.
+#[derive(Debug, Clone, Default)]
+pub struct Tabulation {
+    /// One report per selected component
+    pub reports: Reports,
+    /// Skipped peripheral sections and inconsistent peripheral counts
+    pub warnings: Vec<String>,
+}
+
+/// Default slack in volts allowed beyond a switch's voltage range.
+pub const V_TOL: Float = 1e-3;
+
+/// Run-wide settings that control how configurations are tabulated.
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    /// Area scaling factor applied to every report (see [`crate::scale`]).
+    ///
+    /// Cell dimensions are shrunk linearly by its square root, so the
+    /// reported area scales by exactly this factor.
+    pub scale: Float,
+    /// Treat missing peripheral sections as errors instead of skipping them.
+    pub require_complete: bool,
+    /// Use pinned cells even when they do not meet the required constraints.
+    pub force: bool,
+    /// How enclosure is accounted for in reported areas.
+    pub area_model: AreaModel,
+    /// Factor applied to every enclosure before computing area.
+    pub enc_scale: Float,
+    /// Distance in μm added to every enclosure side after `enc_scale`.
+    pub enc_add: Float,
+    /// Reject peripheral cells that do not match the core pitch.
+    pub pitch_match: bool,
+    /// Slack in volts allowed beyond a switch's voltage range.
+    pub v_tol: Float,
+    /// Pick a core cell automatically when the configured one is missing.
+    pub auto_core: bool,
+    /// Drive strength multiplier for well switches (see [`WELL_SCALE`]).
+    pub well_scale: Float,
+    /// Drive strength multiplier for decoder logic (see [`LOGIC_SCALE`]).
+    pub logic_scale: Float,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            scale: 1.0,
+            require_complete: false,
+            force: false,
+            area_model: AreaModel::default(),
+            enc_scale: 1.0,
+            enc_add: 0.0,
+            pitch_match: false,
+            v_tol: V_TOL,
+            auto_core: false,
+            well_scale: WELL_SCALE,
+            logic_scale: LOGIC_SCALE,
+        }
+    }
+}
+
+/// Computes the reported area of a mosaic of cells.
+///
+/// Enclosure overrides are applied first (scale, then add), then every length
+/// is shrunk by the linear node factor (the square root of the area factor),
+/// and finally the area is computed under the selected area model.
+///
+/// Under [`AreaModel::PerimeterOnly`] the vertical enclosure is `enc[1]`;
+/// earlier versions padded the height with the cell height instead, so areas
+/// of cells whose vertical enclosure differs from their height have changed.
+fn report_area(dims: &Dims, mos: Mosaic, opts: &Options) -> Float {
+    dims.adjust_enc(opts.enc_scale, opts.enc_add)
+        .scaled(opts.scale.sqrt())
+        .area_with(mos, opts.area_model)
+}
+
+/// Computes the width and height of a mosaic of cells, like [`report_area`].
+fn report_extent(dims: &Dims, mos: Mosaic, opts: &Options) -> [Float; 2] {
+    dims.adjust_enc(opts.enc_scale, opts.enc_add)
+        .scaled(opts.scale.sqrt())
+        .extent(mos, opts.area_model)
+}
+
+/// Number of select bits needed to decode between `count` voltages.
+///
+/// A single voltage (or none) needs no decoding.
+///
+/// # Examples
+/// ```
+/// use memea::tabulate::decode_bits;
+///
+/// assert_eq!(decode_bits(0), 0);
+/// assert_eq!(decode_bits(1), 0);
+/// assert_eq!(decode_bits(3), 2);
+/// assert_eq!(decode_bits(4), 2);
+/// ```
+pub fn decode_bits(count: usize) -> usize {
+    match count {
+        0 | 1 => 0,
+        n => (n as Float).log2().ceil() as usize,
+    }
+}
+
+/// Leakage of `count` instances of a cell, if the cell has leakage data.
+fn total_leakage(per_cell: Option<Float>, count: usize) -> Option<Float> {
+    per_cell.map(|l| l * count as Float)
+}
+
+/// Warns about each selected cell without leakage data, once per cache.
+///
+/// Without a cache, each cell is reported once per call. Nothing is reported
+/// for databases that carry no leakage data at all.
+fn warn_missing_leakage(db: &Database, reports: &Reports, cache: Option<&SelectionCache>) {
+    let fresh;
+    let cache = match cache {
+        Some(c) => c,
+        None => {
+            fresh = SelectionCache::new();
+            &fresh
+        }
+    };
+    if !cache.has_leakage(db) {
+        return;
+    }
+
+    let mut warned = cache.no_leakage.borrow_mut();
+    for r in reports.iter().filter(|r| r.leakage.is_none()) {
+        if warned.insert(r.name.clone()) {
+            warnln!("No leakage data for {}; counting it as zero", r.name);
+        }
+    }
+}
+
+/// Checks that peripheral counts are consistent with the array dimensions.
+///
+/// Each wordline-side report may have at most one instance per row (`n`),
+/// and each bitline- or well-side report at most one per column (`m`); more
+/// than that usually means a mistyped `adcs` or array size. Banks repeat the
+/// peripherals along their split, which raises the limit on that side by the
+/// number of banks. Counts must not yet include `copies`.
+///
+/// # Arguments
+/// * `config` - Configuration the reports were tabulated from
+/// * `reports` - Per-array reports, before replication
+///
+/// # Returns
+/// One message per inconsistent report (empty if all counts fit)
+///
+/// # Examples
+/// ```
+/// use memea::config::Config;
+/// use memea::db::CellType;
+/// use memea::tabulate::{count_mismatches, Report};
+///
+/// let config: Config = serde_yaml::from_str("n: 128\nm: 64\ncell: c").unwrap();
+/// let adc = Report {
+///     name: "sar".to_string(),
+///     count: 128,
+///     celltype: CellType::ADC,
+///     loc: "BL".to_string(),
+///     area: 1.0,
+///     copies: 1,
+///     dx_required: None,
+///     dx_selected: None,
+///     leakage: None,
+///     extent: [0.0, 0.0],
+/// };
+///
+/// assert_eq!(count_mismatches(&config, &[adc.clone()]).len(), 1);
+/// assert!(count_mismatches(&config, &[Report { count: 64, ..adc }]).is_empty());
+/// ```
+pub fn count_mismatches(config: &Config, reports: &[Report]) -> Vec<String> {
+    let banks = config.banks.unwrap_or(1);
+    let (rows, cols) = match config.bank_split.unwrap_or_default() {
+        BankSplit::Rows => (config.n, config.m * banks),
+        BankSplit::Cols => (config.n * banks, config.m),
+    };
+
+    reports
+        .iter()
+        .filter_map(|r| {
+            let (limit, lines) = match r.loc.as_str() {
+                "WL" => (rows, "rows"),
+                "BL" | "Well" => (cols, "columns"),
+                _ => return None,
+            };
+            (r.count > limit).then(|| {
+                format!(
+                    "{} {} {} has {} instances but the array only has {} {}",
+                    r.loc, r.celltype, r.name, r.count, limit, lines
+                )
+            })
+        })
+        .collect()
+}
+
+fn logic_fits(logic: &Logic, dx: Float, bits: usize) -> bool {
+    logic.dx >= dx && logic.bits >= bits
+}
+
+fn adc_fits(adc: &ADC, fs: Float, bits: usize, op: OperatingPoint) -> bool {
+    adc.fs_at(op) >= fs && adc.enob >= bits as Float
+}
+
+fn sense_fits(sense: &SenseAmp, dx: Float, fs: Float) -> bool {
+    sense.dx >= dx && sense.fs >= fs
+}
+
+fn switch_fits(switch: &Switch, voltage: Float, dx: Float, tol: Float) -> bool {
+    switch.dx >= dx && switch.covers(voltage, tol)
+}
+
+/// Looks up a cell pinned by name in the config, bypassing auto-selection.
+///
+/// Errors if the cell does not exist, or if it does not satisfy the
+/// requirement and `force` is unset. Unless `force` is set, a cell that
+/// does not match the pitch is warned about, or rejected if the pitch is
+/// required, as for automatically selected cells.
+fn pin<T: Clone>(
+    cells: &HashMap<String, T>,
+    name: &str,
+    fits: bool,
+    requirement: String,
+    pitch: Pitch,
+    dims: impl Fn(&T) -> &Dims,
+    force: bool,
+) -> Result<(String, T), DBError> {
+    let cell = cells
+        .get(name)
+        .ok_or(DBError::MissingCell(name.to_string()))?;
+
+    if !force && !pitch.fits(dims(cell)) {
+        let p = pitch.value.unwrap_or_default();
+        if pitch.require {
+            return Err(DBError::UnsuitableCell(format!(
+                "{name} does not match the {p} μm pitch"
+            )));
+        }
+        warnln!("Pinned cell {} does not match the {} μm pitch", name, p);
+    }
+
+    if !fits {
+        if !force {
+            return Err(DBError::UnsuitableCell(format!(
+                "{name} does not satisfy {requirement}"
+            )));
+        }
+        warnln!(
+            "Pinned cell {} does not satisfy {}; using it anyway",
+            name,
+            requirement
+        );
+    }
+
+    Ok((name.to_string(), cell.clone()))
+}
+
+/// Largest value of a cell parameter, if any cells are given.
+fn max_of<T>(cells: impl Iterator<Item = T>, f: impl Fn(T) -> Float) -> Option<Float> {
+    cells.map(f).reduce(Float::max)
+}
+
+/// Explains which logic requirement the database cannot meet.
+fn logic_shortfall(db: &Database, dx: Float, bits: usize) -> String {
+    let Some(max_bits) = max_of(db.logic.values(), |l| l.bits as Float) else {
+        return String::from("no logic cells in database");
+    };
+    if max_bits < bits as Float {
+        return format!("need {bits} bits but largest logic cell provides {max_bits}");
+    }
+
+    let candidates = db.logic.values().filter(|l| l.bits >= bits);
+    let max_dx = max_of(candidates, |l| l.dx).unwrap_or_default();
+    format!("need dx {dx} but strongest logic cell with {bits} bits provides {max_dx}")
+}
+
+/// Explains which ADC requirement the database cannot meet.
+fn adc_shortfall(db: &Database, fs: Float, bits: usize, op: OperatingPoint) -> String {
+    let Some(max_enob) = max_of(db.adc.values(), |a| a.enob) else {
+        return String::from("no ADCs in database");
+    };
+    if max_enob < bits as Float {
+        return format!("need {bits} bits but highest ADC enob is {max_enob}");
+    }
+
+    let candidates = db.adc.values().filter(|a| a.enob >= bits as Float);
+    let max_fs = max_of(candidates, |a| a.fs_at(op)).unwrap_or_default();
+    format!("need fs {fs} but fastest ADC with {bits} bits provides {max_fs}")
+}
+
+/// Explains which switch requirement the database cannot meet.
+fn switch_shortfall(db: &Database, voltage: Float, dx: Float, tol: Float) -> String {
+    let Some(hi) = max_of(db.switch.values(), |s| s.voltage[1]) else {
+        return String::from("no switches in database");
+    };
+    let lo = db
+        .switch
+        .values()
+        .map(|s| s.voltage[0])
+        .reduce(Float::min)
+        .unwrap_or_default();
+
+    let mut candidates = db
+        .switch
+        .values()
+        .filter(|s| s.covers(voltage, tol))
+        .peekable();
+    if candidates.peek().is_none() {
+        return format!("need {voltage} V but switches only cover {lo} to {hi} V");
+    }
+
+    let max_dx = max_of(candidates, |s| s.dx).unwrap_or_default();
+    format!("need dx {dx} but strongest switch for {voltage} V provides {max_dx}")
+}
+
+/// Explains which sense amplifier requirement the database cannot meet.
+fn sense_shortfall(db: &Database, dx: Float, fs: Float) -> String {
+    let Some(max_fs) = max_of(db.sense.values(), |s| s.fs) else {
+        return String::from("no sense amplifiers in database");
+    };
+    if max_fs < fs {
+        return format!("need fs {fs} but fastest sense amplifier provides {max_fs}");
+    }
+
+    let candidates = db.sense.values().filter(|s| s.fs >= fs);
+    let max_dx = max_of(candidates, |s| s.dx).unwrap_or_default();
+    format!("need dx {dx} but strongest sense amplifier for fs {fs} provides {max_dx}")
+}
+
+/// Pitch that peripheral cells along one edge of the array should match.
+#[derive(Debug, Clone, Copy)]
+struct Pitch {
+    /// Pitch in μm, if the core cell specifies one
+    value: Option<Float>,
+    /// Cell dimension compared against the pitch (0 for width, 1 for height)
+    axis: usize,
+    /// Reject cells that do not match instead of warning about them
+    require: bool,
+}
+
+impl Pitch {
+    /// No pitch constraint.
+    const NONE: Pitch = Pitch {
+        value: None,
+        axis: 0,
+        require: false,
+    };
+
+    fn fits(&self, dims: &Dims) -> bool {
+        self.value.is_none_or(|p| dims.matches_pitch(self.axis, p))
+    }
+
+    fn key(&self) -> PitchKey {
+        (self.value.map(Float::to_bits), self.axis, self.require)
+    }
+}
+
+type PitchKey = (Option<u32>, usize, bool);
+type SwitchKey = (u32, u32, u32, Mosaic, PitchKey);
+type LogicKey = (u32, usize, Mosaic, PitchKey);
+type AdcKey = (u32, usize, Option<u32>, Option<u32>, Mosaic);
+type SenseKey = (u32, u32, Mosaic, PitchKey);
+
+/// Memo of automatic cell selections, reusable across configurations.
+///
+/// A selection depends only on the database and on the requirements of the
+/// query (voltage, drive, bits, sampling rate, operating point, mosaic and
+/// pitch), so repeated queries are answered without rescanning the database.
+/// It also remembers which selected cells were reported as lacking leakage
+/// data, so each is reported once however many configurations use it. A
+/// cache must only be reused with the database it was filled from.
+///
+/// # Examples
+/// ```
+/// use memea::config::Config;
+/// use memea::db::{Core, Database, Dims, Logic, Switch};
+/// use memea::tabulate::{tabulate, Options, SelectionCache};
+///
+/// let mut db = Database::new();
+/// let dims = Dims::from(1.0, 1.0, 0.0, 0.0);
+/// let core = Core { dx_wl: 1.0, dx_bl: 1.0, wl_pitch: None, bl_pitch: None, dims, leakage: None, note: None, source: None };
+/// let logic = Logic { dx: 8.0, bits: 2, fs: 1e9, dims, leakage: None, note: None, source: None };
+/// let switch = Switch { dx: 8.0, voltage: [0.0, 2.0], dims, leakage: None, note: None, source: None };
+/// db.core.insert("c".into(), core);
+/// db.logic.insert("l".into(), logic);
+/// db.switch.insert("s".into(), switch);
+///
+/// let config: Config = serde_yaml::from_str("{n: 4, m: 4, cell: c, wl: [1.0, 1.0]}").unwrap();
+/// let cache = SelectionCache::new();
+/// let first = tabulate("a", &config, &db, &Options::default(), Some(&cache)).unwrap();
+/// assert_eq!((cache.hits(), cache.misses()), (1, 2));
+///
+/// let second = tabulate("b", &config, &db, &Options::default(), Some(&cache)).unwrap();
+/// assert_eq!((cache.hits(), cache.misses()), (4, 2));
+/// assert_eq!(first[1].name, second[1].name);
+/// ```
+#[derive(Debug, Default)]
+pub struct SelectionCache {
+    switch: RefCell<HashMap<SwitchKey, Option<String>>>,
+    logic: RefCell<HashMap<LogicKey, Option<String>>>,
+    adc: RefCell<HashMap<AdcKey, Option<String>>>,
+    sense: RefCell<HashMap<SenseKey, Option<String>>>,
+    hits: Cell<usize>,
+    misses: Cell<usize>,
+    /// Whether any database cell has leakage data, once scanned
+    leakage: Cell<Option<bool>>,
+    /// Cells already reported as lacking leakage data
+    no_leakage: RefCell<HashSet<String>>,
+}
+
+impl SelectionCache {
+    /// Creates an empty cache.
+    pub fn new() -> SelectionCache {
+        SelectionCache::default()
+    }
+
+    /// Number of queries answered from the cache.
+    pub fn hits(&self) -> usize {
+        self.hits.get()
+    }
+
+    /// Number of queries that required a database scan.
+    pub fn misses(&self) -> usize {
+        self.misses.get()
+    }
+
+    /// Whether any cell in `db` has leakage data, scanning it only once.
+    fn has_leakage(&self, db: &Database) -> bool {
+        if let Some(any) = self.leakage.get() {
+            return any;
+        }
+        let any = db.core.values().any(|c| c.leakage.is_some())
+            || db.logic.values().any(|c| c.leakage.is_some())
+            || db.switch.values().any(|c| c.leakage.is_some())
+            || db.adc.values().any(|c| c.leakage.is_some())
+            || db.sense.values().any(|c| c.leakage.is_some());
+        self.leakage.set(Some(any));
+        any
+    }
+
+    /// Returns the memoized selection for `key`, scanning on a miss.
+    fn memo<K: Eq + Hash>(
+        &self,
+        map: &RefCell<HashMap<K, Option<String>>>,
+        key: K,
+        scan: impl FnOnce() -> Option<String>,
+    ) -> Option<String> {
+        if let Some(found) = map.borrow().get(&key) {
+            self.hits.set(self.hits.get() + 1);
+            return found.clone();
+        }
+
+        self.misses.set(self.misses.get() + 1);
+        let found = scan();
+        map.borrow_mut().insert(key, found.clone());
+        found
+    }
+}
+
+/// Database, optional selection cache, and matching slack used during selection.
+#[derive(Clone, Copy)]
+struct Lookup<'a> {
+    db: &'a Database,
+    cache: Option<&'a SelectionCache>,
+    v_tol: Float,
+}
+
+/// Whether candidate `a` should replace the current selection `b`.
+///
+/// Pitch-matched cells are preferred over unmatched ones; otherwise the
+/// smaller cell wins. Candidates are scanned in name order (see [`by_name`])
+/// and an equally good candidate does not replace the selection, so ties go
+/// to the first name.
+fn prefer(a: &Dims, b: &Dims, mos: Mosaic, pitch: Pitch) -> bool {
+    match (pitch.fits(a), pitch.fits(b)) {
+        (true, false) => true,
+        (false, true) => false,
+        _ => a.area(mos) < b.area(mos),
+    }
+}
+
+/// Lists database cells sorted by name, so selection does not depend on
+/// `HashMap` iteration order.
+fn by_name<V>(cells: &HashMap<String, V>) -> Vec<(&String, &V)> {
+    let mut cells: Vec<(&String, &V)> = cells.iter().collect();
+    cells.sort_unstable_by_key(|(name, _)| *name);
+    cells
+}
+
+/// Locations in the order reports are listed; unknown locations go last.
+const LOCATION_ORDER: [&str; 4] = ["Array", "WL", "BL", "Well"];
+
+/// Sorts reports by location, cell type and name.
+///
+/// Locations follow [`LOCATION_ORDER`] rather than the alphabet, so the
+/// array comes first. The sort is stable: multiple reports for the same cell
+/// (e.g. one switch per voltage) keep their relative order.
+fn sort_reports(reports: &mut Reports) {
+    let rank = |loc: &str| {
+        LOCATION_ORDER
+            .iter()
+            .position(|l| *l == loc)
+            .unwrap_or(LOCATION_ORDER.len())
+    };
+    reports.sort_by(|a, b| {
+        (rank(&a.loc), &a.loc, a.celltype, &a.name).cmp(&(
+            rank(&b.loc),
+            &b.loc,
+            b.celltype,
+            &b.name,
+        ))
+    });
+}
+
+/// Rejects or warns about a selected cell that does not match the pitch.
+fn check_pitch(what: String, name: &str, dims: &Dims, pitch: Pitch) -> Result<(), DBError> {
+    if pitch.fits(dims) {
+        return Ok(());
+    }
+
+    let p = pitch.value.unwrap_or_default();
+    if pitch.require {
+        return Err(DBError::NoSuitableCells(format!(
+            "{what} matching the {p} μm pitch (best was {name})"
+        )));
+    }
+    warnln!(
+        "Best available cell {} does not match the {} μm pitch",
+        name,
+        p
+    );
+
+    Ok(())
+}
+
 fn locate_logic(
-    db: &Database,
+    look: Lookup,
     dx: Float,
     bits: usize,
     mos: Mosaic,
+    pitch: Pitch,
 ) -> Result<(String, Logic), DBError> {
-    let mut target = String::new();
-    let mut sel: Option<&Logic> = None;
-
-    for (name, logic) in &db.logic {
-        let condition = || -> bool { logic.dx >= dx && logic.bits >= bits };
-
-        if sel.is_none() && condition() {
-            (target, sel) = (name.clone(), Some(logic));
-        } else if sel.is_some() && condition() {
-            let dims = sel.unwrap().dims;
-            if logic.dims.area(mos) <= dims.area(mos) {
-                (target, sel) = (name.clone(), Some(logic))
+    let db = look.db;
+    let scan = || {
+        let mut target = String::new();
+        let mut sel: Option<&Logic> = None;
+
+        for (name, logic) in by_name(&db.logic) {
+            let condition = || -> bool { logic_fits(logic, dx, bits) };
+
+            if sel.is_none() && condition() {
+                (target, sel) = (name.clone(), Some(logic));
+            } else if sel.is_some() && condition() {
+                let dims = sel.unwrap().dims;
+                if prefer(&logic.dims, &dims, mos, pitch) {
+                    (target, sel) = (name.clone(), Some(logic))
+                }
             }
         }
-    }
 
-    match sel {
-        Some(x) => Ok((target, *x)),
+        sel.map(|_| target)
+    };
+    let key = (dx.to_bits(), bits, mos, pitch.key());
+    let found = match look.cache {
+        Some(cache) => cache.memo(&cache.logic, key, scan),
+        None => scan(),
+    };
+
+    match found.and_then(|name| db.logic.get(&name).map(|x| (name, x))) {
+        Some((target, x)) => {
+            let what = format!("Logic with dx {dx} and {bits} bits");
+            check_pitch(what, &target, &x.dims, pitch)?;
+            Ok((target, x.clone()))
+        }
         None => Err(DBError::NoSuitableCells(format!(
-            "Logic with dx {dx} and {bits} bits"
+            "Logic with dx {dx} and {bits} bits ({})",
+            logic_shortfall(db, dx, bits)
         ))),
     }
 }
 
 fn locate_adc(
-    db: &Database,
+    look: Lookup,
     fs: Float,
     bits: usize,
+    op: OperatingPoint,
     mos: Mosaic,
 ) -> Result<(String, ADC), DBError> {
-    let mut target = String::new();
-    let mut sel: Option<&ADC> = None;
-
-    for (name, adc) in &db.adc {
-        let condition = || -> bool { adc.fs >= fs && adc.enob >= bits as Float };
-
-        if sel.is_none() && condition() {
-            (target, sel) = (name.clone(), Some(adc));
-        } else if sel.is_some() && condition() {
-            let dims = sel.unwrap().dims;
-            if adc.dims.area(mos) <= dims.area(mos) {
-                (target, sel) = (name.clone(), Some(adc))
+    let db = look.db;
+    let scan = || {
+        let mut target = String::new();
+        let mut sel: Option<&ADC> = None;
+
+        for (name, adc) in by_name(&db.adc) {
+            let condition = || -> bool { adc_fits(adc, fs, bits, op) };
+
+            if sel.is_none() && condition() {
+                (target, sel) = (name.clone(), Some(adc));
+            } else if sel.is_some() && condition() {
+                let dims = sel.unwrap().dims;
+                if adc.dims.area(mos) < dims.area(mos) {
+                    (target, sel) = (name.clone(), Some(adc))
+                }
             }
         }
-    }
 
-    match sel {
-        Some(x) => Ok((target, *x)),
+        sel.map(|_| target)
+    };
+    let key = (
+        fs.to_bits(),
+        bits,
+        op.vdd.map(Float::to_bits),
+        op.temp.map(Float::to_bits),
+        mos,
+    );
+    let found = match look.cache {
+        Some(cache) => cache.memo(&cache.adc, key, scan),
+        None => scan(),
+    };
+
+    match found.and_then(|name| db.adc.get(&name).map(|x| (name, x))) {
+        Some((target, x)) => Ok((target, x.clone())),
         None => Err(DBError::NoSuitableCells(format!(
-            "ADC with fs {fs} and {bits} bits"
+            "ADC with fs {fs} and {bits} bits ({})",
+            adc_shortfall(db, fs, bits, op)
         ))),
     }
 }
 
 fn locate_switch(
-    db: &Database,
+    look: Lookup,
     voltage: Float,
     dx: Float,
     mos: Mosaic,
+    pitch: Pitch,
 ) -> Result<(String, Switch), DBError> {
-    let mut target = String::new();
-    let mut sel: Option<&Switch> = None;
+    let db = look.db;
+    let scan = || {
+        let mut target = String::new();
+        let mut sel: Option<&Switch> = None;
 
-    for (name, switch) in &db.switch {
-        let condition = || -> bool {
-            switch.dx >= dx && voltage >= switch.voltage[0] && voltage <= switch.voltage[1]
-        };
+        for (name, switch) in by_name(&db.switch) {
+            let condition = || -> bool { switch_fits(switch, voltage, dx, look.v_tol) };
 
-        if sel.is_none() && condition() {
-            (target, sel) = (name.clone(), Some(switch));
-        } else if sel.is_some() && condition() {
-            let dims = sel.unwrap().dims;
-            if switch.dims.area(mos) <= dims.area(mos) {
-                (target, sel) = (name.clone(), Some(switch))
+            if sel.is_none() && condition() {
+                (target, sel) = (name.clone(), Some(switch));
+            } else if sel.is_some() && condition() {
+                let dims = sel.unwrap().dims;
+                if prefer(&switch.dims, &dims, mos, pitch) {
+                    (target, sel) = (name.clone(), Some(switch))
+                }
             }
         }
+
+        sel.map(|_| target)
+    };
+    let key = (
+        voltage.to_bits(),
+        dx.to_bits(),
+        look.v_tol.to_bits(),
+        mos,
+        pitch.key(),
+    );
+    let found = match look.cache {
+        Some(cache) => cache.memo(&cache.switch, key, scan),
+        None => scan(),
+    };
+
+    match found.and_then(|name| db.switch.get(&name).map(|x| (name, x))) {
+        Some((target, x)) => {
+            let what = format!("Switch for voltage {voltage} and dx {dx}");
+            check_pitch(what, &target, &x.dims, pitch)?;
+            Ok((target, x.clone()))
+        }
+        None => Err(DBError::NoSuitableCells(format!(
+            "Switch for voltage {voltage} and dx {dx} ({})",
+            switch_shortfall(db, voltage, dx, look.v_tol)
+        ))),
     }
+}
+
+fn locate_sense(
+    look: Lookup,
+    dx: Float,
+    fs: Float,
+    mos: Mosaic,
+    pitch: Pitch,
+) -> Result<(String, SenseAmp), DBError> {
+    let db = look.db;
+    let scan = || {
+        let mut target = String::new();
+        let mut sel: Option<&SenseAmp> = None;
+
+        for (name, sense) in by_name(&db.sense) {
+            let condition = || -> bool { sense_fits(sense, dx, fs) };
+
+            if sel.is_none() && condition() {
+                (target, sel) = (name.clone(), Some(sense));
+            } else if sel.is_some() && condition() {
+                let dims = sel.unwrap().dims;
+                if prefer(&sense.dims, &dims, mos, pitch) {
+                    (target, sel) = (name.clone(), Some(sense))
+                }
+            }
+        }
+
+        sel.map(|_| target)
+    };
+    let key = (dx.to_bits(), fs.to_bits(), mos, pitch.key());
+    let found = match look.cache {
+        Some(cache) => cache.memo(&cache.sense, key, scan),
+        None => scan(),
+    };
 
-    match sel {
-        Some(x) => Ok((target, *x)),
+    match found.and_then(|name| db.sense.get(&name).map(|x| (name, x))) {
+        Some((target, x)) => {
+            let what = format!("Sense amplifier with dx {dx} and fs {fs}");
+            check_pitch(what, &target, &x.dims, pitch)?;
+            Ok((target, x.clone()))
+        }
         None => Err(DBError::NoSuitableCells(format!(
-            "Switch for voltage {voltage} and dx {dx}"
+            "Sense amplifier with dx {dx} and fs {fs} ({})",
+            sense_shortfall(db, dx, fs)
         ))),
     }
 }
 
+fn select_logic(
+    look: Lookup,
+    pinned: &Option<String>,
+    dx: Float,
+    bits: usize,
+    mos: Mosaic,
+    pitch: Pitch,
+    force: bool,
+) -> Result<(String, Logic), DBError> {
+    match pinned {
+        Some(name) => {
+            let fits = look
+                .db
+                .logic
+                .get(name)
+                .is_some_and(|l| logic_fits(l, dx, bits));
+            let requirement = format!("dx {dx} and {bits} bits");
+            pin(
+                &look.db.logic,
+                name,
+                fits,
+                requirement,
+                pitch,
+                |c| &c.dims,
+                force,
+            )
+        }
+        None => locate_logic(look, dx, bits, mos, pitch),
+    }
+}
+
+fn select_adc(
+    look: Lookup,
+    pinned: &Option<String>,
+    fs: Float,
+    bits: usize,
+    op: OperatingPoint,
+    mos: Mosaic,
+    force: bool,
+) -> Result<(String, ADC), DBError> {
+    match pinned {
+        Some(name) => {
+            let fits = look
+                .db
+                .adc
+                .get(name)
+                .is_some_and(|a| adc_fits(a, fs, bits, op));
+            let requirement = format!("fs {fs} and {bits} bits");
+            pin(
+                &look.db.adc,
+                name,
+                fits,
+                requirement,
+                Pitch::NONE,
+                |c| &c.dims,
+                force,
+            )
+        }
+        None => locate_adc(look, fs, bits, op, mos),
+    }
+}
+
+fn select_sense(
+    look: Lookup,
+    pinned: &Option<String>,
+    dx: Float,
+    fs: Float,
+    mos: Mosaic,
+    pitch: Pitch,
+    force: bool,
+) -> Result<(String, SenseAmp), DBError> {
+    match pinned {
+        Some(name) => {
+            let fits = look
+                .db
+                .sense
+                .get(name)
+                .is_some_and(|s| sense_fits(s, dx, fs));
+            let requirement = format!("dx {dx} and fs {fs}");
+            pin(
+                &look.db.sense,
+                name,
+                fits,
+                requirement,
+                pitch,
+                |c| &c.dims,
+                force,
+            )
+        }
+        None => locate_sense(look, dx, fs, mos, pitch),
+    }
+}
+
+fn select_switch(
+    look: Lookup,
+    pinned: &Option<String>,
+    voltage: Float,
+    dx: Float,
+    mos: Mosaic,
+    pitch: Pitch,
+    force: bool,
+) -> Result<(String, Switch), DBError> {
+    match pinned {
+        Some(name) => {
+            let fits = look
+                .db
+                .switch
+                .get(name)
+                .is_some_and(|s| switch_fits(s, voltage, dx, look.v_tol));
+            let requirement = format!("voltage {voltage} and dx {dx}");
+            pin(
+                &look.db.switch,
+                name,
+                fits,
+                requirement,
+                pitch,
+                |c| &c.dims,
+                force,
+            )
+        }
+        None => locate_switch(look, voltage, dx, mos, pitch),
+    }
+}
+
 fn locate_core<'a>(
     config: &'a Config,
     db: &'a Database,
@@ -126,148 +900,514 @@ fn locate_core<'a>(
     Ok((name, cell))
 }
 
+/// Selects the smallest core cell whose drive requirements can be met.
+///
+/// A core cell qualifies if, for every wordline and bitline voltage in the
+/// configuration, some switch covers the voltage and provides the drive
+/// strength the array needs (`n * dx_wl` for wordlines, `m * dx_bl` for
+/// bitlines). Among qualifying cells, the one with the smallest array area
+/// wins, with ties going to the first name.
+///
+/// # Arguments
+/// * `config` - Configuration whose array size and voltages must be supported
+/// * `db` - Database to select from
+/// * `v_tol` - Slack in volts allowed beyond a switch's voltage range
+///
+/// # Returns
+/// * `Ok((name, core))` - Selected core cell
+/// * `Err(MemeaError)` - No core cell can be driven by the available switches
+///
+/// # Examples
+/// ```
+/// use memea::config::Config;
+/// use memea::db::synthetic_db;
+/// use memea::tabulate::{locate_core_auto, V_TOL};
+///
+/// let db = synthetic_db(4);
+/// let config: Config =
+///     serde_yaml::from_str("n: 4\nm: 2\ncell: missing\nwl: [0.8]").unwrap();
+/// let (name, _) = locate_core_auto(&config, &db, V_TOL).unwrap();
+/// assert_eq!(name, "core0");
+///
+/// let config: Config =
+///     serde_yaml::from_str("n: 4\nm: 2\ncell: missing\nwl: [100.0]").unwrap();
+/// assert!(locate_core_auto(&config, &db, V_TOL).is_err());
+/// ```
+pub fn locate_core_auto<'a>(
+    config: &Config,
+    db: &'a Database,
+    v_tol: Float,
+) -> Result<(&'a String, &'a Core), MemeaError> {
+    let drivable = |voltages: &Option<Vec<Float>>, dx: Float| {
+        voltages
+            .iter()
+            .flatten()
+            .all(|v| db.switch.values().any(|s| switch_fits(s, *v, dx, v_tol)))
+    };
+    let fits = |core: &Core| {
+        drivable(&config.wl, config.n as Float * core.dx_wl)
+            && drivable(&config.bl, config.m as Float * core.dx_bl)
+    };
+
+    let mos = Mosaic::array(config.n, config.m);
+    let mut sel: Option<(&String, &Core)> = None;
+    for (name, core) in by_name(&db.core) {
+        if !fits(core) {
+            continue;
+        }
+        match sel {
+            Some((_, best)) if core.dims.area(mos) >= best.dims.area(mos) => {}
+            _ => sel = Some((name, core)),
+        }
+    }
+
+    sel.ok_or_else(|| {
+        DBError::NoSuitableCells(format!(
+            "Core cell whose wordline and bitline drive can be met for a {}x{} array",
+            config.n, config.m
+        ))
+        .into()
+    })
+}
+
+/// Estimates the area of every component of one configuration.
+///
+/// The core array is reported first, followed by the wordline, bitline, sense
+/// amplifier, well and ADC peripherals that the configuration asks for. With
+/// `transpose` set, the core cell is rotated by 90° (see [`Dims::transposed`]).
+///
+/// # Arguments
+/// * `id` - Configuration name, used in messages
+/// * `config` - Configuration to estimate
+/// * `db` - Database to select cells from
+/// * `opts` - Run-wide settings
+/// * `cache` - Optional cache shared across configurations
+///
+/// # Returns
+/// * `Ok(Reports)` - One report per selected component, sorted by location
+///   (array, WL, BL, well), cell type and name
+/// * `Err(MemeaError)` - Invalid configuration or no suitable cell
+///
+/// # Examples
+/// ```
+/// use memea::config::Config;
+/// use memea::db::{synthetic_db, CellType, Dims};
+/// use memea::tabulate::{tabulate, Options};
+/// use memea::Mosaic;
+///
+/// let mut db = synthetic_db(12);
+/// let tall = Dims::from(1.0, 2.0, 0.5, 0.0);
+/// db.core.get_mut("core0").unwrap().dims = tall;
+///
+/// let core_area = |yaml: &str| {
+///     let config: Config = serde_yaml::from_str(yaml).unwrap();
+///     tabulate("doc", &config, &db, &Options::default(), None).unwrap()[0].area
+/// };
+///
+/// let mos = Mosaic::array(4, 2);
+/// assert_eq!(core_area("n: 4\nm: 2\ncell: core0"), tall.area(mos));
+/// assert_eq!(
+///     core_area("n: 4\nm: 2\ncell: core0\ntranspose: true"),
+///     tall.transposed().area(mos)
+/// );
+///
+/// let config: Config =
+///     serde_yaml::from_str("n: 4\nm: 2\ncell: core0\nbl: [0.8]\nwl: [0.8]").unwrap();
+/// let reports = tabulate("doc", &config, &db, &Options::default(), None).unwrap();
+/// let order: Vec<String> = reports
+///     .iter()
+///     .map(|r| format!("{} {}", r.loc, r.celltype))
+///     .collect();
+/// assert_eq!(order, ["Array Core", "WL Logic", "WL Switch", "BL Logic", "BL Switch"]);
+///
+/// let config: Config =
+///     serde_yaml::from_str("n: 4\nm: 2\ncell: core0\nsense_amps: 2\nfs: 1e6").unwrap();
+/// let reports = tabulate("doc", &config, &db, &Options::default(), None).unwrap();
+/// assert_eq!(reports[1].celltype, CellType::SenseAmp);
+/// assert_eq!((reports[1].loc.as_str(), reports[1].count), ("BL", 2));
+///
+/// // Two side-by-side banks share the array but each needs its own wordline
+/// // drivers
+/// let area_at = |yaml: &str, loc: &str| -> f32 {
+///     let config: Config = serde_yaml::from_str(yaml).unwrap();
+///     let reports = tabulate("doc", &config, &db, &Options::default(), None).unwrap();
+///     reports.iter().filter(|r| r.loc == loc).map(|r| r.area).sum()
+/// };
+/// let one = "n: 4\nm: 2\ncell: core0\nbl: [0.8]\nwl: [0.8]";
+/// let two = "n: 4\nm: 2\ncell: core0\nbl: [0.8]\nwl: [0.8]\nbanks: 2";
+/// assert_eq!(area_at(two, "Array"), area_at(one, "Array"));
+/// assert_eq!(area_at(two, "WL"), 2.0 * area_at(one, "WL"));
+///
+/// // Stacked banks each get their own sense amplifiers
+/// let config: Config = serde_yaml::from_str(
+///     "n: 4\nm: 2\ncell: core0\nsense_amps: 2\nfs: 1e6\nbanks: 2\nbank_split: rows",
+/// )
+/// .unwrap();
+/// let reports = tabulate("doc", &config, &db, &Options::default(), None).unwrap();
+/// assert_eq!((reports[1].celltype, reports[1].count), (CellType::SenseAmp, 4));
+/// ```
 pub fn tabulate(
     id: &str,
     config: &Config,
     db: &Database,
-    scale: Float,
+    opts: &Options,
+    cache: Option<&SelectionCache>,
 ) -> Result<Reports, MemeaError> {
+    tabulate_warned(id, config, db, opts, cache).map(|t| t.reports)
+}
+
+/// Estimates the area of every component of one configuration, keeping its
+/// warnings.
+///
+/// Works like [`tabulate`], but also returns the warnings about skipped
+/// peripheral sections and inconsistent peripheral counts, so they can be
+/// exported with the reports. They are still printed as they occur.
+///
+/// # Arguments
+/// * `id` - Configuration name, used in messages
+/// * `config` - Configuration to estimate
+/// * `db` - Database to select cells from
+/// * `opts` - Run-wide settings
+/// * `cache` - Optional cache shared across configurations
+///
+/// # Returns
+/// * `Ok(Tabulation)` - Reports and warnings of the configuration
+/// * `Err(MemeaError)` - Invalid configuration or no suitable cell
+///
+/// # Examples
+/// ```
+/// use memea::config::Config;
+/// use memea::db::synthetic_db;
+/// use memea::tabulate::{tabulate_warned, Options};
+///
+/// let db = synthetic_db(12);
+/// let config: Config = serde_yaml::from_str("n: 4\nm: 2\ncell: core0\nwl: [0.8]").unwrap();
+/// let t = tabulate_warned("doc", &config, &db, &Options::default(), None).unwrap();
+///
+/// assert_eq!(t.reports.len(), 3);
+/// assert!(t.warnings.iter().any(|w| w.starts_with("No 'bl' key supplied")));
+/// assert!(t.warnings.iter().any(|w| w.starts_with("Missing ADC config info")));
+/// ```
+pub fn tabulate_warned(
+    id: &str,
+    config: &Config,
+    db: &Database,
+    opts: &Options,
+    cache: Option<&SelectionCache>,
+) -> Result<Tabulation, MemeaError> {
+    let mut warnings: Vec<String> = Vec::new();
+    let mut warn = |msg: String| {
+        warnln!("{}", msg);
+        warnings.push(msg);
+    };
+
+    let look = Lookup {
+        db,
+        cache,
+        v_tol: opts.v_tol,
+    };
     let mut results: Reports = Vec::new();
+    let copies = config.copies.unwrap_or(1);
+    let force = opts.force;
+
+    config.validate()?;
+
+    // In strict mode, refuse to skip any peripheral section
+    if opts.require_complete || config.strict.unwrap_or(false) {
+        let missing = config.missing_sections();
+        if !missing.is_empty() {
+            return Err(ConfigError::MissingOption(missing.join(", ")).into());
+        }
+    }
 
     // Core area
-    let mos = (config.n, config.m);
-    let (name, core) = locate_core(config, db)?;
+    let (rows, cols) = config.bank()?;
+    let mos = Mosaic::array(config.n, config.m);
+    let (name, core) = match locate_core(config, db) {
+        Err(_) if opts.auto_core => {
+            let (name, core) = locate_core_auto(config, db, opts.v_tol)?;
+            infoln!(
+                "Core cell '{}' not found for config {}; selected {}",
+                config.cell,
+                id,
+                name
+            );
+            (name, core)
+        }
+        found => found?,
+    };
+    // A cell rotated by 90° swaps its width and height, and with them the
+    // row and column pitches peripherals should match
+    let (dims, wl_pitch, bl_pitch) = match config.transpose.unwrap_or(false) {
+        true => (core.dims.transposed(), core.bl_pitch, core.wl_pitch),
+        false => (core.dims, core.wl_pitch, core.bl_pitch),
+    };
     let report = Report {
         name: name.clone(),
         count: config.n * config.m,
         celltype: CellType::Core,
         loc: String::from("Array"),
-        area: core.dims.area(mos) * scale,
+        area: report_area(&dims, mos, opts),
+        extent: report_extent(&dims, mos, opts),
+        copies,
+        leakage: total_leakage(core.leakage, config.n * config.m),
+        dx_required: None,
+        dx_selected: None,
     };
     results.push(report);
 
     // WL peripheral area
-    let mos = (config.n, 1);
+    let mos = Mosaic::row(rows);
+    let pitch = Pitch {
+        value: wl_pitch,
+        axis: 1,
+        require: opts.pitch_match,
+    };
     if let Some(v) = &config.wl {
-        let dx = config.n as Float * core.dx_wl;
+        let dx = rows as Float * core.dx_wl;
 
         for voltage in v {
-            let (target, switch) = locate_switch(db, *voltage, dx, mos)?;
+            let (target, switch) =
+                select_switch(look, &config.wl_switch, *voltage, dx, mos, pitch, force)?;
             let report = Report {
                 name: target,
-                count: config.n,
+                count: rows,
                 celltype: CellType::Switch,
                 loc: String::from("WL"),
-                area: switch.dims.area(mos) * scale,
+                area: report_area(&switch.dims, mos, opts),
+                extent: report_extent(&switch.dims, mos, opts),
+                copies,
+                leakage: total_leakage(switch.leakage, rows),
+                dx_required: Some(dx),
+                dx_selected: Some(switch.dx),
             };
             results.push(report);
         }
 
-        let bits = (v.len() as Float).log2().ceil() as usize;
-        let (target, logic) = locate_logic(db, dx * LOGIC_SCALE, bits, mos)?;
+        let bits = decode_bits(v.len());
+        let (target, logic) = select_logic(
+            look,
+            &config.wl_logic,
+            dx * opts.logic_scale,
+            bits,
+            mos,
+            pitch,
+            force,
+        )?;
         let report = Report {
             name: target,
-            count: config.n,
+            count: rows,
             celltype: CellType::Logic,
             loc: String::from("WL"),
-            area: logic.dims.area(mos) * scale,
+            area: report_area(&logic.dims, mos, opts),
+            extent: report_extent(&logic.dims, mos, opts),
+            copies,
+            leakage: total_leakage(logic.leakage, rows),
+            dx_required: Some(dx * opts.logic_scale),
+            dx_selected: Some(logic.dx),
         };
         results.push(report);
     } else {
-        warnln!(
-            "No 'wl' key supplied, skipping wordline drivers for config {}",
-            id
-        )
+        warn(format!(
+            "No 'wl' key supplied, skipping wordline drivers for config {id}"
+        ))
     }
 
     // BL peripheral area
-    let mos = (1, config.m);
+    let mos = Mosaic::col(cols);
+    let pitch = Pitch {
+        value: bl_pitch,
+        axis: 0,
+        require: opts.pitch_match,
+    };
     if let Some(v) = &config.bl {
-        let dx = config.m as Float * core.dx_bl;
+        let dx = cols as Float * core.dx_bl;
 
         for voltage in v {
-            let (target, switch) = locate_switch(db, *voltage, dx, mos)?;
+            let (target, switch) =
+                select_switch(look, &config.bl_switch, *voltage, dx, mos, pitch, force)?;
             let report = Report {
                 name: target,
-                count: config.m,
+                count: cols,
                 celltype: CellType::Switch,
                 loc: String::from("BL"),
-                area: switch.dims.area(mos) * scale,
+                area: report_area(&switch.dims, mos, opts),
+                extent: report_extent(&switch.dims, mos, opts),
+                copies,
+                leakage: total_leakage(switch.leakage, cols),
+                dx_required: Some(dx),
+                dx_selected: Some(switch.dx),
             };
             results.push(report);
         }
 
-        let bits = (v.len() as Float).log2().ceil() as usize;
-        let (target, logic) = locate_logic(db, dx * LOGIC_SCALE, bits, mos)?;
+        let bits = decode_bits(v.len());
+        let (target, logic) = select_logic(
+            look,
+            &config.bl_logic,
+            dx * opts.logic_scale,
+            bits,
+            mos,
+            pitch,
+            force,
+        )?;
         let report = Report {
             name: target,
-            count: config.m,
+            count: cols,
             celltype: CellType::Logic,
             loc: String::from("BL"),
-            area: logic.dims.area(mos) * scale,
+            area: report_area(&logic.dims, mos, opts),
+            extent: report_extent(&logic.dims, mos, opts),
+            copies,
+            leakage: total_leakage(logic.leakage, cols),
+            dx_required: Some(dx * opts.logic_scale),
+            dx_selected: Some(logic.dx),
         };
         results.push(report);
     } else {
-        warnln!(
-            "No 'bl' key supplied, skipping bitline drivers for config {}",
-            id
-        )
+        warn(format!(
+            "No 'bl' key supplied, skipping bitline drivers for config {id}"
+        ))
     }
 
     // Well peripheral area
-    let mos = (1, config.m);
+    let mos = Mosaic::col(cols);
     if let Some(v) = &config.well {
-        let dx = config.n as Float * ((core.dx_bl + core.dx_wl) / 2.0) * WELL_SCALE;
+        let dx = rows as Float * ((core.dx_bl + core.dx_wl) / 2.0) * opts.well_scale;
 
         for voltage in v {
-            let (target, switch) = locate_switch(db, *voltage, dx, mos)?;
+            let (target, switch) =
+                select_switch(look, &config.well_switch, *voltage, dx, mos, pitch, force)?;
             let report = Report {
                 name: target,
-                count: config.m,
+                count: cols,
                 celltype: CellType::Switch,
                 loc: String::from("Well"),
-                area: switch.dims.area(mos) * scale,
+                area: report_area(&switch.dims, mos, opts),
+                extent: report_extent(&switch.dims, mos, opts),
+                copies,
+                leakage: total_leakage(switch.leakage, cols),
+                dx_required: Some(dx),
+                dx_selected: Some(switch.dx),
             };
             results.push(report);
         }
 
-        let bits = (v.len() as Float).log2().ceil() as usize;
-        let (target, logic) = locate_logic(db, dx * LOGIC_SCALE, bits, SINGLE)?;
+        let bits = decode_bits(v.len());
+        let (target, logic) = select_logic(
+            look,
+            &config.well_logic,
+            dx * opts.logic_scale,
+            bits,
+            Mosaic::SINGLE,
+            Pitch::NONE,
+            force,
+        )?;
         let report = Report {
             name: target,
             count: 1,
             celltype: CellType::Logic,
             loc: String::from("Well"),
-            area: logic.dims.area(SINGLE) * scale,
+            area: report_area(&logic.dims, Mosaic::SINGLE, opts),
+            extent: report_extent(&logic.dims, Mosaic::SINGLE, opts),
+            copies,
+            leakage: total_leakage(logic.leakage, 1),
+            dx_required: Some(dx * opts.logic_scale),
+            dx_selected: Some(logic.dx),
         };
         results.push(report);
     } else {
-        warnln!(
-            "No 'well' key supplied, skipping well drivers for config {}",
-            id
-        )
+        warn(format!(
+            "No 'well' key supplied, skipping well drivers for config {id}"
+        ))
+    }
+
+    // Sense amplifier area, on the bitline side of each bank
+    if let Some(count) = config.sense_amps {
+        let mos = Mosaic::col(count);
+        let dx = cols as Float * core.dx_bl;
+        let fs = config.fs.unwrap_or_default();
+
+        let (target, sense) = select_sense(look, &config.sense_amp, dx, fs, mos, pitch, force)?;
+        let report = Report {
+            name: target,
+            count,
+            celltype: CellType::SenseAmp,
+            loc: String::from("BL"),
+            area: report_area(&sense.dims, mos, opts),
+            extent: report_extent(&sense.dims, mos, opts),
+            copies,
+            leakage: total_leakage(sense.leakage, count),
+            dx_required: Some(dx),
+            dx_selected: Some(sense.dx),
+        };
+        results.push(report);
+    }
+
+    // Every bank repeats the peripherals along its split
+    let banks = config.banks.unwrap_or(1);
+    if banks != 1 {
+        let axis = match config.bank_split.unwrap_or_default() {
+            BankSplit::Rows => 1,
+            BankSplit::Cols => 0,
+        };
+        for r in results.iter_mut().skip(1) {
+            r.count *= banks;
+            r.area *= banks as Float;
+            r.leakage = r.leakage.map(|l| l * banks as Float);
+            r.extent[axis] *= banks as Float;
+        }
     }
 
     // ADC area
-    if let (Some(bits), Some(fs), Some(adcs)) = (config.bits, config.fs, config.adcs) {
-        let mos = (1, adcs);
+    if let (Some(bits), Some(fs), Some(adcs)) = (config.bits, config.fs, config.adc_count()?) {
+        let mos = Mosaic::col(adcs);
+
+        let op = OperatingPoint {
+            vdd: config.vdd,
+            temp: config.temp,
+        };
 
-        let (target, adc) = locate_adc(db, fs, bits, mos)?;
+        let (target, adc) = select_adc(look, &config.adc, fs, bits, op, mos, force)?;
         let report = Report {
             name: target,
             count: adcs,
             celltype: CellType::ADC,
             loc: String::from("BL"),
-            area: adc.dims.area(mos) * scale,
+            area: report_area(&adc.dims, mos, opts),
+            extent: report_extent(&adc.dims, mos, opts),
+            copies,
+            leakage: total_leakage(adc.leakage, adcs),
+            dx_required: None,
+            dx_selected: None,
         };
 
         results.push(report);
     } else {
-        warnln!(
-            "Missing ADC config info for {} (expecting 'bits', 'fs', and 'adcs'); ADCs will not be generated",
-            id
-        );
+        warn(format!(
+            "Missing ADC config info for {id} (expecting 'bits', 'fs', and 'adcs' or 'adc_per_cols'); ADCs will not be generated"
+        ));
+    }
+
+    warn_missing_leakage(db, &results, cache);
+    for msg in count_mismatches(config, &results) {
+        warn(format!(
+            "Inconsistent peripheral count in config {id}: {msg}"
+        ));
+    }
+
+    // Replicate the entire solution for redundant arrays
+    if copies != 1 {
+        for r in results.iter_mut() {
+            r.count *= copies;
+            r.area *= copies as Float;
+            r.leakage = r.leakage.map(|l| l * copies as Float);
+        }
     }
 
-    Ok(results)
+    sort_reports(&mut results);
+    Ok(Tabulation {
+        reports: results,
+        warnings,
+    })
 }